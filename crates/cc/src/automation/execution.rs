@@ -5,8 +5,10 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::services::{message_service, session_service};
+use crate::types::AgentOptions;
 use crate::{CCState, CCConnectParams};
 use codexia_codex::CodexAppServer;
+use codexia_codex::rate_limits;
 use codexia_db::automation_runs;
 use codexia_shared::event_sink::EventSink;
 
@@ -166,14 +168,17 @@ async fn run_task_with_cc(
         session_service::connect(
             CCConnectParams {
                 session_id: session_id.clone(),
-                cwd: target_dir,
-                model: if task.model.trim().is_empty() {
-                    None
-                } else {
-                    Some(task.model.clone())
-                },
-                permission_mode: Some("bypassPermissions".to_string()),
                 resume_id: None,
+                options: AgentOptions {
+                    cwd: target_dir,
+                    model: if task.model.trim().is_empty() {
+                        None
+                    } else {
+                        Some(task.model.clone())
+                    },
+                    permission_mode: Some("bypassPermissions".to_string()),
+                    ..Default::default()
+                },
             },
             &cc_state,
         )
@@ -252,6 +257,18 @@ pub(super) async fn execute_task(
         return;
     }
 
+    // Account rate limits only apply to codex's own provider.
+    if task.model_provider == "openai"
+        && let Some(reason) = rate_limits::automation_defer_reason()
+    {
+        log::info!("automation '{}' deferred because {}", task.id, reason);
+        event_sink.emit(
+            "automation:run/deferred",
+            json!({ "taskId": task.id, "reason": reason }),
+        );
+        return;
+    }
+
     let codex = {
         let guard = codex_ref.lock().await;
         guard.clone()
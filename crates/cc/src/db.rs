@@ -1,5 +1,8 @@
 use claude_agent_sdk_rs::types::sessions::SdkSessionInfo;
+use codexia_db::codex_search::fts_match_expression;
 use rusqlite::{Connection, Row, params};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 fn parse_session_info_row(row: &Row<'_>) -> Result<SdkSessionInfo, rusqlite::Error> {
     Ok(SdkSessionInfo {
@@ -16,6 +19,75 @@ fn parse_session_info_row(row: &Row<'_>) -> Result<SdkSessionInfo, rusqlite::Err
     })
 }
 
+/// Modification time and size of a transcript when it was last indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionFileState {
+    pub path: String,
+    pub session_id: String,
+    /// Unix milliseconds.
+    pub mtime: i64,
+    pub size: i64,
+}
+
+/// A searchable piece of a session: a message, a shell command, a file path,
+/// the title or a tag.
+#[derive(Debug, Clone)]
+pub struct SessionSearchSegment {
+    pub kind: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchQuery {
+    /// Free text; when empty, matching sessions are listed newest first.
+    #[serde(default)]
+    pub query: String,
+    /// Sessions of this project or one of its worktrees.
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub git_branch: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Lower bound (unix milliseconds) on the last modification time.
+    #[serde(default)]
+    pub from: Option<i64>,
+    /// Upper bound (unix milliseconds) on the last modification time.
+    #[serde(default)]
+    pub to: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct SessionSearchHit {
+    #[serde(flatten)]
+    pub session: SdkSessionInfo,
+    pub tags: Vec<String>,
+    /// Kind of the best matching segment; None without a text query.
+    pub kind: Option<String>,
+    pub snippet: Option<String>,
+}
+
+/// Claude Code keeps a single `tag` per session; Codexia stores several in it
+/// separated by commas.
+pub fn split_tags(tag: Option<&str>) -> Vec<String> {
+    tag.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn join_tags(tags: &[String]) -> String {
+    tags.join(", ")
+}
+
+/// Bump when the search index changes so every transcript is indexed again.
+const SEARCH_INDEX_VERSION: i64 = 1;
+
 pub struct SessionCache {
     conn: Connection,
 }
@@ -30,7 +102,12 @@ impl SessionCache {
         let db_path = db_dir.join("cache.db");
         let conn = Connection::open(db_path)
             .map_err(|e| format!("Failed to open session cache database: {}", e))?;
+        conn.busy_timeout(codexia_db::BUSY_TIMEOUT)
+            .map_err(|e| format!("Failed to set session cache busy timeout: {}", e))?;
+        Self::with_connection(conn)
+    }
 
+    fn with_connection(conn: Connection) -> Result<Self, String> {
         conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS cc_sessions (
                 session_id TEXT PRIMARY KEY,
@@ -46,24 +123,121 @@ impl SessionCache {
                 synced_at INTEGER NOT NULL
             );
             CREATE INDEX IF NOT EXISTS idx_cc_sessions_cwd_last_modified
-            ON cc_sessions(cwd, last_modified DESC);",
+            ON cc_sessions(cwd, last_modified DESC);
+            CREATE TABLE IF NOT EXISTS cc_session_files (
+                path TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                file_mtime INTEGER NOT NULL,
+                file_size INTEGER NOT NULL
+            );
+            CREATE VIRTUAL TABLE IF NOT EXISTS cc_session_search_fts USING fts5(
+                session_id UNINDEXED,
+                kind UNINDEXED,
+                body,
+                tokenize = 'unicode61'
+            );
+            CREATE TABLE IF NOT EXISTS cc_cache_meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );",
         )
         .map_err(|e| format!("Failed to initialize cc_sessions cache table: {}", e))?;
 
+        // Caches written by an older version have sessions that were never
+        // indexed. Forgetting the recorded file states makes the next sync
+        // re-read, and so re-index, every transcript. The version lives in a
+        // table of our own: `PRAGMA user_version` belongs to all of cache.db.
+        let version: i64 = conn
+            .query_row(
+                "SELECT COALESCE(MAX(CAST(value AS INTEGER)), 0) FROM cc_cache_meta
+                 WHERE key = 'search_index_version'",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to read session cache version: {}", e))?;
+        if version < SEARCH_INDEX_VERSION {
+            conn.execute_batch(&format!(
+                "BEGIN;
+                 DELETE FROM cc_session_files;
+                 INSERT OR REPLACE INTO cc_cache_meta (key, value)
+                 VALUES ('search_index_version', '{}');
+                 COMMIT;",
+                SEARCH_INDEX_VERSION
+            ))
+            .map_err(|e| format!("Failed to reset session search index: {}", e))?;
+        }
+
         Ok(Self { conn })
     }
 
-    pub fn replace_all(&mut self, sessions: &[SdkSessionInfo]) -> Result<(), String> {
+    /// Indexed transcripts: path -> (mtime, size).
+    pub fn file_states(&self) -> Result<HashMap<String, (i64, i64)>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path, file_mtime, file_size FROM cc_session_files")
+            .map_err(|e| format!("Failed to prepare cc_session_files query: {}", e))?;
+        stmt.query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))
+            .map_err(|e| format!("Failed to query cc_session_files: {}", e))?
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| format!("Failed to read cc_session_files rows: {}", e))
+    }
+
+    /// Apply one sync pass: record the new state of `changed` transcripts,
+    /// upsert their parsed `sessions` and forget `removed_paths`. A changed
+    /// transcript with no parsed session (e.g. still empty) drops its row.
+    /// Returns the ids of sessions no longer in the cache.
+    pub fn apply_file_changes(
+        &mut self,
+        changed: &[SessionFileState],
+        sessions: &[SdkSessionInfo],
+        removed_paths: &[String],
+    ) -> Result<Vec<String>, String> {
+        let parsed: HashSet<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+        let changed_ids: HashSet<&str> = changed.iter().map(|f| f.session_id.as_str()).collect();
+        let mut removed_ids: Vec<String> = Vec::new();
+
         let tx = self
             .conn
             .transaction()
-            .map_err(|e| format!("Failed to start cc_sessions cache transaction: {}", e))?;
+            .map_err(|e| format!("Failed to start cc_sessions sync transaction: {}", e))?;
 
-        tx.execute("DELETE FROM cc_sessions", [])
-            .map_err(|e| format!("Failed to clear cc_sessions cache: {}", e))?;
+        for path in removed_paths {
+            let session_id: Option<String> = tx
+                .query_row(
+                    "SELECT session_id FROM cc_session_files WHERE path = ?1",
+                    [path],
+                    |row| row.get(0),
+                )
+                .ok();
+            tx.execute("DELETE FROM cc_session_files WHERE path = ?1", [path])
+                .map_err(|e| format!("Failed to delete cc_session_files row: {}", e))?;
+            // A transcript moved to another project keeps its session.
+            if let Some(session_id) = session_id.filter(|id| !changed_ids.contains(id.as_str())) {
+                removed_ids.push(session_id);
+            }
+        }
 
         {
-            let mut stmt = tx
+            let mut file_stmt = tx
+                .prepare(
+                    "INSERT INTO cc_session_files (path, session_id, file_mtime, file_size)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT(path) DO UPDATE SET
+                        session_id = excluded.session_id,
+                        file_mtime = excluded.file_mtime,
+                        file_size = excluded.file_size",
+                )
+                .map_err(|e| format!("Failed to prepare cc_session_files upsert: {}", e))?;
+            for file in changed {
+                file_stmt
+                    .execute(params![file.path, file.session_id, file.mtime, file.size])
+                    .map_err(|e| format!("Failed to upsert cc_session_files row: {}", e))?;
+                if !parsed.contains(file.session_id.as_str()) {
+                    removed_ids.push(file.session_id.clone());
+                }
+            }
+
+            let mut session_stmt = tx
                 .prepare(
                     "INSERT INTO cc_sessions (
                         session_id,
@@ -77,29 +251,77 @@ impl SessionCache {
                         tag,
                         created_at,
                         synced_at
-                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, strftime('%s','now'))",
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, strftime('%s','now'))
+                    ON CONFLICT(session_id) DO UPDATE SET
+                        summary = excluded.summary,
+                        last_modified = excluded.last_modified,
+                        cwd = excluded.cwd,
+                        file_size = excluded.file_size,
+                        custom_title = excluded.custom_title,
+                        first_prompt = excluded.first_prompt,
+                        git_branch = excluded.git_branch,
+                        tag = excluded.tag,
+                        created_at = excluded.created_at,
+                        synced_at = excluded.synced_at",
                 )
-                .map_err(|e| format!("Failed to prepare cc_sessions cache insert: {}", e))?;
-
+                .map_err(|e| format!("Failed to prepare cc_sessions upsert: {}", e))?;
             for session in sessions {
-                stmt.execute(params![
-                    session.session_id,
-                    session.summary,
-                    session.last_modified,
-                    session.cwd,
-                    session.file_size.map(|size| size as i64),
-                    session.custom_title,
-                    session.first_prompt,
-                    session.git_branch,
-                    session.tag,
-                    session.created_at,
-                ])
-                .map_err(|e| format!("Failed to insert cc_sessions cache row: {}", e))?;
+                session_stmt
+                    .execute(params![
+                        session.session_id,
+                        session.summary,
+                        session.last_modified,
+                        session.cwd,
+                        session.file_size.map(|size| size as i64),
+                        session.custom_title,
+                        session.first_prompt,
+                        session.git_branch,
+                        session.tag,
+                        session.created_at,
+                    ])
+                    .map_err(|e| format!("Failed to upsert cc_sessions row: {}", e))?;
+            }
+
+            let mut delete_stmt = tx
+                .prepare("DELETE FROM cc_sessions WHERE session_id = ?1")
+                .map_err(|e| format!("Failed to prepare cc_sessions delete: {}", e))?;
+            let mut delete_search_stmt = tx
+                .prepare("DELETE FROM cc_session_search_fts WHERE session_id = ?1")
+                .map_err(|e| format!("Failed to prepare cc session search delete: {}", e))?;
+            for session_id in &removed_ids {
+                delete_stmt
+                    .execute([session_id])
+                    .map_err(|e| format!("Failed to delete cc_sessions row: {}", e))?;
+                delete_search_stmt
+                    .execute([session_id])
+                    .map_err(|e| format!("Failed to delete cc session search rows: {}", e))?;
             }
         }
 
         tx.commit()
-            .map_err(|e| format!("Failed to commit cc_sessions cache transaction: {}", e))
+            .map_err(|e| format!("Failed to commit cc_sessions sync transaction: {}", e))?;
+        Ok(removed_ids)
+    }
+
+    /// Drop cached sessions with no indexed transcript, e.g. rows left by a
+    /// cache written before transcripts were tracked. Returns their ids.
+    pub fn prune_untracked_sessions(&self) -> Result<Vec<String>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT session_id FROM cc_sessions
+                 WHERE session_id NOT IN (SELECT session_id FROM cc_session_files)",
+            )
+            .map_err(|e| format!("Failed to prepare untracked cc_sessions query: {}", e))?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Failed to query untracked cc_sessions: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read untracked cc_sessions rows: {}", e))?;
+        for session_id in &ids {
+            self.delete_session(session_id)?;
+        }
+        Ok(ids)
     }
 
     pub fn replace_project_sessions(
@@ -252,6 +474,277 @@ impl SessionCache {
         self.conn
             .execute("DELETE FROM cc_sessions WHERE session_id = ?1", [session_id])
             .map_err(|e| format!("Failed to delete cc_sessions cache row: {}", e))?;
+        self.conn
+            .execute("DELETE FROM cc_session_search_fts WHERE session_id = ?1", [session_id])
+            .map_err(|e| format!("Failed to delete cc session search rows: {}", e))?;
         Ok(())
     }
+
+    /// Replace the indexed contents of one session.
+    pub fn replace_search_segments(
+        &mut self,
+        session_id: &str,
+        segments: &[SessionSearchSegment],
+    ) -> Result<(), String> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Failed to start cc session search transaction: {}", e))?;
+        tx.execute("DELETE FROM cc_session_search_fts WHERE session_id = ?1", [session_id])
+            .map_err(|e| format!("Failed to clear cc session search rows: {}", e))?;
+        {
+            let mut stmt = tx
+                .prepare("INSERT INTO cc_session_search_fts (session_id, kind, body) VALUES (?1, ?2, ?3)")
+                .map_err(|e| format!("Failed to prepare cc session search insert: {}", e))?;
+            for segment in segments {
+                if segment.text.trim().is_empty() {
+                    continue;
+                }
+                stmt.execute(params![session_id, segment.kind, segment.text])
+                    .map_err(|e| format!("Failed to insert cc session search row: {}", e))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit cc session search transaction: {}", e))
+    }
+
+    /// Sessions matching the text and filters, best match first, with the best
+    /// matching segment of each. Without text, sessions are listed newest first.
+    pub fn search(&self, query: &SessionSearchQuery) -> Result<Vec<SessionSearchHit>, String> {
+        let limit = query.limit.unwrap_or(50).clamp(1, 500);
+        let match_expr = fts_match_expression(&query.query);
+
+        let mut filters: Vec<String> = Vec::new();
+        let mut params_list: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        if let Some(match_expr) = &match_expr {
+            params_list.push(Box::new(match_expr.clone()));
+        }
+        if let Some(cwd) = query.cwd.as_deref().map(|cwd| cwd.trim().trim_end_matches('/')).filter(|cwd| !cwd.is_empty()) {
+            filters.push("(s.cwd = ? OR s.cwd LIKE ?)".to_string());
+            params_list.push(Box::new(cwd.to_string()));
+            params_list.push(Box::new(format!("{}/.codexia/worktrees/%", cwd)));
+        }
+        if let Some(branch) = query.git_branch.as_deref().filter(|branch| !branch.is_empty()) {
+            filters.push("s.git_branch = ?".to_string());
+            params_list.push(Box::new(branch.to_string()));
+        }
+        if let Some(tag) = query.tag.as_deref().map(str::trim).filter(|tag| !tag.is_empty()) {
+            filters.push("instr(',' || REPLACE(COALESCE(s.tag, ''), ', ', ',') || ',', ?) > 0".to_string());
+            params_list.push(Box::new(format!(",{},", tag)));
+        }
+        if let Some(from) = query.from {
+            filters.push("s.last_modified >= ?".to_string());
+            params_list.push(Box::new(from));
+        }
+        if let Some(to) = query.to {
+            filters.push("s.last_modified <= ?".to_string());
+            params_list.push(Box::new(to));
+        }
+        params_list.push(Box::new(limit as i64));
+        let where_clause = if filters.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", filters.join(" AND "))
+        };
+
+        let sql = if match_expr.is_some() {
+            // As in the codex search, the materialized CTE keeps snippet() and
+            // bm25() on the MATCH query, and the bare columns come from the row
+            // holding MIN(rank) within each session.
+            format!(
+                "WITH matches AS MATERIALIZED (
+                    SELECT session_id, kind,
+                           snippet(cc_session_search_fts, 2, '[', ']', '…', 16) AS snippet,
+                           bm25(cc_session_search_fts) AS rank
+                    FROM cc_session_search_fts
+                    WHERE cc_session_search_fts MATCH ?
+                 )
+                 SELECT s.session_id, s.summary, s.last_modified, s.file_size, s.custom_title,
+                        s.first_prompt, s.git_branch, s.cwd, s.tag, s.created_at,
+                        m.kind, m.snippet, MIN(m.rank)
+                 FROM matches m
+                 JOIN cc_sessions s ON s.session_id = m.session_id
+                 {}
+                 GROUP BY s.session_id
+                 ORDER BY MIN(m.rank) ASC, s.last_modified DESC
+                 LIMIT ?",
+                where_clause
+            )
+        } else {
+            format!(
+                "SELECT s.session_id, s.summary, s.last_modified, s.file_size, s.custom_title,
+                        s.first_prompt, s.git_branch, s.cwd, s.tag, s.created_at,
+                        NULL, NULL
+                 FROM cc_sessions s
+                 {}
+                 ORDER BY s.last_modified DESC
+                 LIMIT ?",
+                where_clause
+            )
+        };
+
+        let mut stmt = self
+            .conn
+            .prepare(&sql)
+            .map_err(|e| format!("Failed to prepare cc session search query: {}", e))?;
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_list.iter().map(|b| b.as_ref()).collect();
+        stmt.query_map(params_refs.as_slice(), |row| {
+            let session = parse_session_info_row(row)?;
+            Ok(SessionSearchHit {
+                tags: split_tags(session.tag.as_deref()),
+                session,
+                kind: row.get(10)?,
+                snippet: row.get(11)?,
+            })
+        })
+        .map_err(|e| format!("Failed to run cc session search query: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read cc session search rows: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(session_id: &str, summary: &str) -> SdkSessionInfo {
+        SdkSessionInfo {
+            session_id: session_id.to_string(),
+            summary: summary.to_string(),
+            last_modified: 1,
+            file_size: Some(10),
+            custom_title: None,
+            first_prompt: None,
+            git_branch: None,
+            cwd: Some("/repo".to_string()),
+            tag: None,
+            created_at: None,
+        }
+    }
+
+    fn file(path: &str, session_id: &str, mtime: i64) -> SessionFileState {
+        SessionFileState { path: path.to_string(), session_id: session_id.to_string(), mtime, size: 10 }
+    }
+
+    #[test]
+    fn search_matches_text_and_filters() {
+        let conn = Connection::open_in_memory().expect("open in-memory database");
+        let mut cache = SessionCache::with_connection(conn).unwrap();
+        let mut tagged = session("a", "auth work");
+        tagged.tag = Some("bug, backend".to_string());
+        tagged.git_branch = Some("main".to_string());
+        let mut worktree = session("b", "docs");
+        worktree.cwd = Some("/repo/.codexia/worktrees/feature".to_string());
+        cache
+            .apply_file_changes(
+                &[file("/p/a.jsonl", "a", 1), file("/p/b.jsonl", "b", 1)],
+                &[tagged, worktree],
+                &[],
+            )
+            .unwrap();
+        let segment = |kind: &str, text: &str| SessionSearchSegment { kind: kind.to_string(), text: text.to_string() };
+        cache.replace_search_segments("a", &[segment("user", "fix the login token refresh")]).unwrap();
+        cache.replace_search_segments("b", &[segment("command", "npm run docs")]).unwrap();
+
+        let hits = cache
+            .search(&SessionSearchQuery { query: "login".to_string(), ..Default::default() })
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session.session_id, "a");
+        assert_eq!(hits[0].tags, ["bug", "backend"]);
+        assert!(hits[0].snippet.as_deref().unwrap().contains("[login]"));
+
+        let by_tag = cache
+            .search(&SessionSearchQuery { tag: Some("backend".to_string()), ..Default::default() })
+            .unwrap();
+        assert_eq!(by_tag.len(), 1);
+        assert!(by_tag[0].kind.is_none());
+
+        let in_repo = cache
+            .search(&SessionSearchQuery { cwd: Some("/repo/".to_string()), ..Default::default() })
+            .unwrap();
+        assert_eq!(in_repo.len(), 2);
+
+        let on_branch = cache
+            .search(&SessionSearchQuery {
+                query: "docs".to_string(),
+                git_branch: Some("main".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(on_branch.is_empty());
+    }
+
+    #[test]
+    fn file_changes_upsert_and_remove_sessions() {
+        let conn = Connection::open_in_memory().expect("open in-memory database");
+        let mut cache = SessionCache::with_connection(conn).unwrap();
+
+        cache
+            .apply_file_changes(
+                &[file("/p/a.jsonl", "a", 1), file("/p/b.jsonl", "b", 1)],
+                &[session("a", "first"), session("b", "other")],
+                &[],
+            )
+            .unwrap();
+        assert_eq!(cache.file_states().unwrap()["/p/a.jsonl"], (1, 10));
+
+        // `a` changed on disk and `b` was deleted.
+        let removed = cache
+            .apply_file_changes(&[file("/p/a.jsonl", "a", 2)], &[session("a", "second")], &["/p/b.jsonl".to_string()])
+            .unwrap();
+        assert_eq!(removed, ["b"]);
+        let (sessions, total) = cache.list_sessions(None, None, 0, true).unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(sessions[0].summary, "second");
+        assert_eq!(cache.file_states().unwrap().len(), 1);
+
+        // A transcript that no longer parses as a session drops its row.
+        let removed = cache.apply_file_changes(&[file("/p/a.jsonl", "a", 3)], &[], &[]).unwrap();
+        assert_eq!(removed, ["a"]);
+        assert_eq!(cache.list_sessions(None, None, 0, true).unwrap().1, 0);
+    }
+
+    #[test]
+    fn older_caches_reindex_every_transcript() {
+        let conn = Connection::open_in_memory().expect("open in-memory database");
+        let mut cache = SessionCache::with_connection(conn).unwrap();
+        cache
+            .apply_file_changes(&[file("/p/a.jsonl", "a", 1)], &[session("a", "first")], &[])
+            .unwrap();
+
+        // Reopening at the current version keeps the indexed file states.
+        let cache = SessionCache::with_connection(cache.conn).unwrap();
+        assert_eq!(cache.file_states().unwrap().len(), 1);
+
+        // A cache from before the search index forgets them, so the next sync
+        // re-reads the transcript; the session stays listed meanwhile.
+        cache.conn.execute("DELETE FROM cc_cache_meta", []).unwrap();
+        let cache = SessionCache::with_connection(cache.conn).unwrap();
+        assert!(cache.file_states().unwrap().is_empty());
+        assert_eq!(cache.list_sessions(None, None, 0, true).unwrap().1, 1);
+        let version: String = cache
+            .conn
+            .query_row(
+                "SELECT value FROM cc_cache_meta WHERE key = 'search_index_version'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(version, SEARCH_INDEX_VERSION.to_string());
+
+        // Other users of cache.db are free to use `PRAGMA user_version`.
+        let mut cache = cache;
+        cache
+            .apply_file_changes(&[file("/p/a.jsonl", "a", 2)], &[session("a", "first")], &[])
+            .unwrap();
+        cache.conn.pragma_update(None, "user_version", 7).unwrap();
+        let cache = SessionCache::with_connection(cache.conn).unwrap();
+        assert_eq!(cache.file_states().unwrap().len(), 1);
+        let user_version: i64 = cache
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(user_version, 7);
+    }
 }
@@ -1,10 +1,13 @@
 pub mod automation;
 pub mod db;
 pub mod mcp;
+pub mod mcp_catalog;
 pub mod mcp_unified;
+pub mod rewind;
 pub mod scan;
 pub mod services;
 pub mod state;
+pub mod transcript;
 pub mod types;
 
 pub use state::CCState;
@@ -1,8 +1,8 @@
+use codexia_shared::config_backup;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
 
 // ~/.claude.json {projects: { "working_dir": "mcpServers": {server}, disabledMcpServers:[server_name]}, mcpServers: {}, other_keys: {}}
 // {'sentry': {'type': 'http', 'url': 'https://mcp.sentry.dev/mcp'},
@@ -17,6 +17,9 @@ pub struct ClaudeCodeMcpServer {
     pub command: Option<String>,
     pub args: Option<Vec<String>>,
     pub env: Option<HashMap<String, String>>,
+    /// Request headers of http and sse servers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
     pub scope: String, // "global", "project", "local"
     pub enabled: bool,
 }
@@ -117,6 +120,56 @@ pub async fn cc_mcp_list(working_dir: String) -> Result<Vec<ClaudeCodeMcpServer>
     Ok(servers)
 }
 
+/// Servers configured in a single Claude scope, without the override of
+/// project by local and global by project that [`cc_mcp_list`] applies.
+/// `project` and `local` need a working directory.
+pub(crate) fn cc_mcp_list_scope(
+    scope: &str,
+    working_dir: Option<&str>,
+) -> Result<Vec<ClaudeCodeMcpServer>, String> {
+    let read_json = |path: &Path| -> Result<Option<serde_json::Value>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    };
+    let config = read_json(&get_cc_config_path(None)?)?.unwrap_or_default();
+    let project = working_dir.map(|dir| &config["projects"][dir]);
+    let disabled: HashSet<&str> = project
+        .and_then(|project| project["disabledMcpServers"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .collect();
+
+    let project_config;
+    let servers = match (scope, working_dir) {
+        ("global", _) => &config["mcpServers"],
+        ("project", Some(dir)) => {
+            project_config = read_json(&Path::new(dir).join(".mcp.json"))?.unwrap_or_default();
+            &project_config["mcpServers"]
+        }
+        ("local", Some(dir)) => &config["projects"][dir]["mcpServers"],
+        ("project" | "local", None) => {
+            return Err(format!("The {} scope requires a project path", scope));
+        }
+        _ => return Err(format!("Invalid scope: {}", scope)),
+    };
+
+    let mut result = Vec::new();
+    for (name, server_config) in servers.as_object().into_iter().flatten() {
+        let mut server = parse_server_config(name, server_config)?;
+        server.scope = scope.to_string();
+        server.enabled = !disabled.contains(name.as_str());
+        result.push(server);
+    }
+    Ok(result)
+}
+
 /// Get details for a specific MCP server
 pub async fn cc_mcp_get(name: String, working_dir: String) -> Result<ClaudeCodeMcpServer, String> {
     let servers = cc_mcp_list(working_dir).await?;
@@ -139,12 +192,6 @@ pub async fn cc_mcp_add(
     match scope.as_str() {
         "global" => {
             let config_path = get_cc_config_path(None)?;
-            let backup_path = if config_path.exists() {
-                Some(create_backup(&config_path)?)
-            } else {
-                None
-            };
-
             let mut config = if config_path.exists() {
                 let content = fs::read_to_string(&config_path)
                     .map_err(|e| format!("Failed to read config: {}", e))?;
@@ -159,23 +206,10 @@ pub async fn cc_mcp_add(
             }
             config["mcpServers"][&server_name] = server_json;
 
-            fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).map_err(
-                |e| {
-                    if let Some(bp) = backup_path {
-                        let _ = restore_backup(&config_path, &bp);
-                    }
-                    format!("Failed to write config: {}", e)
-                },
-            )?;
+            write_config(&config_path, &config)?;
         }
         "project" => {
             let config_path = Path::new(&working_dir).join(".mcp.json");
-            let backup_path = if config_path.exists() {
-                Some(create_backup(&config_path)?)
-            } else {
-                None
-            };
-
             let mut config = if config_path.exists() {
                 let content = fs::read_to_string(&config_path)
                     .map_err(|e| format!("Failed to read local config: {}", e))?;
@@ -190,23 +224,10 @@ pub async fn cc_mcp_add(
             }
             config["mcpServers"][&server_name] = server_json;
 
-            fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).map_err(
-                |e| {
-                    if let Some(bp) = backup_path {
-                        let _ = restore_backup(&config_path, &bp);
-                    }
-                    format!("Failed to write local config: {}", e)
-                },
-            )?;
+            write_config(&config_path, &config)?;
         }
         "local" | "" => {
             let config_path = get_cc_config_path(Some(working_dir.clone()))?;
-            let backup_path = if config_path.exists() {
-                Some(create_backup(&config_path)?)
-            } else {
-                None
-            };
-
             let mut config = if config_path.exists() {
                 let content = fs::read_to_string(&config_path)
                     .map_err(|e| format!("Failed to read config: {}", e))?;
@@ -228,14 +249,7 @@ pub async fn cc_mcp_add(
 
             config["projects"][&working_dir]["mcpServers"][&server_name] = server_json;
 
-            fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).map_err(
-                |e| {
-                    if let Some(bp) = backup_path {
-                        let _ = restore_backup(&config_path, &bp);
-                    }
-                    format!("Failed to write config: {}", e)
-                },
-            )?;
+            write_config(&config_path, &config)?;
         }
         _ => return Err(format!("Invalid scope: {}", scope)),
     }
@@ -261,8 +275,6 @@ pub async fn cc_mcp_remove(
             if !config_path.exists() {
                 return Err("Global config not found".to_string());
             }
-            let backup_path = create_backup(&config_path)?;
-
             let content = fs::read_to_string(&config_path)
                 .map_err(|e| format!("Failed to read config: {}", e))?;
             let mut config: serde_json::Value = serde_json::from_str(&content)
@@ -277,15 +289,8 @@ pub async fn cc_mcp_remove(
             };
 
             if removed {
-                fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).map_err(
-                    |e| {
-                        let _ = restore_backup(&config_path, &backup_path);
-                        format!("Failed to write config: {}", e)
-                    },
-                )?;
-                let _ = fs::remove_file(backup_path);
+                write_config(&config_path, &config)?;
             } else {
-                let _ = fs::remove_file(backup_path);
                 return Err(format!("Server '{}' not found in global scope", name));
             }
         }
@@ -294,8 +299,6 @@ pub async fn cc_mcp_remove(
             if !config_path.exists() {
                 return Err("Project .mcp.json not found".to_string());
             }
-            let backup_path = create_backup(&config_path)?;
-
             let content = fs::read_to_string(&config_path)
                 .map_err(|e| format!("Failed to read local config: {}", e))?;
             let mut config: serde_json::Value = serde_json::from_str(&content)
@@ -310,15 +313,8 @@ pub async fn cc_mcp_remove(
             };
 
             if removed {
-                fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).map_err(
-                    |e| {
-                        let _ = restore_backup(&config_path, &backup_path);
-                        format!("Failed to write local config: {}", e)
-                    },
-                )?;
-                let _ = fs::remove_file(backup_path);
+                write_config(&config_path, &config)?;
             } else {
-                let _ = fs::remove_file(backup_path);
                 return Err(format!("Server '{}' not found in project scope", name));
             }
         }
@@ -327,8 +323,6 @@ pub async fn cc_mcp_remove(
             if !config_path.exists() {
                 return Err("Claude config not found".to_string());
             }
-            let backup_path = create_backup(&config_path)?;
-
             let content = fs::read_to_string(&config_path)
                 .map_err(|e| format!("Failed to read config: {}", e))?;
             let mut config: serde_json::Value = serde_json::from_str(&content)
@@ -351,15 +345,8 @@ pub async fn cc_mcp_remove(
             };
 
             if removed {
-                fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).map_err(
-                    |e| {
-                        let _ = restore_backup(&config_path, &backup_path);
-                        format!("Failed to write config: {}", e)
-                    },
-                )?;
-                let _ = fs::remove_file(backup_path);
+                write_config(&config_path, &config)?;
             } else {
-                let _ = fs::remove_file(backup_path);
                 return Err(format!("Server '{}' not found in local scope", name));
             }
         }
@@ -414,8 +401,6 @@ fn update_disabled_server(
         return Err("Claude config file not found".to_string());
     }
 
-    let backup_path = create_backup(&cc_config_path)?;
-
     let config_content = fs::read_to_string(&cc_config_path)
         .map_err(|e| format!("Failed to read Claude config: {}", e))?;
 
@@ -447,16 +432,7 @@ fn update_disabled_server(
         }
     }
 
-    fs::write(
-        &cc_config_path,
-        serde_json::to_string_pretty(&config).unwrap(),
-    )
-    .map_err(|e| {
-        let _ = restore_backup(&cc_config_path, &backup_path);
-        format!("Failed to write config: {}", e)
-    })?;
-
-    let _ = fs::remove_file(backup_path);
+    write_config(&cc_config_path, &config)?;
 
     Ok(ClaudeCodeResponse {
         success: true,
@@ -513,11 +489,15 @@ fn parse_server_config(
             .collect()
     });
 
-    let env = config.get("env").and_then(|v| v.as_object()).map(|obj| {
-        obj.iter()
-            .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
-            .collect()
-    });
+    let string_map = |key: &str| {
+        config.get(key).and_then(|v| v.as_object()).map(|obj| {
+            obj.iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+    };
+    let env = string_map("env");
+    let headers = string_map("headers");
 
     Ok(ClaudeCodeMcpServer {
         name: name.to_string(),
@@ -526,6 +506,7 @@ fn parse_server_config(
         command,
         args,
         env,
+        headers,
         scope: "".to_string(),
         enabled: true,
     })
@@ -560,32 +541,23 @@ fn server_to_json(server: &ClaudeCodeMcpServer) -> Result<serde_json::Value, Str
         );
     }
 
-    Ok(json)
-}
-
-fn create_backup(config_path: &PathBuf) -> Result<PathBuf, String> {
-    if !config_path.exists() {
-        return Err("Config file does not exist".to_string());
+    if let Some(headers) = &server.headers {
+        json["headers"] = serde_json::Value::Object(
+            headers
+                .iter()
+                .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+                .collect(),
+        );
     }
 
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-
-    let backup_path = config_path.with_extension(format!("json.backup.{}", timestamp));
-
-    fs::copy(config_path, &backup_path).map_err(|e| format!("Failed to create backup: {}", e))?;
-
-    Ok(backup_path)
+    Ok(json)
 }
 
-fn restore_backup(config_path: &PathBuf, backup_path: &PathBuf) -> Result<(), String> {
-    if !backup_path.exists() {
-        return Err("Backup file does not exist".to_string());
-    }
-
-    fs::copy(backup_path, config_path).map_err(|e| format!("Failed to restore backup: {}", e))?;
-
+/// Write a Claude JSON config, keeping the previous content in the shared backup history.
+/// `write_with_backup` puts the previous content back if the write itself fails.
+fn write_config(config_path: &Path, config: &serde_json::Value) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    config_backup::write_with_backup(config_path, &content)?;
     Ok(())
 }
@@ -0,0 +1,647 @@
+//! Canonical Codexia MCP catalog, kept in `~/.codexia/mcp_catalog.json`.
+//!
+//! The catalog holds one definition per server. [`catalog_status`] compares it
+//! with every agent store (codex `config.toml`, and Claude's global, project
+//! and local scopes) and [`sync_catalog`] reconciles one store with it, either
+//! pulling the store's definitions into the catalog or pushing the catalog's
+//! definitions into the store.
+
+use codexia_codex as codex;
+use codexia_codex::config::mcp::{McpHttpAuth, McpServerConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::mcp::{ClaudeCodeMcpServer, cc_mcp_add, cc_mcp_list_scope, cc_mcp_remove};
+
+const CATALOG_FILE: &str = "mcp_catalog.json";
+
+fn default_type() -> String {
+    "stdio".to_string()
+}
+
+/// A server definition in the catalog, in the shape Claude stores it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatalogServer {
+    #[serde(default = "default_type")]
+    pub r#type: String, // "stdio", "http", "sse"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// A server as a store defines it.
+#[derive(Debug, Clone, PartialEq)]
+struct StoreEntry {
+    server: CatalogServer,
+    enabled: bool,
+    /// Store settings the catalog cannot hold, lost on a pull.
+    unmodeled: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct McpCatalog {
+    #[serde(default)]
+    pub servers: BTreeMap<String, CatalogServer>,
+}
+
+/// An agent store that MCP servers are synced with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum McpTarget {
+    /// `mcp_servers` in codex `config.toml`.
+    Codex,
+    /// Top-level `mcpServers` in `~/.claude.json`.
+    CcGlobal,
+    /// `$project/.mcp.json`.
+    CcProject,
+    /// `projects[$project].mcpServers` in `~/.claude.json`.
+    CcLocal,
+}
+
+impl McpTarget {
+    fn cc_scope(self) -> Option<&'static str> {
+        match self {
+            McpTarget::Codex => None,
+            McpTarget::CcGlobal => Some("global"),
+            McpTarget::CcProject => Some("project"),
+            McpTarget::CcLocal => Some("local"),
+        }
+    }
+
+    /// Targets that can be read with or without a project path.
+    fn available(path: Option<&str>) -> Vec<McpTarget> {
+        let mut targets = vec![McpTarget::Codex, McpTarget::CcGlobal];
+        if path.is_some() {
+            targets.extend([McpTarget::CcProject, McpTarget::CcLocal]);
+        }
+        targets
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DriftStatus {
+    InSync,
+    /// Both define the server, with different fields.
+    Differs,
+    /// Only the catalog defines the server.
+    Missing,
+    /// Only the target defines the server.
+    Untracked,
+    Absent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldDiff {
+    /// `type`, `url`, `command`, `args`, or `env.<KEY>` / `headers.<KEY>`.
+    pub field: String,
+    pub catalog: Value,
+    pub target: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpTargetState {
+    pub target: McpTarget,
+    pub status: DriftStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<CatalogServer>,
+    pub differences: Vec<FieldDiff>,
+    /// Catalog fields this target cannot store, and target settings the
+    /// catalog cannot store.
+    pub unsupported: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerDrift {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub catalog: Option<CatalogServer>,
+    pub targets: Vec<McpTargetState>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpCatalogStatus {
+    pub targets: Vec<McpTarget>,
+    pub servers: Vec<McpServerDrift>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncDirection {
+    /// Copy the target's definitions into the catalog.
+    Pull,
+    /// Write the catalog's definitions into the target.
+    Push,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSyncRequest {
+    pub direction: SyncDirection,
+    pub target: McpTarget,
+    /// Project path, required for the Claude project and local scopes.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Servers to reconcile; all of them when omitted.
+    #[serde(default)]
+    pub servers: Option<Vec<String>>,
+    /// Also remove servers the source does not define.
+    #[serde(default)]
+    pub prune: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedServer {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct McpSyncResult {
+    pub written: Vec<String>,
+    pub removed: Vec<String>,
+    pub skipped: Vec<SkippedServer>,
+}
+
+fn catalog_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".codexia").join(CATALOG_FILE))
+}
+
+pub fn read_catalog() -> Result<McpCatalog, String> {
+    let path = catalog_path()?;
+    if !path.exists() {
+        return Ok(McpCatalog::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Write the catalog owner-only, since env and headers may hold credentials.
+pub fn write_catalog(catalog: &McpCatalog) -> Result<(), String> {
+    let path = catalog_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(catalog)
+        .map_err(|e| format!("Failed to serialize MCP catalog: {}", e))?;
+    codexia_shared::config_backup::write_private(&path, content.as_bytes())
+}
+
+impl From<&ClaudeCodeMcpServer> for CatalogServer {
+    fn from(server: &ClaudeCodeMcpServer) -> Self {
+        let to_map = |map: &Option<HashMap<String, String>>| -> BTreeMap<String, String> {
+            map.iter().flatten().map(|(k, v)| (k.clone(), v.clone())).collect()
+        };
+        CatalogServer {
+            r#type: server.r#type.clone(),
+            url: server.url.clone(),
+            command: server.command.clone(),
+            args: server.args.clone().unwrap_or_default(),
+            env: to_map(&server.env),
+            headers: to_map(&server.headers),
+        }
+    }
+}
+
+/// The Claude form of a catalog server, keeping the `enabled` state the
+/// scope already has for it.
+fn to_cc(name: &str, server: &CatalogServer, scope: &str, enabled: bool) -> ClaudeCodeMcpServer {
+    let to_map = |map: &BTreeMap<String, String>| -> Option<HashMap<String, String>> {
+        (!map.is_empty()).then(|| map.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+    };
+    ClaudeCodeMcpServer {
+        name: name.to_string(),
+        r#type: server.r#type.clone(),
+        url: server.url.clone(),
+        command: server.command.clone(),
+        args: (!server.args.is_empty()).then(|| server.args.clone()),
+        env: to_map(&server.env),
+        headers: to_map(&server.headers),
+        scope: scope.to_string(),
+        enabled,
+    }
+}
+
+/// The catalog form of a codex server, whether it is enabled, and the
+/// env-sourced auth the catalog cannot hold.
+fn from_codex(config: &McpServerConfig) -> StoreEntry {
+    let remote = |r#type: &str, url: &str, auth: &McpHttpAuth| CatalogServer {
+        r#type: r#type.to_string(),
+        url: Some(url.to_string()),
+        command: None,
+        args: Vec::new(),
+        env: BTreeMap::new(),
+        headers: auth.http_headers.iter().flatten().map(|(k, v)| (k.clone(), v.clone())).collect(),
+    };
+    let unmodeled = |auth: &McpHttpAuth| {
+        let mut fields: Vec<String> = auth
+            .env_http_headers
+            .iter()
+            .flatten()
+            .map(|(header, _)| format!("env_http_headers.{}", header))
+            .collect();
+        fields.sort();
+        if auth.bearer_token_env_var.is_some() {
+            fields.insert(0, "bearer_token_env_var".to_string());
+        }
+        fields
+    };
+    match config {
+        McpServerConfig::Stdio { command, args, env, enabled, .. } => StoreEntry {
+            server: CatalogServer {
+                r#type: "stdio".to_string(),
+                url: None,
+                command: Some(command.clone()),
+                args: args.clone(),
+                env: env.iter().flatten().map(|(k, v)| (k.clone(), v.clone())).collect(),
+                headers: BTreeMap::new(),
+            },
+            enabled: *enabled,
+            unmodeled: Vec::new(),
+        },
+        McpServerConfig::Http { url, auth, enabled, .. } => StoreEntry {
+            server: remote("http", url, auth),
+            enabled: *enabled,
+            unmodeled: unmodeled(auth),
+        },
+        McpServerConfig::Sse { url, auth, enabled, .. } => StoreEntry {
+            server: remote("sse", url, auth),
+            enabled: *enabled,
+            unmodeled: unmodeled(auth),
+        },
+    }
+}
+
+/// Fields of `server` that codex's `McpServerConfig` cannot hold.
+fn codex_unsupported(server: &CatalogServer) -> Vec<String> {
+    let mut unsupported = Vec::new();
+    match server.r#type.as_str() {
+        "stdio" => {
+            if server.command.is_none() {
+                unsupported.push("command".to_string());
+            }
+        }
+        "http" | "sse" => {
+            if server.url.is_none() {
+                unsupported.push("url".to_string());
+            }
+            if !server.args.is_empty() {
+                unsupported.push("args".to_string());
+            }
+            if !server.env.is_empty() {
+                unsupported.push("env".to_string());
+            }
+        }
+        _ => unsupported.push("type".to_string()),
+    }
+    if server.r#type == "stdio" && !server.headers.is_empty() {
+        unsupported.push("headers".to_string());
+    }
+    unsupported
+}
+
+/// The codex form of a catalog server. Settings the catalog does not model
+/// (cwd, timeouts, env-sourced headers, bearer token) and the enabled flag
+/// are carried over from the `current` codex entry.
+fn to_codex(
+    server: &CatalogServer,
+    current: Option<&McpServerConfig>,
+) -> Result<McpServerConfig, Vec<String>> {
+    let unsupported = codex_unsupported(server);
+    if !unsupported.is_empty() {
+        return Err(unsupported);
+    }
+    let (enabled, current_cwd, current_auth) = match current {
+        Some(McpServerConfig::Stdio { enabled, cwd, .. }) => (*enabled, cwd.clone(), None),
+        Some(McpServerConfig::Http { enabled, auth, .. })
+        | Some(McpServerConfig::Sse { enabled, auth, .. }) => {
+            (*enabled, None, Some(auth.clone()))
+        }
+        None => (true, None, None),
+    };
+    let timeouts = current.map(|config| config.timeouts().clone()).unwrap_or_default();
+    let to_map = |map: &BTreeMap<String, String>| -> Option<HashMap<String, String>> {
+        (!map.is_empty()).then(|| map.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+    };
+    let url = server.url.clone().unwrap_or_default();
+    let auth = McpHttpAuth {
+        http_headers: to_map(&server.headers),
+        ..current_auth.unwrap_or_default()
+    };
+    Ok(match server.r#type.as_str() {
+        "http" => McpServerConfig::Http { url, auth, timeouts, enabled },
+        "sse" => McpServerConfig::Sse { url, auth, timeouts, enabled },
+        _ => McpServerConfig::Stdio {
+            command: server.command.clone().unwrap_or_default(),
+            args: server.args.clone(),
+            env: to_map(&server.env),
+            cwd: current_cwd,
+            timeouts,
+            enabled,
+        },
+    })
+}
+
+fn diff_servers(catalog: &CatalogServer, target: &CatalogServer) -> Vec<FieldDiff> {
+    let mut diffs = Vec::new();
+    let mut check = |field: String, left: Value, right: Value| {
+        if left != right {
+            diffs.push(FieldDiff { field, catalog: left, target: right });
+        }
+    };
+    check("type".to_string(), json!(catalog.r#type), json!(target.r#type));
+    check("url".to_string(), json!(catalog.url), json!(target.url));
+    check("command".to_string(), json!(catalog.command), json!(target.command));
+    check("args".to_string(), json!(catalog.args), json!(target.args));
+    for (field, catalog_map, target_map) in [
+        ("env", &catalog.env, &target.env),
+        ("headers", &catalog.headers, &target.headers),
+    ] {
+        let keys: BTreeSet<&String> = catalog_map.keys().chain(target_map.keys()).collect();
+        for key in keys {
+            check(
+                format!("{}.{}", field, key),
+                json!(catalog_map.get(key)),
+                json!(target_map.get(key)),
+            );
+        }
+    }
+    diffs
+}
+
+fn compare(target: McpTarget, catalog: Option<&CatalogServer>, entry: Option<&StoreEntry>) -> McpTargetState {
+    let mut unsupported = match (target, catalog) {
+        (McpTarget::Codex, Some(server)) => codex_unsupported(server),
+        _ => Vec::new(),
+    };
+    unsupported.extend(entry.iter().flat_map(|entry| entry.unmodeled.iter().cloned()));
+    let (status, differences) = match (catalog, entry) {
+        (Some(server), Some(current)) => {
+            let differences = diff_servers(server, &current.server);
+            let status = if differences.is_empty() { DriftStatus::InSync } else { DriftStatus::Differs };
+            (status, differences)
+        }
+        (Some(_), None) => (DriftStatus::Missing, Vec::new()),
+        (None, Some(_)) => (DriftStatus::Untracked, Vec::new()),
+        (None, None) => (DriftStatus::Absent, Vec::new()),
+    };
+    McpTargetState {
+        target,
+        status,
+        enabled: entry.map(|entry| entry.enabled),
+        config: entry.map(|entry| entry.server.clone()),
+        differences,
+        unsupported,
+    }
+}
+
+fn require_path(target: McpTarget, path: Option<&str>) -> Result<(), String> {
+    if matches!(target, McpTarget::CcProject | McpTarget::CcLocal) && path.is_none() {
+        return Err("Claude project and local scopes require a project path".to_string());
+    }
+    Ok(())
+}
+
+/// Servers defined in `target`.
+async fn read_target(target: McpTarget, path: Option<&str>) -> Result<BTreeMap<String, StoreEntry>, String> {
+    require_path(target, path)?;
+    match target.cc_scope() {
+        None => Ok(codex::read_mcp_servers()
+            .await?
+            .iter()
+            .map(|(name, config)| (name.clone(), from_codex(config)))
+            .collect()),
+        Some(scope) => Ok(cc_mcp_list_scope(scope, path)?
+            .iter()
+            .map(|server| {
+                let entry = StoreEntry {
+                    server: CatalogServer::from(server),
+                    enabled: server.enabled,
+                    unmodeled: Vec::new(),
+                };
+                (server.name.clone(), entry)
+            })
+            .collect()),
+    }
+}
+
+/// Compare the catalog with every store; the Claude project and local scopes
+/// are only included when `path` is given.
+pub async fn catalog_status(path: Option<&str>) -> Result<McpCatalogStatus, String> {
+    let catalog = read_catalog()?;
+    let targets = McpTarget::available(path);
+    let mut stores = Vec::new();
+    for target in &targets {
+        stores.push(read_target(*target, path).await?);
+    }
+
+    let names: BTreeSet<&String> = catalog
+        .servers
+        .keys()
+        .chain(stores.iter().flat_map(|store| store.keys()))
+        .collect();
+    let servers = names
+        .into_iter()
+        .map(|name| {
+            let server = catalog.servers.get(name);
+            McpServerDrift {
+                name: name.clone(),
+                catalog: server.cloned(),
+                targets: targets
+                    .iter()
+                    .zip(&stores)
+                    .map(|(target, store)| compare(*target, server, store.get(name)))
+                    .collect(),
+            }
+        })
+        .collect();
+    Ok(McpCatalogStatus { targets, servers })
+}
+
+/// Reconcile one store with the catalog in the requested direction.
+pub async fn sync_catalog(request: McpSyncRequest) -> Result<McpSyncResult, String> {
+    let path = request.path.as_deref();
+    let mut catalog = read_catalog()?;
+    let store = read_target(request.target, path).await?;
+    let names: BTreeSet<String> = match &request.servers {
+        Some(servers) => servers.iter().cloned().collect(),
+        None => catalog.servers.keys().chain(store.keys()).cloned().collect(),
+    };
+
+    let mut result = McpSyncResult::default();
+    match request.direction {
+        SyncDirection::Pull => {
+            for name in names {
+                match store.get(&name) {
+                    Some(entry) if catalog.servers.get(&name) != Some(&entry.server) => {
+                        catalog.servers.insert(name.clone(), entry.server.clone());
+                        result.written.push(name);
+                    }
+                    Some(_) => {}
+                    None => {
+                        if request.prune && catalog.servers.remove(&name).is_some() {
+                            result.removed.push(name);
+                        }
+                    }
+                }
+            }
+            if !result.written.is_empty() || !result.removed.is_empty() {
+                write_catalog(&catalog)?;
+            }
+        }
+        SyncDirection::Push => {
+            let working_dir = path.unwrap_or_default().to_string();
+            let codex_current = match request.target.cc_scope() {
+                None => codex::read_mcp_servers().await?,
+                Some(_) => HashMap::new(),
+            };
+            for name in names {
+                let current = store.get(&name);
+                match (catalog.servers.get(&name), request.target.cc_scope()) {
+                    (Some(server), _) if current.is_some_and(|current| &current.server == server) => {}
+                    (Some(server), None) => {
+                        match to_codex(server, codex_current.get(&name)) {
+                            Ok(config) => {
+                                codex::add_mcp_server(name.clone(), config).await?;
+                                result.written.push(name);
+                            }
+                            Err(unsupported) => result.skipped.push(SkippedServer {
+                                reason: format!("codex cannot store {}", unsupported.join(", ")),
+                                name,
+                            }),
+                        }
+                    }
+                    (Some(server), Some(scope)) => {
+                        let enabled = current.is_none_or(|current| current.enabled);
+                        cc_mcp_add(to_cc(&name, server, scope, enabled), working_dir.clone()).await?;
+                        result.written.push(name);
+                    }
+                    (None, _) if !request.prune || current.is_none() => {}
+                    (None, None) => {
+                        codex::delete_mcp_server(name.clone()).await?;
+                        result.removed.push(name);
+                    }
+                    (None, Some(scope)) => {
+                        cc_mcp_remove(name.clone(), working_dir.clone(), scope.to_string()).await?;
+                        result.removed.push(name);
+                    }
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codexia_codex::config::mcp::McpTimeouts;
+
+    fn entry(server: CatalogServer, enabled: bool) -> StoreEntry {
+        StoreEntry { server, enabled, unmodeled: Vec::new() }
+    }
+
+    fn stdio(command: &str, env: &[(&str, &str)]) -> CatalogServer {
+        CatalogServer {
+            r#type: "stdio".to_string(),
+            url: None,
+            command: Some(command.to_string()),
+            args: vec!["-y".to_string()],
+            env: env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            headers: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn env_differences_are_reported_per_key() {
+        let catalog = stdio("npx", &[("TOKEN", "a"), ("MODE", "x")]);
+        let target = stdio("npx", &[("TOKEN", "b")]);
+        let fields: Vec<String> = diff_servers(&catalog, &target).into_iter().map(|d| d.field).collect();
+        assert_eq!(fields, ["env.MODE", "env.TOKEN"]);
+
+        let state = compare(McpTarget::CcLocal, Some(&catalog), Some(&entry(target, true)));
+        assert_eq!(state.status, DriftStatus::Differs);
+        let state = compare(McpTarget::Codex, None, Some(&entry(catalog, false)));
+        assert_eq!((state.status, state.enabled), (DriftStatus::Untracked, Some(false)));
+    }
+
+    #[test]
+    fn codex_round_trip_keeps_headers_and_current_settings() {
+        let mut server = stdio("npx", &[("TOKEN", "a")]);
+        assert_eq!(from_codex(&to_codex(&server, None).unwrap()), entry(server.clone(), true));
+
+        server.r#type = "http".to_string();
+        server.command = None;
+        server.url = Some("https://example.com/mcp".to_string());
+        server.headers.insert("Authorization".to_string(), "Bearer x".to_string());
+        assert_eq!(to_codex(&server, None).unwrap_err(), ["args", "env"]);
+        let state = compare(McpTarget::Codex, Some(&server), None);
+        assert_eq!(state.unsupported, ["args", "env"]);
+
+        server.args.clear();
+        server.env.clear();
+        let current = McpServerConfig::Http {
+            url: "https://old.example.com/mcp".to_string(),
+            auth: McpHttpAuth {
+                bearer_token_env_var: Some("MCP_TOKEN".to_string()),
+                ..Default::default()
+            },
+            timeouts: McpTimeouts { startup_timeout_sec: Some(20.0), tool_timeout_sec: None },
+            enabled: false,
+        };
+        let pushed = to_codex(&server, Some(&current)).unwrap();
+        let McpServerConfig::Http { auth, timeouts, enabled, .. } = &pushed else {
+            panic!("expected an http server");
+        };
+        assert_eq!(auth.bearer_token_env_var.as_deref(), Some("MCP_TOKEN"));
+        assert_eq!(timeouts.startup_timeout_sec, Some(20.0));
+        assert!(!enabled);
+        let pulled = from_codex(&pushed);
+        assert_eq!((&pulled.server, pulled.enabled), (&server, false));
+        assert_eq!(pulled.unmodeled, ["bearer_token_env_var"]);
+    }
+
+    #[test]
+    fn codex_auth_the_catalog_cannot_hold_is_reported() {
+        let mut env_http_headers = HashMap::new();
+        env_http_headers.insert("X-Api-Key".to_string(), "MCP_KEY".to_string());
+        let config = McpServerConfig::Http {
+            url: "https://example.com/mcp".to_string(),
+            auth: McpHttpAuth {
+                env_http_headers: Some(env_http_headers),
+                bearer_token_env_var: Some("MCP_TOKEN".to_string()),
+                ..Default::default()
+            },
+            timeouts: McpTimeouts::default(),
+            enabled: true,
+        };
+        let codex = from_codex(&config);
+        let state = compare(McpTarget::Codex, Some(&codex.server), Some(&codex));
+        assert_eq!(state.status, DriftStatus::InSync);
+        assert_eq!(state.unsupported, ["bearer_token_env_var", "env_http_headers.X-Api-Key"]);
+    }
+
+    #[test]
+    fn claude_push_keeps_the_enabled_state() {
+        let server = stdio("npx", &[]);
+        assert!(!to_cc("fs", &server, "local", false).enabled);
+        assert_eq!(CatalogServer::from(&to_cc("fs", &server, "local", true)), server);
+    }
+}
@@ -54,6 +54,13 @@ pub async fn unified_add_mcp_server(
                             .collect()
                     })
                 }),
+                headers: server_config.get("headers").and_then(|v| {
+                    v.as_object().map(|obj| {
+                        obj.iter()
+                            .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                            .collect()
+                    })
+                }),
                 scope: scope.unwrap_or_else(|| "local".to_string()),
                 enabled: true,
             };
@@ -152,6 +159,9 @@ pub async fn unified_read_mcp_config(
                 if let Some(env) = server.env {
                     server_json["env"] = serde_json::to_value(env).unwrap();
                 }
+                if let Some(headers) = server.headers {
+                    server_json["headers"] = serde_json::to_value(headers).unwrap();
+                }
 
                 servers_map.insert(server.name, server_json);
             }
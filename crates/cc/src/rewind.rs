@@ -0,0 +1,580 @@
+//! Fork and rewind of Claude Code session transcripts at a message.
+//!
+//! Every transcript record carries a `uuid` and the `parentUuid` it follows.
+//! A fork copies the chain of records leading to the chosen message into a new
+//! session. A rewind truncates the transcript after that message, keeping a
+//! backup under `~/.codexia/cc_rewind_backups`, and can undo the file edits
+//! the removed part recorded (Edit, MultiEdit, Write and NotebookEdit calls)
+//! inside the session's cwd. Shell commands are not reverted.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::transcript::{find_session_file, validate_session_id};
+
+const BACKUP_DIR: &str = "cc_rewind_backups";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkedSession {
+    pub session_id: String,
+    pub forked_from: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewindResult {
+    pub session_id: String,
+    /// Transcript records removed after the message.
+    pub removed_records: usize,
+    /// Holds the original transcript and every file the rewind changed.
+    pub backup_dir: String,
+    pub reverted_files: Vec<String>,
+    /// Files left as they are because their recorded edits no longer apply.
+    pub conflicts: Vec<String>,
+    /// Edited files outside the session's cwd, never reverted.
+    pub skipped: Vec<String>,
+}
+
+/// A file the rewind changed, and the copy of its previous content when it
+/// existed, so a failed rewind can put it back.
+#[derive(Debug)]
+struct RevertedFile {
+    path: String,
+    backup: Option<PathBuf>,
+}
+
+/// Copy `session_id` up to and including `message_uuid` into a new session in
+/// the same project. Only the message's own chain of records is copied, so
+/// branches left by earlier rewinds are not carried over.
+pub fn fork_session(session_id: &str, message_uuid: &str) -> Result<ForkedSession, String> {
+    let path = find_session_file(session_id)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read session {}: {}", path.display(), e))?;
+    let new_session_id = uuid::Uuid::new_v4().to_string();
+    let forked = fork_records(&content, message_uuid, &new_session_id)?;
+
+    let new_path = path.with_file_name(format!("{}.jsonl", new_session_id));
+    fs::write(&new_path, forked)
+        .map_err(|e| format!("Failed to write {}: {}", new_path.display(), e))?;
+    if let Err(e) = crate::scan::refresh_session_file(&new_path) {
+        log::warn!("Failed to refresh session cache after fork: {}", e);
+    }
+    Ok(ForkedSession {
+        session_id: new_session_id,
+        forked_from: session_id.to_string(),
+        path: new_path.to_string_lossy().to_string(),
+    })
+}
+
+fn parse_records(content: &str) -> Vec<(&str, Option<Value>)> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| (line, serde_json::from_str::<Value>(line).ok()))
+        .collect()
+}
+
+fn record_uuid(record: &Value) -> Option<&str> {
+    record.get("uuid").and_then(Value::as_str)
+}
+
+fn fork_records(content: &str, message_uuid: &str, new_session_id: &str) -> Result<String, String> {
+    let records = parse_records(content);
+    let by_uuid: HashMap<&str, &Value> = records
+        .iter()
+        .filter_map(|(_, record)| record.as_ref())
+        .filter_map(|record| Some((record_uuid(record)?, record)))
+        .collect();
+    if !by_uuid.contains_key(message_uuid) {
+        return Err(format!("Message '{}' not found in session", message_uuid));
+    }
+
+    let mut chain: HashSet<&str> = HashSet::new();
+    let mut next = Some(message_uuid);
+    while let Some(uuid) = next {
+        if !chain.insert(uuid) {
+            break;
+        }
+        next = by_uuid
+            .get(uuid)
+            .and_then(|record| record.get("parentUuid"))
+            .and_then(Value::as_str);
+    }
+
+    let mut output = String::new();
+    for (_, record) in &records {
+        let Some(record) = record else { continue };
+        if !record_uuid(record).is_some_and(|uuid| chain.contains(uuid)) {
+            continue;
+        }
+        let mut record = record.clone();
+        if let Some(object) = record.as_object_mut()
+            && object.contains_key("sessionId")
+        {
+            object.insert("sessionId".to_string(), json!(new_session_id));
+        }
+        output.push_str(&record.to_string());
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Truncate `session_id` after `message_uuid`. With `revert_files`, the file
+/// edits recorded after the message are undone, newest first. When reverting
+/// or truncating fails, the files already reverted are put back.
+pub fn rewind_session(session_id: &str, message_uuid: &str, revert_files: bool) -> Result<RewindResult, String> {
+    let path = find_session_file(session_id)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read session {}: {}", path.display(), e))?;
+    let (kept, removed) = split_after_message(&content, message_uuid)?;
+
+    let backup_dir = backup_dir(session_id)?;
+    fs::create_dir_all(backup_dir.join("files"))
+        .map_err(|e| format!("Failed to create {}: {}", backup_dir.display(), e))?;
+    fs::write(backup_dir.join(format!("{}.jsonl", session_id)), &content)
+        .map_err(|e| format!("Failed to back up session {}: {}", session_id, e))?;
+
+    let mut result = RewindResult {
+        session_id: session_id.to_string(),
+        removed_records: removed.len(),
+        backup_dir: backup_dir.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let reverted = if revert_files {
+        let removed_records: Vec<Value> = removed
+            .iter()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        let cwd = parse_records(&content)
+            .into_iter()
+            .find_map(|(_, record)| record?.get("cwd")?.as_str().map(ToOwned::to_owned))
+            .unwrap_or_default();
+        revert_file_edits(&recorded_edits(&removed_records), &cwd, &backup_dir, &mut result)?
+    } else {
+        Vec::new()
+    };
+
+    let mut output = String::new();
+    for line in &kept {
+        output.push_str(line);
+        output.push('\n');
+    }
+    if let Err(e) = fs::write(&path, output) {
+        restore_files(&reverted);
+        return Err(format!("Failed to write {}: {}", path.display(), e));
+    }
+    if let Err(e) = crate::scan::refresh_session_file(&path) {
+        log::warn!("Failed to refresh session cache after rewind: {}", e);
+    }
+    Ok(result)
+}
+
+fn backup_dir(session_id: &str) -> Result<PathBuf, String> {
+    validate_session_id(session_id)?;
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%3fZ");
+    Ok(home
+        .join(".codexia")
+        .join(BACKUP_DIR)
+        .join(format!("{}-{}", session_id, stamp)))
+}
+
+/// Records that describe the session rather than the conversation, such as
+/// its title and tags. They survive a rewind wherever they appear.
+const METADATA_TYPES: &[&str] = &["custom-title", "tag", "summary"];
+
+/// Lines up to and including the message plus the later metadata records
+/// (those of a metadata type or without a `uuid`), and the other lines after it.
+fn split_after_message<'a>(content: &'a str, message_uuid: &str) -> Result<(Vec<&'a str>, Vec<&'a str>), String> {
+    let lines: Vec<(&str, Option<Value>)> = parse_records(content);
+    let index = lines
+        .iter()
+        .position(|(_, record)| record.as_ref().and_then(record_uuid) == Some(message_uuid))
+        .ok_or_else(|| format!("Message '{}' not found in session", message_uuid))?;
+    let mut kept: Vec<&str> = lines[..=index].iter().map(|(line, _)| *line).collect();
+    let mut removed = Vec::new();
+    for (line, record) in &lines[index + 1..] {
+        let is_metadata = record.as_ref().is_none_or(|record| {
+            record_uuid(record).is_none()
+                || record["type"].as_str().is_some_and(|kind| METADATA_TYPES.contains(&kind))
+        });
+        if is_metadata {
+            kept.push(line);
+        } else {
+            removed.push(*line);
+        }
+    }
+    Ok((kept, removed))
+}
+
+/// How to undo one recorded edit.
+#[derive(Debug, Clone, PartialEq)]
+enum Undo {
+    /// Put back the content the file had before the edit.
+    Restore(String),
+    /// The edit created the file.
+    Delete,
+    /// Swap the inserted text back for the replaced one.
+    Replace { inserted: String, replaced: String, all: bool },
+    /// The previous content was not recorded.
+    Unrecoverable,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RecordedEdit {
+    path: String,
+    /// Applied in order to undo the edit.
+    undo: Vec<Undo>,
+}
+
+/// Successful file edits of `records`, oldest first.
+fn recorded_edits(records: &[Value]) -> Vec<RecordedEdit> {
+    // tool_use_id -> the tool's `toolUseResult`, for calls that succeeded.
+    let mut results: HashMap<String, Value> = HashMap::new();
+    for record in records.iter().filter(|record| record["type"] == "user") {
+        for block in record["message"]["content"].as_array().into_iter().flatten() {
+            if block["type"] == "tool_result"
+                && block["is_error"].as_bool() != Some(true)
+                && let Some(id) = block["tool_use_id"].as_str()
+            {
+                results.insert(id.to_string(), record.get("toolUseResult").cloned().unwrap_or(Value::Null));
+            }
+        }
+    }
+
+    let mut edits = Vec::new();
+    for record in records.iter().filter(|record| record["type"] == "assistant") {
+        for block in record["message"]["content"].as_array().into_iter().flatten() {
+            if block["type"] != "tool_use" {
+                continue;
+            }
+            let Some(result) = block["id"].as_str().and_then(|id| results.get(id)) else {
+                continue;
+            };
+            if let Some(edit) = recorded_edit(block["name"].as_str().unwrap_or_default(), &block["input"], result) {
+                edits.push(edit);
+            }
+        }
+    }
+    edits
+}
+
+fn recorded_edit(tool: &str, input: &Value, result: &Value) -> Option<RecordedEdit> {
+    let path = input["file_path"]
+        .as_str()
+        .or_else(|| input["notebook_path"].as_str())?
+        .to_string();
+    let replace = |edit: &Value| Undo::Replace {
+        inserted: edit["new_string"].as_str().unwrap_or_default().to_string(),
+        replaced: edit["old_string"].as_str().unwrap_or_default().to_string(),
+        all: edit["replace_all"].as_bool().unwrap_or(false),
+    };
+    let undo = match (tool, result["type"].as_str(), result["originalFile"].as_str()) {
+        ("Edit" | "MultiEdit" | "Write" | "NotebookEdit", _, Some(original)) => vec![Undo::Restore(original.to_string())],
+        ("Write", Some("create"), None) => vec![Undo::Delete],
+        ("Edit", _, None) => vec![replace(input)],
+        ("MultiEdit", _, None) => input["edits"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .rev()
+            .map(replace)
+            .collect(),
+        ("Write" | "NotebookEdit", _, None) => vec![Undo::Unrecoverable],
+        _ => return None,
+    };
+    Some(RecordedEdit { path, undo })
+}
+
+/// Undo `edit` on the file content (`None` when the file does not exist).
+fn undo_edit(content: Option<String>, undo: &Undo) -> Result<Option<String>, String> {
+    match undo {
+        Undo::Restore(original) => Ok(Some(original.clone())),
+        Undo::Delete => Ok(None),
+        Undo::Replace { inserted, replaced, all } => {
+            let content = content.ok_or("file no longer exists")?;
+            if inserted.is_empty() || !content.contains(inserted.as_str()) {
+                return Err("edited text is no longer in the file".to_string());
+            }
+            Ok(Some(if *all {
+                content.replace(inserted.as_str(), replaced)
+            } else {
+                content.replacen(inserted.as_str(), replaced, 1)
+            }))
+        }
+        Undo::Unrecoverable => Err("previous content was not recorded".to_string()),
+    }
+}
+
+/// Whether `path` is an absolute path inside `cwd`, without `..` components.
+fn is_within(path: &str, cwd: &str) -> bool {
+    let path = Path::new(path);
+    !cwd.is_empty()
+        && path.is_absolute()
+        && !path.components().any(|component| component == Component::ParentDir)
+        && path.starts_with(cwd)
+}
+
+/// Undo `edits` newest first and write the files that fully reverted, after
+/// copying their current content into the backup. Files outside `cwd` are
+/// reported as skipped. If a write fails, the files already written are put
+/// back before the error is returned.
+fn revert_file_edits(
+    edits: &[RecordedEdit],
+    cwd: &str,
+    backup_dir: &Path,
+    result: &mut RewindResult,
+) -> Result<Vec<RevertedFile>, String> {
+    let mut contents: HashMap<&str, Option<String>> = HashMap::new();
+    let mut conflicted: HashSet<&str> = HashSet::new();
+    for edit in edits.iter().rev() {
+        let path = edit.path.as_str();
+        if conflicted.contains(path) {
+            continue;
+        }
+        if !is_within(path, cwd) {
+            if !result.skipped.iter().any(|skipped| skipped == path) {
+                result.skipped.push(path.to_string());
+            }
+            continue;
+        }
+        let mut content = contents
+            .remove(path)
+            .unwrap_or_else(|| fs::read_to_string(path).ok());
+        for undo in &edit.undo {
+            match undo_edit(content.clone(), undo) {
+                Ok(next) => content = next,
+                Err(reason) => {
+                    result.conflicts.push(format!("{}: {}", path, reason));
+                    conflicted.insert(path);
+                    break;
+                }
+            }
+        }
+        contents.insert(path, content);
+    }
+    contents.retain(|path, _| !conflicted.contains(path));
+
+    let mut reverted = Vec::new();
+    if let Err(e) = write_reverted(&contents, backup_dir, &mut reverted) {
+        restore_files(&reverted);
+        return Err(e);
+    }
+    result.reverted_files = reverted.iter().map(|file| file.path.clone()).collect();
+    Ok(reverted)
+}
+
+/// Write the undone `contents`, recording each file in `reverted` before it
+/// is touched, then the manifest of backups.
+fn write_reverted(
+    contents: &HashMap<&str, Option<String>>,
+    backup_dir: &Path,
+    reverted: &mut Vec<RevertedFile>,
+) -> Result<(), String> {
+    let mut manifest = Vec::new();
+    let mut paths: Vec<&str> = contents.keys().copied().collect();
+    paths.sort();
+    for (index, path) in paths.into_iter().enumerate() {
+        let target = Path::new(path);
+        let backup = if target.exists() {
+            let name = target.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            let backup = backup_dir.join("files").join(format!("{}-{}", index, name));
+            fs::copy(target, &backup).map_err(|e| format!("Failed to back up {}: {}", path, e))?;
+            Some(backup)
+        } else {
+            None
+        };
+        manifest.push(json!({ "path": path, "backup": backup.as_ref().map(|backup| backup.to_string_lossy()) }));
+        reverted.push(RevertedFile { path: path.to_string(), backup });
+        let written = match &contents[path] {
+            Some(content) => fs::write(target, content),
+            None if target.exists() => fs::remove_file(target),
+            None => Ok(()),
+        };
+        written.map_err(|e| format!("Failed to revert {}: {}", path, e))?;
+    }
+    let manifest = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize rewind manifest: {}", e))?;
+    fs::write(backup_dir.join("files.json"), manifest)
+        .map_err(|e| format!("Failed to write rewind manifest: {}", e))
+}
+
+/// Put back files changed by a failed rewind: copy back their backups and
+/// delete the ones that did not exist before.
+fn restore_files(reverted: &[RevertedFile]) {
+    for file in reverted {
+        let restored = match &file.backup {
+            Some(backup) => fs::copy(backup, &file.path).map(|_| ()),
+            None if Path::new(&file.path).exists() => fs::remove_file(&file.path),
+            None => Ok(()),
+        };
+        if let Err(e) = restored {
+            log::warn!("Failed to restore {} after a failed rewind: {}", file.path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(uuid: &str, parent: Option<&str>, text: &str) -> String {
+        json!({
+            "type": "user",
+            "uuid": uuid,
+            "parentUuid": parent,
+            "sessionId": "old",
+            "message": { "role": "user", "content": text },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn fork_keeps_only_the_message_chain() {
+        // b2 is a branch left by an earlier rewind to a.
+        let content = [
+            record("a", None, "one"),
+            record("b1", Some("a"), "two"),
+            record("b2", Some("a"), "two again"),
+            record("c", Some("b2"), "three"),
+        ]
+        .join("\n");
+        let forked = fork_records(&content, "c", "new").unwrap();
+        let uuids: Vec<String> = forked
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .inspect(|record| assert_eq!(record["sessionId"], "new"))
+            .map(|record| record["uuid"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(uuids, ["a", "b2", "c"]);
+        assert!(fork_records(&content, "missing", "new").is_err());
+    }
+
+    #[test]
+    fn split_keeps_the_message_itself() {
+        let content = [record("a", None, "one"), record("b", Some("a"), "two")].join("\n");
+        let (kept, removed) = split_after_message(&content, "a").unwrap();
+        assert_eq!((kept.len(), removed.len()), (1, 1));
+    }
+
+    #[test]
+    fn split_keeps_the_title_and_tags_set_later() {
+        let title = json!({ "type": "custom-title", "customTitle": "Renamed", "sessionId": "old" }).to_string();
+        let tag = json!({ "type": "tag", "tag": "bug", "sessionId": "old" }).to_string();
+        let snapshot = json!({ "type": "file-history-snapshot", "messageId": "b" }).to_string();
+        let content = [
+            record("a", None, "one"),
+            record("b", Some("a"), "two"),
+            title.clone(),
+            record("c", Some("b"), "three"),
+            tag.clone(),
+            snapshot.clone(),
+        ]
+        .join("\n");
+        let (kept, removed) = split_after_message(&content, "a").unwrap();
+        assert_eq!(kept, [record("a", None, "one"), title, tag, snapshot]);
+        assert_eq!(removed.len(), 2);
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("codexia-{}-{}-{}", name, std::process::id(), nanos));
+        fs::create_dir_all(dir.join("backup").join("files")).unwrap();
+        dir
+    }
+
+    fn restore(path: &Path, original: &str) -> RecordedEdit {
+        RecordedEdit {
+            path: path.to_string_lossy().to_string(),
+            undo: vec![Undo::Restore(original.to_string())],
+        }
+    }
+
+    #[test]
+    fn revert_skips_files_outside_the_cwd() {
+        let dir = temp_dir("rewind-cwd");
+        let project = dir.join("project");
+        fs::create_dir_all(&project).unwrap();
+        let inside = project.join("a.txt");
+        let outside = dir.join("outside.txt");
+        fs::write(&inside, "edited").unwrap();
+        fs::write(&outside, "edited").unwrap();
+        let sneaky = format!("{}/../outside.txt", project.display());
+        let edits = [
+            restore(&inside, "original"),
+            restore(&outside, "original"),
+            RecordedEdit { path: sneaky.clone(), undo: vec![Undo::Delete] },
+        ];
+
+        let mut result = RewindResult::default();
+        revert_file_edits(&edits, &project.to_string_lossy(), &dir.join("backup"), &mut result).unwrap();
+        assert_eq!(fs::read_to_string(&inside).unwrap(), "original");
+        assert_eq!(fs::read_to_string(&outside).unwrap(), "edited");
+        assert_eq!(result.reverted_files, [inside.to_string_lossy().to_string()]);
+        assert_eq!(result.skipped, [sneaky, outside.to_string_lossy().to_string()]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn failed_revert_puts_back_the_files_already_reverted() {
+        let dir = temp_dir("rewind-rollback");
+        let first = dir.join("a.txt");
+        let created = dir.join("b.txt");
+        // A directory where a file is expected makes the last write fail.
+        let blocked = dir.join("c");
+        fs::write(&first, "edited").unwrap();
+        fs::create_dir_all(&blocked).unwrap();
+        let edits = [
+            restore(&first, "original"),
+            restore(&created, "recreated"),
+            restore(&blocked, "original"),
+        ];
+
+        let mut result = RewindResult::default();
+        let err = revert_file_edits(&edits, &dir.to_string_lossy(), &dir.join("backup"), &mut result).unwrap_err();
+        assert!(err.contains("c"), "{}", err);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "edited");
+        assert!(!created.exists());
+        assert!(result.reverted_files.is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn edits_undo_from_original_or_by_swapping_text() {
+        let records = vec![
+            json!({ "type": "assistant", "message": { "content": [
+                { "type": "tool_use", "id": "t1", "name": "Edit",
+                  "input": { "file_path": "/a.rs", "old_string": "foo", "new_string": "bar" } },
+                { "type": "tool_use", "id": "t2", "name": "Write",
+                  "input": { "file_path": "/b.rs", "content": "new" } },
+                { "type": "tool_use", "id": "t3", "name": "Edit",
+                  "input": { "file_path": "/c.rs", "old_string": "x", "new_string": "y" } },
+            ] } }),
+            json!({ "type": "user", "toolUseResult": { "filePath": "/a.rs" }, "message": { "content": [
+                { "type": "tool_result", "tool_use_id": "t1", "content": "ok" },
+            ] } }),
+            json!({ "type": "user", "toolUseResult": { "type": "create" }, "message": { "content": [
+                { "type": "tool_result", "tool_use_id": "t2", "content": "ok" },
+            ] } }),
+            json!({ "type": "user", "message": { "content": [
+                { "type": "tool_result", "tool_use_id": "t3", "is_error": true, "content": "denied" },
+            ] } }),
+        ];
+        let edits = recorded_edits(&records);
+        assert_eq!(edits.len(), 2);
+        assert_eq!(edits[1].undo, [Undo::Delete]);
+
+        let undone = undo_edit(Some("let bar = bar;".to_string()), &edits[0].undo[0]).unwrap();
+        assert_eq!(undone.as_deref(), Some("let foo = bar;"));
+        assert!(undo_edit(Some("nothing".to_string()), &edits[0].undo[0]).is_err());
+    }
+}
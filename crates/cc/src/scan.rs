@@ -1,22 +1,201 @@
-use std::sync::Once;
-use std::time::Instant;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Once, OnceLock};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 
-use super::db::SessionCache;
+use claude_agent_sdk_rs::types::sessions::SdkSessionInfo;
+use codexia_codex::rollout::RolloutEvent;
+use codexia_shared::event_sink::EventSink;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::{Value, json};
+
+use super::db::{SessionCache, SessionFileState, SessionSearchSegment, split_tags};
+
+type EventSinks = Arc<Mutex<Vec<Arc<dyn EventSink>>>>;
 
 static SESSION_SCANNER_START: Once = Once::new();
+static SESSION_SCANNER_SINKS: OnceLock<EventSinks> = OnceLock::new();
+/// Set once the watcher covers `~/.claude/projects` and the cache has been
+/// reconciled; listings then read straight from SQLite.
+static PROJECTS_WATCHED: AtomicBool = AtomicBool::new(false);
+/// How often to retry watching `~/.claude/projects` until it exists.
+const WATCH_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+/// How long a batch of transcripts the cache failed to index waits before it
+/// is retried, together with whatever changed since.
+const INDEX_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+/// First prompts longer than this are cut when used as a summary.
+const MAX_PROMPT_CHARS: usize = 200;
+
+pub const SESSIONS_UPDATED_EVENT: &str = "cc:sessions/updated";
+
+fn projects_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".claude").join("projects"))
+}
+
+/// Watch `~/.claude/projects` and keep the `cc_sessions` cache in step with it,
+/// emitting `cc:sessions/updated` for every batch of changes.
+pub fn start_session_scanner(event_sink: Arc<dyn EventSink>) {
+    let sinks = SESSION_SCANNER_SINKS
+        .get_or_init(|| Arc::new(Mutex::new(Vec::new())))
+        .clone();
+    if let Ok(mut guarded) = sinks.lock() {
+        guarded.push(Arc::clone(&event_sink));
+    }
 
-pub fn start_session_scanner() {
     SESSION_SCANNER_START.call_once(|| {
-        std::thread::spawn(|| {
-            sync_session_cache();
+        let root = match projects_dir() {
+            Ok(root) => root,
+            Err(err) => {
+                log::error!("[cc session scanner] {}", err);
+                return;
+            }
+        };
+
+        std::thread::spawn(move || {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let mut watcher: RecommendedWatcher = match notify::recommended_watcher(tx) {
+                Ok(watcher) => watcher,
+                Err(err) => {
+                    log::error!("[cc session scanner] watcher init failed: {}", err);
+                    sync_session_cache();
+                    return;
+                }
+            };
+
+            // `~/.claude/projects` only appears once Claude Code first runs, so
+            // keep trying to watch it; listings reconcile on read until then.
+            let mut watched = false;
+            let mut synced = false;
+            let mut last_watch_attempt: Option<Instant> = None;
+            let mut last_scan = Instant::now() - Duration::from_secs(60);
+            let mut pending_paths: HashSet<PathBuf> = HashSet::new();
+            let mut index_failed_at: Option<Instant> = None;
+            loop {
+                if !watched
+                    && last_watch_attempt.is_none_or(|at| at.elapsed() >= WATCH_RETRY_INTERVAL)
+                {
+                    last_watch_attempt = Some(Instant::now());
+                    watched = watch_projects(&mut watcher, &root);
+                    if watched || !synced {
+                        match sync_session_cache_changes() {
+                            Ok(changes) => emit_changes(&sinks, &changes),
+                            Err(err) => log::error!("[cc session scanner] failed to sync session cache: {}", err),
+                        }
+                        synced = true;
+                    }
+                    PROJECTS_WATCHED.store(watched, Ordering::Release);
+                }
+
+                match rx.recv_timeout(Duration::from_millis(200)) {
+                    Ok(Ok(event)) => {
+                        if should_rescan_for_event(&event) {
+                            pending_paths.extend(
+                                event.paths.into_iter().filter(|path| is_session_file(&root, path)),
+                            );
+                        }
+                    }
+                    Ok(Err(err)) => log::warn!("[cc session scanner] watch event error: {}", err),
+                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+
+                // A failed batch (e.g. the database stayed locked) is kept, since
+                // a watched cache is not reconciled on read.
+                if !pending_paths.is_empty()
+                    && last_scan.elapsed() >= Duration::from_millis(500)
+                    && index_failed_at.is_none_or(|at| at.elapsed() >= INDEX_RETRY_INTERVAL)
+                {
+                    last_scan = Instant::now();
+                    match index_session_paths(&pending_paths) {
+                        Ok(changes) => {
+                            emit_changes(&sinks, &changes);
+                            pending_paths.clear();
+                            index_failed_at = None;
+                        }
+                        Err(err) => {
+                            log::error!("[cc session scanner] index update failed, retrying: {}", err);
+                            index_failed_at = Some(Instant::now());
+                        }
+                    }
+                }
+            }
         });
     });
 }
 
+/// Start watching `root` recursively; false while it does not exist yet.
+fn watch_projects(watcher: &mut RecommendedWatcher, root: &Path) -> bool {
+    if !root.is_dir() {
+        return false;
+    }
+    match watcher.watch(root, RecursiveMode::Recursive) {
+        Ok(()) => {
+            log::info!("[cc session scanner] watching {}", root.display());
+            true
+        }
+        Err(err) => {
+            log::error!("[cc session scanner] watch failed for {}: {}", root.display(), err);
+            false
+        }
+    }
+}
+
+/// Sessions added or updated and ids removed by one sync pass.
+#[derive(Default)]
+pub struct SessionChanges {
+    pub updated: Vec<SdkSessionInfo>,
+    pub removed: Vec<String>,
+}
+
+impl SessionChanges {
+    fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn emit_changes(sinks: &EventSinks, changes: &SessionChanges) {
+    if changes.is_empty() {
+        return;
+    }
+    let payload = json!({
+        "updated": changes.updated,
+        "removed": changes.removed,
+    });
+    let sink_list = match sinks.lock() {
+        Ok(guarded) => guarded.clone(),
+        Err(_) => Vec::new(),
+    };
+    for sink in sink_list {
+        sink.emit(SESSIONS_UPDATED_EVENT, payload.clone());
+    }
+}
+
 pub fn sync_project_session_cache(directory: &str, include_worktrees: bool) -> Result<(), String> {
     let started_at = Instant::now();
     let sessions = claude_agent_sdk_rs::sessions::list_sessions(Some(directory), None, 0, include_worktrees);
-    SessionCache::new()?.replace_project_sessions(directory, &sessions, include_worktrees)?;
+    let mut cache = SessionCache::new()?;
+    cache.replace_project_sessions(directory, &sessions, include_worktrees)?;
+    // The listing carries no transcript paths; index each session from where
+    // Claude Code keeps the transcripts of its cwd.
+    let root = projects_dir()?;
+    for session in &sessions {
+        let Some(cwd) = session.cwd.as_deref() else {
+            continue;
+        };
+        let path = root
+            .join(super::transcript::project_dir_name(cwd))
+            .join(format!("{}.jsonl", session.session_id));
+        if !path.is_file() {
+            continue;
+        }
+        let segments = search_segments(&path, session);
+        if let Err(err) = cache.replace_search_segments(&session.session_id, &segments) {
+            log::warn!("[cc session scanner] failed to index {}: {}", path.display(), err);
+        }
+    }
     log::info!(
         "[cc session scanner] synced {} project sessions for {} in {:?}",
         sessions.len(),
@@ -27,19 +206,342 @@ pub fn sync_project_session_cache(directory: &str, include_worktrees: bool) -> R
 }
 
 pub fn sync_session_cache() {
+    if let Err(err) = sync_session_cache_changes() {
+        log::error!("[cc session scanner] failed to sync session cache: {}", err);
+    }
+}
+
+/// Without a live watcher, reconcile before reading so listings still reflect
+/// the filesystem; only changed transcripts are re-read.
+pub fn ensure_session_cache() {
+    if !PROJECTS_WATCHED.load(Ordering::Acquire) {
+        sync_session_cache();
+    }
+}
+
+/// Reconcile the cache with `~/.claude/projects`: re-read transcripts whose
+/// mtime or size changed and drop sessions whose transcript is gone.
+fn sync_session_cache_changes() -> Result<SessionChanges, String> {
     let started_at = Instant::now();
-    let sessions = claude_agent_sdk_rs::sessions::list_sessions(None, None, 0, true);
+    let mut cache = SessionCache::new()?;
+    let known = cache.file_states()?;
 
-    match SessionCache::new().and_then(|mut cache| cache.replace_all(&sessions)) {
-        Ok(()) => {
-            log::info!(
-                "[cc session scanner] synced {} sessions in {:?}",
-                sessions.len(),
-                started_at.elapsed()
-            );
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut changed: Vec<SessionFileState> = Vec::new();
+    let root = projects_dir()?;
+    if let Ok(projects) = fs::read_dir(&root) {
+        for project in projects.flatten() {
+            let Ok(files) = fs::read_dir(project.path()) else {
+                continue;
+            };
+            for path in files.flatten().map(|entry| entry.path()) {
+                let Some(state) = file_state(&root, &path) else {
+                    continue;
+                };
+                seen.insert(state.path.clone());
+                if known.get(&state.path) != Some(&(state.mtime, state.size)) {
+                    changed.push(state);
+                }
+            }
         }
-        Err(err) => {
-            log::error!("[cc session scanner] failed to sync session cache: {}", err);
+    }
+    let removed: Vec<String> = known.into_keys().filter(|path| !seen.contains(path)).collect();
+
+    let mut changes = apply_changes(&mut cache, changed, &removed)?;
+    changes.removed.extend(cache.prune_untracked_sessions()?);
+    log::info!(
+        "[cc session scanner] synced {} updated and {} removed sessions in {:?}",
+        changes.updated.len(),
+        changes.removed.len(),
+        started_at.elapsed()
+    );
+    Ok(changes)
+}
+
+/// Update the cache for transcripts reported by the watcher.
+fn index_session_paths(paths: &HashSet<PathBuf>) -> Result<SessionChanges, String> {
+    let root = projects_dir()?;
+    let mut cache = SessionCache::new()?;
+    let known = cache.file_states()?;
+
+    let mut changed: Vec<SessionFileState> = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    for path in paths {
+        match file_state(&root, path) {
+            Some(state) if known.get(&state.path) != Some(&(state.mtime, state.size)) => changed.push(state),
+            Some(_) => {}
+            None => {
+                let key = path.to_string_lossy().to_string();
+                if known.contains_key(&key) {
+                    removed.push(key);
+                }
+            }
+        }
+    }
+    apply_changes(&mut cache, changed, &removed)
+}
+
+fn apply_changes(
+    cache: &mut SessionCache,
+    changed: Vec<SessionFileState>,
+    removed: &[String],
+) -> Result<SessionChanges, String> {
+    if changed.is_empty() && removed.is_empty() {
+        return Ok(SessionChanges::default());
+    }
+    let sessions = load_session_infos(&changed);
+    let removed = cache.apply_file_changes(&changed, &sessions, removed)?;
+    for session in &sessions {
+        let Some(file) = changed.iter().find(|file| file.session_id == session.session_id) else {
+            continue;
+        };
+        let segments = search_segments(Path::new(&file.path), session);
+        if let Err(err) = cache.replace_search_segments(&session.session_id, &segments) {
+            log::warn!("[cc session scanner] failed to index {}: {}", file.path, err);
+        }
+    }
+    Ok(SessionChanges { updated: sessions, removed })
+}
+
+/// Re-index one transcript right away instead of waiting for the watcher,
+/// e.g. after Codexia appended to it.
+pub fn refresh_session_file(path: &Path) -> Result<(), String> {
+    let changes = index_session_paths(&HashSet::from([path.to_path_buf()]))?;
+    if let Some(sinks) = SESSION_SCANNER_SINKS.get() {
+        emit_changes(sinks, &changes);
+    }
+    Ok(())
+}
+
+/// Messages, shell commands and touched files of a transcript, plus its title
+/// and tags.
+fn search_segments(path: &Path, session: &SdkSessionInfo) -> Vec<SessionSearchSegment> {
+    let segment = |kind: &str, text: &str| SessionSearchSegment {
+        kind: kind.to_string(),
+        text: text.to_string(),
+    };
+    let mut segments = vec![segment("summary", &session.summary)];
+    if let Some(title) = &session.custom_title {
+        segments.push(segment("title", title));
+    }
+    for tag in split_tags(session.tag.as_deref()) {
+        segments.push(segment("tag", &tag));
+    }
+
+    let Ok(content) = fs::read_to_string(path) else {
+        return segments;
+    };
+    let rollout = super::transcript::parse_transcript(&content);
+    for event in &rollout.events {
+        match event {
+            RolloutEvent::Message { role, text, .. } => segments.push(segment(role, text)),
+            RolloutEvent::ToolCall { command: Some(command), .. } => segments.push(segment("command", command)),
+            _ => {}
         }
     }
-}
\ No newline at end of file
+    for file in rollout.touched_files() {
+        segments.push(segment("file", &file));
+    }
+    segments
+}
+
+/// Parse the changed transcripts, and only those.
+fn load_session_infos(changed: &[SessionFileState]) -> Vec<SdkSessionInfo> {
+    changed
+        .iter()
+        .filter_map(|file| {
+            let content = fs::read_to_string(&file.path).ok()?;
+            session_info(file, &content)
+        })
+        .collect()
+}
+
+/// The listing entry of one transcript, as the SDK's session listing reports
+/// it: the summary is the custom title, else the last `summary` record, else
+/// the first prompt. Transcripts without a cwd or a prompt have no messages
+/// yet and are not sessions.
+fn session_info(file: &SessionFileState, content: &str) -> Option<SdkSessionInfo> {
+    let mut cwd = None;
+    let mut git_branch = None;
+    let mut created_at = None;
+    let mut first_prompt: Option<String> = None;
+    let mut summary = None;
+    let mut custom_title = None;
+    let mut tag = None;
+
+    for record in content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        let str_field = |key: &str| {
+            record
+                .get(key)
+                .and_then(Value::as_str)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        match str_field("type").as_deref() {
+            Some("summary") => summary = str_field("summary").or(summary),
+            Some("custom-title") => custom_title = str_field("customTitle").or(custom_title),
+            // An empty tag record clears the tags.
+            Some("tag") => tag = str_field("tag"),
+            _ => {}
+        }
+        if record.get("isSidechain").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        cwd = cwd.or_else(|| str_field("cwd"));
+        git_branch = git_branch.or_else(|| str_field("gitBranch"));
+        if created_at.is_none() {
+            created_at = str_field("timestamp")
+                .and_then(|timestamp| chrono::DateTime::parse_from_rfc3339(&timestamp).ok())
+                .map(|timestamp| timestamp.timestamp_millis());
+        }
+        if first_prompt.is_none()
+            && str_field("type").as_deref() == Some("user")
+            && record.get("isMeta").and_then(Value::as_bool) != Some(true)
+        {
+            first_prompt = record.get("message").and_then(prompt_text);
+        }
+    }
+
+    let cwd = cwd?;
+    let summary = custom_title
+        .clone()
+        .or(summary)
+        .or_else(|| first_prompt.clone())?;
+    Some(SdkSessionInfo {
+        session_id: file.session_id.clone(),
+        summary,
+        last_modified: file.mtime as _,
+        file_size: Some(file.size as u64),
+        custom_title,
+        first_prompt,
+        git_branch,
+        cwd: Some(cwd),
+        tag,
+        created_at: created_at.map(|created_at| created_at as _),
+    })
+}
+
+/// The text a user typed in a message, skipping tool results and the
+/// wrappers Claude Code records for slash commands and their output.
+fn prompt_text(message: &Value) -> Option<String> {
+    let text = match message.get("content")? {
+        Value::String(text) => text.as_str(),
+        Value::Array(blocks) => blocks.iter().find_map(|block| {
+            (block.get("type").and_then(Value::as_str) == Some("text"))
+                .then(|| block.get("text").and_then(Value::as_str))
+                .flatten()
+        })?,
+        _ => return None,
+    };
+    let text = text.trim();
+    if text.is_empty() || text.starts_with('<') {
+        return None;
+    }
+    Some(match text.char_indices().nth(MAX_PROMPT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    })
+}
+
+/// A session transcript is a `.jsonl` file directly inside a project
+/// directory; subagent transcripts live deeper and are skipped.
+fn is_session_file(root: &Path, path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("jsonl")
+        && path.parent().and_then(Path::parent) == Some(root)
+}
+
+fn file_state(root: &Path, path: &Path) -> Option<SessionFileState> {
+    if !is_session_file(root, path) {
+        return None;
+    }
+    let metadata = fs::metadata(path).ok().filter(|metadata| metadata.is_file())?;
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default();
+    Some(SessionFileState {
+        path: path.to_string_lossy().to_string(),
+        session_id: path.file_stem()?.to_string_lossy().to_string(),
+        mtime,
+        size: metadata.len() as i64,
+    })
+}
+
+fn should_rescan_for_event(event: &Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(session_id: &str) -> SessionFileState {
+        SessionFileState {
+            path: format!("/home/u/.claude/projects/-repo/{}.jsonl", session_id),
+            session_id: session_id.to_string(),
+            mtime: 42,
+            size: 100,
+        }
+    }
+
+    fn lines(records: &[Value]) -> String {
+        records.iter().map(|record| format!("{}\n", record)).collect()
+    }
+
+    #[test]
+    fn session_info_reads_listing_fields() {
+        let content = lines(&[
+            json!({ "type": "user", "cwd": "/repo", "gitBranch": "main", "isMeta": true,
+                    "timestamp": "2026-01-01T00:00:00Z",
+                    "message": { "role": "user", "content": "<local-command-caveat>x</local-command-caveat>" } }),
+            json!({ "type": "user", "cwd": "/repo",
+                    "message": { "role": "user", "content": [{ "type": "text", "text": "  fix the build " }] } }),
+            json!({ "type": "tag", "tag": "ci, build" }),
+        ]);
+        let info = session_info(&file("s1"), &content).unwrap();
+        assert_eq!(info.session_id, "s1");
+        assert_eq!(info.summary, "fix the build");
+        assert_eq!(info.first_prompt.as_deref(), Some("fix the build"));
+        assert_eq!(info.cwd.as_deref(), Some("/repo"));
+        assert_eq!(info.git_branch.as_deref(), Some("main"));
+        assert_eq!(info.tag.as_deref(), Some("ci, build"));
+        assert_eq!(info.file_size, Some(100));
+        assert!(info.created_at.is_some());
+    }
+
+    #[test]
+    fn session_info_prefers_title_then_summary_and_skips_empty_sessions() {
+        let prompt = json!({ "type": "user", "cwd": "/repo",
+                             "message": { "role": "user", "content": "hello" } });
+        let summary = json!({ "type": "summary", "summary": "Greeting" });
+        let title = json!({ "type": "custom-title", "customTitle": "My title" });
+        let info = |records: &[Value]| session_info(&file("s"), &lines(records));
+
+        assert_eq!(info(&[prompt.clone(), summary.clone()]).unwrap().summary, "Greeting");
+        let titled = info(&[prompt.clone(), summary, title]).unwrap();
+        assert_eq!(titled.summary, "My title");
+        assert_eq!(titled.custom_title.as_deref(), Some("My title"));
+        let cleared = info(&[prompt, json!({ "type": "tag", "tag": "a" }), json!({ "type": "tag", "tag": "" })]);
+        assert_eq!(cleared.unwrap().tag, None);
+
+        assert!(info(&[json!({ "type": "summary", "summary": "no cwd" })]).is_none());
+        assert!(info(&[json!({ "type": "system", "cwd": "/repo" })]).is_none());
+    }
+
+    #[test]
+    fn only_top_level_project_transcripts_are_sessions() {
+        let root = Path::new("/home/u/.claude/projects");
+        assert!(is_session_file(root, &root.join("-repo/abc.jsonl")));
+        assert!(!is_session_file(root, &root.join("-repo/abc/subagents/agent-1.jsonl")));
+        assert!(!is_session_file(root, &root.join("-repo/notes.txt")));
+        assert!(!is_session_file(root, &root.join("stray.jsonl")));
+    }
+}
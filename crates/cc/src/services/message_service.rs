@@ -1,6 +1,8 @@
-use crate::state::CCState;
+use super::session_lifecycle;
+use crate::state::{CCState, QueuedMessage};
 use claude_agent_sdk_rs::{Message, UserContentBlock};
 use futures::StreamExt;
+use serde_json::json;
 
 fn image_path_to_content_block(path: &str) -> Result<UserContentBlock, String> {
     let bytes = std::fs::read(path).map_err(|e| format!("Failed to read image {path}: {e}"))?;
@@ -27,11 +29,20 @@ pub(crate) async fn send_message_and_wait(
     mut message_callback: impl FnMut(Message) + Send,
 ) -> Result<(), String> {
     log::info!("[CC automation] Sending message to session {}: {}", session_id, message);
+    session_lifecycle::ensure_live(session_id, state).await?;
+    state.touch_session(session_id, None);
     let client = state
         .get_client(session_id)
         .await
         .ok_or("Client not found")?;
 
+    if let Some(limit) = state.get_budget_guard(session_id) {
+        let spent = state.get_usage(session_id).total_cost_usd;
+        if spent >= limit {
+            return Err(format!("Session budget of ${:.2} exhausted (${:.2} spent)", limit, spent));
+        }
+    }
+
     {
         let mut client = client.write().await;
         client.connect().await.map_err(|e| e.to_string())?;
@@ -55,6 +66,7 @@ pub(crate) async fn send_message_and_wait(
         client.query_with_content_and_session(content, session_id).await.map_err(|e| e.to_string())?;
     }
 
+    let mut budget_interrupted = false;
     loop {
         let result = {
             // Use a read lock so interrupt() can acquire a concurrent read lock
@@ -65,6 +77,40 @@ pub(crate) async fn send_message_and_wait(
 
         match result {
             Some(Ok(msg)) => {
+                let value = serde_json::to_value(&msg).unwrap_or_default();
+                state.touch_session(session_id, value["session_id"].as_str());
+                if let Some(usage) = state.observe_usage(session_id, &value) {
+                    state.emit("cc-usage", json!({ "sessionId": session_id, "usage": usage }));
+                    if let Some(limit) = state.get_budget_guard(session_id)
+                        && usage.total_cost_usd >= limit
+                        && !budget_interrupted
+                    {
+                        budget_interrupted = true;
+                        log::warn!(
+                            "[CC automation] Session {} reached its ${:.2} budget, interrupting",
+                            session_id, limit
+                        );
+                        state.emit("cc-budget-exceeded", json!({
+                            "sessionId": session_id,
+                            "limitUsd": limit,
+                            "usage": usage,
+                        }));
+                        if let Err(e) = client.read().await.interrupt().await {
+                            log::error!("Failed to interrupt session {} over budget: {}", session_id, e);
+                        }
+                    }
+                }
+
+                // Partial messages go to their own event so they never land in the transcript.
+                if value["type"] == "stream_event" {
+                    state.emit("cc-stream-event", json!({
+                        "sessionId": session_id,
+                        "parentToolUseId": value["parent_tool_use_id"],
+                        "event": value["event"],
+                    }));
+                    continue;
+                }
+
                 log::info!("[CC automation] Received message from session {}: {:?}", session_id, msg);
                 message_callback(msg.clone());
                 if matches!(msg, Message::Result(_)) {
@@ -76,39 +122,80 @@ pub(crate) async fn send_message_and_wait(
         }
     }
 
+    state.touch_session(session_id, None);
     log::info!("[CC automation] Message exchange completed for session {}", session_id);
     Ok(())
 }
 
+/// Queue a message for the session and make sure a worker is sending the
+/// queue in order, one exchange at a time. With `steer` the message jumps the
+/// queue and the running exchange is interrupted so it is sent right away.
+///
+/// The callback of the call that starts the worker receives the messages of
+/// every exchange that worker sends. A session reaped while idle is resumed
+/// before the message is queued.
 pub async fn send_message(
     session_id: &str,
     message: &str,
     image_paths: &[String],
+    steer: bool,
     state: &CCState,
-    message_callback: impl Fn(Message) + Send + 'static,
-) -> Result<(), String> {
-    if state.get_client(session_id).await.is_none() {
-        return Err("Client not found".to_string());
+    message_callback: impl Fn(Message) + Send + Sync + 'static,
+) -> Result<QueuedMessage, String> {
+    session_lifecycle::ensure_live(session_id, state).await?;
+    let client = state.get_client(session_id).await.ok_or("Client not found")?;
+
+    let queued = QueuedMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        message: message.to_string(),
+        image_paths: image_paths.to_vec(),
+        queued_at: chrono::Utc::now().timestamp_millis(),
+    };
+    if !state.enqueue_message(session_id, queued.clone(), steer) {
+        if steer {
+            log::info!("[CC] Steering session {}: interrupting the running exchange", session_id);
+            if let Err(e) = client.read().await.interrupt().await {
+                log::warn!("[CC] Failed to interrupt session {} for steering: {}", session_id, e);
+            }
+        }
+        return Ok(queued);
     }
 
     let session_id_owned = session_id.to_string();
-    let message_owned = message.to_string();
-    let image_paths_owned = image_paths.to_vec();
     let state_cloned = state.clone();
 
     tokio::spawn(async move {
-        if let Err(err) = send_message_and_wait(
-            &session_id_owned,
-            &message_owned,
-            &image_paths_owned,
-            &state_cloned,
-            message_callback,
-        )
-        .await
-        {
-            log::error!("Error sending/receiving message: {}", err);
+        while let Some(next) = state_cloned.next_queued_message(&session_id_owned) {
+            if let Err(err) = send_message_and_wait(
+                &session_id_owned,
+                &next.message,
+                &next.image_paths,
+                &state_cloned,
+                |msg| message_callback(msg),
+            )
+            .await
+            {
+                log::error!("Error sending/receiving message: {}", err);
+                state_cloned.emit("cc-message-error", json!({
+                    "sessionId": session_id_owned,
+                    "queuedMessageId": next.id,
+                    "error": err,
+                }));
+            }
         }
     });
 
-    Ok(())
+    Ok(queued)
+}
+
+pub fn list_queue(session_id: &str, state: &CCState) -> Vec<QueuedMessage> {
+    state.list_queued_messages(session_id)
+}
+
+pub fn cancel_queued(session_id: &str, message_id: &str, state: &CCState) -> Result<(), String> {
+    state.cancel_queued_message(session_id, message_id)
+}
+
+pub fn reorder_queue(session_id: &str, message_ids: &[String], state: &CCState) -> Result<(), String> {
+    state.reorder_queued_messages(session_id, message_ids)
 }
@@ -1,5 +1,9 @@
 pub mod message_service;
+pub mod permission_rules;
 pub mod permission_storage;
+pub mod sensitive_paths;
+pub mod session_lifecycle;
 pub mod session_service;
 pub mod settings_service;
 pub mod skill_service;
+pub mod usage_service;
@@ -0,0 +1,798 @@
+//! Claude Code permission rules (`permissions.allow` / `deny` / `ask` in
+//! `.claude/settings.json`), evaluated by the `PreToolUse` hook.
+//!
+//! Rules use Claude Code's native syntax: a bare tool name (`WebSearch`) or a
+//! tool with a specifier (`Bash(git status:*)`, `Edit(src/**)`,
+//! `WebFetch(domain:docs.rs)`, `mcp__github`). Deny wins over ask, ask over allow.
+
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+/// Tools covered by an `Edit(...)` rule.
+const EDIT_TOOLS: &[&str] = &["Edit", "Write", "MultiEdit", "NotebookEdit"];
+/// Tools covered by a `Read(...)` rule.
+const READ_TOOLS: &[&str] = &["Read", "Grep", "Glob", "LS"];
+/// Programs that run the rest of their arguments as a command, with the flags
+/// of each that take a separate value (`sudo -u root rm`).
+const WRAPPERS: &[(&str, &[&str])] = &[
+    ("sudo", &["-u", "-g", "-C", "-D", "-h", "-p", "-U"]),
+    ("doas", &["-u", "-C"]),
+    ("env", &["-u", "-C", "-S"]),
+    ("command", &[]),
+    ("builtin", &[]),
+    ("exec", &["-a"]),
+    ("nice", &["-n"]),
+    ("nohup", &[]),
+    ("time", &["-f", "-o"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleKind {
+    Allow,
+    Deny,
+    Ask,
+}
+
+impl RuleKind {
+    pub fn key(self) -> &'static str {
+        match self {
+            RuleKind::Allow => "allow",
+            RuleKind::Deny => "deny",
+            RuleKind::Ask => "ask",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionRule {
+    pub tool: String,
+    pub specifier: Option<String>,
+}
+
+impl FromStr for PermissionRule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+        let (tool, specifier) = match rule.split_once('(') {
+            Some((tool, rest)) => {
+                let specifier = rest
+                    .strip_suffix(')')
+                    .ok_or_else(|| format!("Invalid permission rule '{}': missing ')'", rule))?;
+                if specifier.trim().is_empty() {
+                    return Err(format!(
+                        "Invalid permission rule '{}': empty specifier",
+                        rule
+                    ));
+                }
+                (tool, Some(specifier.to_string()))
+            }
+            None => (rule, None),
+        };
+        let valid_tool = !tool.is_empty()
+            && tool
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '*');
+        if !valid_tool {
+            return Err(format!("Invalid permission rule '{}': bad tool name", rule));
+        }
+        Ok(Self {
+            tool: tool.to_string(),
+            specifier,
+        })
+    }
+}
+
+impl fmt::Display for PermissionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.specifier {
+            Some(specifier) => write!(f, "{}({})", self.tool, specifier),
+            None => f.write_str(&self.tool),
+        }
+    }
+}
+
+/// What a rule specifier is matched against for one tool call. A Bash call
+/// yields one subject per chained subcommand.
+#[derive(Debug)]
+enum Subject {
+    Command(String),
+    Path(PathBuf),
+    Domain(String),
+    Other,
+}
+
+impl PermissionRule {
+    fn covers_tool(&self, tool_name: &str) -> bool {
+        if self.tool == tool_name {
+            return true;
+        }
+        if self.tool == "Edit" {
+            return EDIT_TOOLS.contains(&tool_name);
+        }
+        if self.tool == "Read" {
+            return READ_TOOLS.contains(&tool_name);
+        }
+        // `mcp__server` and `mcp__server__*` cover every tool of that server.
+        if let Some(server) = self.tool.strip_prefix("mcp__") {
+            let server = server.strip_suffix("__*").unwrap_or(server);
+            return !server.contains("__")
+                && tool_name
+                    .strip_prefix("mcp__")
+                    .and_then(|rest| rest.strip_prefix(server))
+                    .is_some_and(|rest| rest.starts_with("__"));
+        }
+        false
+    }
+
+    fn matches(&self, tool_name: &str, tool_input: &Value, subject: &Subject, cwd: &str) -> bool {
+        if !self.covers_tool(tool_name) {
+            return false;
+        }
+        let Some(specifier) = self.specifier.as_deref() else {
+            return true;
+        };
+        match subject {
+            Subject::Command(command) => command_matches(specifier, command),
+            Subject::Path(path) => path_matches(specifier, path, cwd),
+            Subject::Domain(host) => specifier
+                .strip_prefix("domain:")
+                .is_some_and(|domain| domain_matches(domain, host)),
+            // `Task(Explore)` scopes by subagent type.
+            Subject::Other => tool_input
+                .get("subagent_type")
+                .and_then(Value::as_str)
+                .is_some_and(|agent| agent == specifier),
+        }
+    }
+}
+
+/// The merged `permissions` of the user, project and local settings files.
+#[derive(Debug, Clone, Default)]
+pub struct PermissionRules {
+    pub allow: Vec<PermissionRule>,
+    pub deny: Vec<PermissionRule>,
+    pub ask: Vec<PermissionRule>,
+}
+
+impl PermissionRules {
+    /// Read `~/.claude/settings.json`, `{cwd}/.claude/settings.json` and
+    /// `{cwd}/.claude/settings.local.json`. Unreadable files and invalid rules
+    /// are skipped with a warning.
+    pub fn load(cwd: &str) -> Self {
+        let mut files = Vec::new();
+        if let Some(home) = dirs::home_dir() {
+            files.push(home.join(".claude").join("settings.json"));
+        }
+        if !cwd.is_empty() {
+            let project = Path::new(cwd).join(".claude");
+            files.push(project.join("settings.json"));
+            files.push(project.join("settings.local.json"));
+        }
+
+        let mut rules = Self::default();
+        for file in files {
+            let Ok(content) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            match serde_json::from_str::<Value>(&content) {
+                Ok(settings) => rules.extend_from_settings(&settings),
+                Err(e) => log::warn!("[permission rules] Failed to parse {:?}: {}", file, e),
+            }
+        }
+        rules
+    }
+
+    pub fn extend_from_settings(&mut self, settings: &Value) {
+        for kind in [RuleKind::Allow, RuleKind::Deny, RuleKind::Ask] {
+            let Some(entries) = settings
+                .pointer(&format!("/permissions/{}", kind.key()))
+                .and_then(Value::as_array)
+            else {
+                continue;
+            };
+            for entry in entries.iter().filter_map(Value::as_str) {
+                match entry.parse() {
+                    Ok(rule) => self.list_mut(kind).push(rule),
+                    Err(e) => log::warn!("[permission rules] {}", e),
+                }
+            }
+        }
+    }
+
+    fn list_mut(&mut self, kind: RuleKind) -> &mut Vec<PermissionRule> {
+        match kind {
+            RuleKind::Allow => &mut self.allow,
+            RuleKind::Deny => &mut self.deny,
+            RuleKind::Ask => &mut self.ask,
+        }
+    }
+
+    /// The decision for a tool call and the rule that produced it, if any rule applies.
+    pub fn evaluate(
+        &self,
+        tool_name: &str,
+        tool_input: &Value,
+        cwd: &str,
+    ) -> Option<(RuleKind, PermissionRule)> {
+        if let Some(rule) = any_match(&self.deny, tool_name, tool_input, cwd) {
+            return Some((RuleKind::Deny, rule));
+        }
+        if let Some(rule) = any_match(&self.ask, tool_name, tool_input, cwd) {
+            return Some((RuleKind::Ask, rule));
+        }
+        all_match(&self.allow, tool_name, tool_input, cwd).map(|rule| (RuleKind::Allow, rule))
+    }
+}
+
+/// A rule in `rules` matching any subject of the call (deny / ask semantics).
+/// Each subcommand is also matched as it actually runs, see [`executed_commands`].
+fn any_match(
+    rules: &[PermissionRule],
+    tool_name: &str,
+    tool_input: &Value,
+    cwd: &str,
+) -> Option<PermissionRule> {
+    subjects(tool_name, tool_input, cwd)
+        .into_iter()
+        .flat_map(|subject| match subject {
+            Subject::Command(command) => executed_commands(&command)
+                .into_iter()
+                .map(Subject::Command)
+                .collect(),
+            subject => vec![subject],
+        })
+        .find_map(|subject| {
+            rules
+                .iter()
+                .find(|rule| rule.matches(tool_name, tool_input, &subject, cwd))
+                .cloned()
+        })
+}
+
+/// Allow semantics: every subject of the call (e.g. each subcommand of
+/// `git status && rm -rf x`) must be matched by some rule. Returns the first
+/// matching rule for the decision reason. A subcommand with a substitution or
+/// an output redirect is never allowed by a rule, so the call prompts.
+pub fn all_match(
+    rules: &[PermissionRule],
+    tool_name: &str,
+    tool_input: &Value,
+    cwd: &str,
+) -> Option<PermissionRule> {
+    let mut first = None;
+    for subject in subjects(tool_name, tool_input, cwd) {
+        if let Subject::Command(command) = &subject
+            && runs_or_writes_more(command)
+        {
+            return None;
+        }
+        let rule = rules
+            .iter()
+            .find(|rule| rule.matches(tool_name, tool_input, &subject, cwd))?;
+        first.get_or_insert_with(|| rule.clone());
+    }
+    first
+}
+
+fn subjects(tool_name: &str, tool_input: &Value, cwd: &str) -> Vec<Subject> {
+    let field = |key: &str| tool_input.get(key).and_then(Value::as_str);
+    match tool_name {
+        "Bash" => {
+            let commands: Vec<Subject> = split_command(field("command").unwrap_or_default())
+                .into_iter()
+                .map(Subject::Command)
+                .collect();
+            if commands.is_empty() {
+                vec![Subject::Command(String::new())]
+            } else {
+                commands
+            }
+        }
+        "WebFetch" => vec![Subject::Domain(
+            field("url")
+                .and_then(|url| reqwest::Url::parse(url).ok())
+                .and_then(|url| url.host_str().map(str::to_lowercase))
+                .unwrap_or_default(),
+        )],
+        _ if EDIT_TOOLS.contains(&tool_name) || READ_TOOLS.contains(&tool_name) => {
+            let path = field("file_path")
+                .or_else(|| field("notebook_path"))
+                .or_else(|| field("path"))
+                .unwrap_or(cwd);
+            vec![Subject::Path(absolute_path(path, cwd))]
+        }
+        _ => vec![Subject::Other],
+    }
+}
+
+/// Split a shell command on `&&`, `||`, `;`, `|`, `&` and newlines. Quotes are
+/// not interpreted, so a quoted separator yields extra subcommands: allow
+/// rules then match less and deny rules match more, never the other way round.
+pub(super) fn split_command(command: &str) -> Vec<String> {
+    command
+        .split(['&', '|', ';', '\n'])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether a subcommand does more than its words say: `$(...)`, backticks and
+/// `<(...)` / `>(...)` run other commands, `>` and `>>` write files. A rule
+/// like `Bash(echo:*)` must not cover `echo $(rm -rf x)` or `echo x > ~/.bashrc`.
+fn runs_or_writes_more(command: &str) -> bool {
+    command.contains("$(")
+        || command.contains('`')
+        || command.contains("<(")
+        || command.contains('>')
+}
+
+/// The commands a subcommand runs, for deny and ask rules: the subcommand as
+/// written, the same without leading `VAR=value` assignments and wrappers
+/// like `sudo` or `env` and with the program reduced to its basename
+/// (`FOO=1 sudo /bin/rm -rf x` runs `rm -rf x`), and every command inside
+/// `$(...)`, backticks and `<(...)` / `>(...)`.
+fn executed_commands(command: &str) -> Vec<String> {
+    let mut commands = vec![command.to_string()];
+    let unwrapped = unwrap_command(command);
+    if !unwrapped.is_empty() && unwrapped != command {
+        commands.push(unwrapped);
+    }
+    for inner in substitutions(command) {
+        for part in split_command(&inner) {
+            commands.extend(executed_commands(&part));
+        }
+    }
+    commands
+}
+
+fn unwrap_command(command: &str) -> String {
+    let mut words = command.split_whitespace().peekable();
+    while let Some(word) = words.peek() {
+        if is_assignment(word) {
+            words.next();
+            continue;
+        }
+        let program = word.rsplit('/').next().unwrap_or(word);
+        let Some((_, value_flags)) = WRAPPERS.iter().find(|(name, _)| *name == program) else {
+            break;
+        };
+        words.next();
+        while let Some(flag) = words.next_if(|word| word.starts_with('-')) {
+            if value_flags.contains(&flag) {
+                words.next();
+            }
+        }
+    }
+    let Some(program) = words.next() else {
+        return String::new();
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+    std::iter::once(program)
+        .chain(words)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `NAME=value`, as in `FOO=1 make`.
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// The bodies of `$(...)`, `<(...)`, `>(...)` and backticks in `command`, at
+/// the outermost level. An unclosed substitution runs to the end.
+fn substitutions(command: &str) -> Vec<String> {
+    let bytes = command.as_bytes();
+    let mut found = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let opens_paren =
+            matches!(bytes[index], b'$' | b'<' | b'>') && bytes.get(index + 1) == Some(&b'(');
+        if opens_paren {
+            let start = index + 2;
+            let mut depth = 1;
+            let mut end = start;
+            while end < bytes.len() {
+                match bytes[end] {
+                    b'(' => depth += 1,
+                    b')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                end += 1;
+            }
+            found.push(command[start..end].to_string());
+            index = end + 1;
+        } else if bytes[index] == b'`' {
+            let start = index + 1;
+            let end = command[start..]
+                .find('`')
+                .map_or(command.len(), |offset| start + offset);
+            found.push(command[start..end].to_string());
+            index = end + 1;
+        } else {
+            index += 1;
+        }
+    }
+    found
+}
+
+/// `git status:*` is a prefix rule, `npm run *` a wildcard; anything else must
+/// match the whole subcommand.
+fn command_matches(specifier: &str, command: &str) -> bool {
+    if let Some(prefix) = specifier.strip_suffix(":*") {
+        return command == prefix
+            || command
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with(char::is_whitespace));
+    }
+    if specifier.contains('*') {
+        return wildcard_matches(specifier, command);
+    }
+    command == specifier
+}
+
+fn wildcard_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (index, part) in parts.iter().enumerate() {
+        if index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+/// Path specifiers follow Claude Code: `//abs` is absolute, `~/x` is under the
+/// home directory, `/x` and `x` are relative to the project directory.
+fn path_matches(specifier: &str, path: &Path, cwd: &str) -> bool {
+    let resolved = if let Some(absolute) = specifier.strip_prefix("//") {
+        format!("/{}", absolute)
+    } else if let Some(home_relative) = specifier.strip_prefix("~/") {
+        match dirs::home_dir() {
+            Some(home) => format!("{}/{}", home.to_string_lossy(), home_relative),
+            None => return false,
+        }
+    } else {
+        let relative = specifier.trim_start_matches("./").trim_start_matches('/');
+        format!("{}/{}", cwd.trim_end_matches('/'), relative)
+    };
+    let Ok(pattern) = Pattern::new(&resolved) else {
+        log::warn!("[permission rules] Invalid path pattern '{}'", specifier);
+        return false;
+    };
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+    pattern.matches_path_with(path, options)
+}
+
+fn domain_matches(domain: &str, host: &str) -> bool {
+    let domain = domain.to_lowercase();
+    match domain.strip_prefix("*.") {
+        Some(parent) => host.ends_with(&format!(".{}", parent)),
+        None => host == domain,
+    }
+}
+
+/// Resolve `path` against `cwd` and drop `.` / `..` components lexically so
+/// `src/../.env` cannot slip past a `src/**` rule.
+pub(super) fn absolute_path(path: &str, cwd: &str) -> PathBuf {
+    let joined = Path::new(cwd).join(path);
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Rules to save when the user chooses "always allow" for a call: a command
+/// prefix per Bash subcommand, the parent directory for file tools, the domain
+/// for WebFetch and the bare tool name otherwise.
+pub fn suggest_rules(tool_name: &str, tool_input: &Value, cwd: &str) -> Vec<PermissionRule> {
+    let rule = |tool: &str, specifier: Option<String>| PermissionRule {
+        tool: tool.to_string(),
+        specifier,
+    };
+    let mut rules = Vec::new();
+    for subject in subjects(tool_name, tool_input, cwd) {
+        let suggested = match subject {
+            Subject::Command(command) if !command.is_empty() => {
+                rule("Bash", Some(format!("{}:*", command_prefix(&command))))
+            }
+            Subject::Path(path) => {
+                let family = if EDIT_TOOLS.contains(&tool_name) {
+                    "Edit"
+                } else {
+                    "Read"
+                };
+                let dir = if path.is_dir() {
+                    path.as_path()
+                } else {
+                    path.parent().unwrap_or(&path)
+                };
+                let specifier = match dir.strip_prefix(cwd) {
+                    Ok(relative) if relative.as_os_str().is_empty() => "./**".to_string(),
+                    Ok(relative) => format!("{}/**", relative.to_string_lossy()),
+                    Err(_) => format!("/{}/**", dir.to_string_lossy()),
+                };
+                rule(family, Some(specifier))
+            }
+            Subject::Domain(host) if !host.is_empty() => {
+                rule("WebFetch", Some(format!("domain:{}", host)))
+            }
+            _ => rule(tool_name, None),
+        };
+        if !rules.contains(&suggested) {
+            rules.push(suggested);
+        }
+    }
+    rules
+}
+
+/// The program plus its subcommand, e.g. `git status` for `git status -s`,
+/// `npm run` for `npm run build`.
+fn command_prefix(command: &str) -> String {
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    match words.next() {
+        Some(sub)
+            if !sub.starts_with('-')
+                && sub
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ':') =>
+        {
+            format!("{} {}", program, sub)
+        }
+        _ => program.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(allow: &[&str], deny: &[&str], ask: &[&str]) -> PermissionRules {
+        let mut rules = PermissionRules::default();
+        rules.extend_from_settings(&json!({
+            "permissions": { "allow": allow, "deny": deny, "ask": ask }
+        }));
+        rules
+    }
+
+    fn decision(rules: &PermissionRules, tool: &str, input: Value) -> Option<RuleKind> {
+        rules.evaluate(tool, &input, "/repo").map(|(kind, _)| kind)
+    }
+
+    #[test]
+    fn parses_and_formats_native_syntax() {
+        for text in [
+            "Bash(git status:*)",
+            "Edit(src/**)",
+            "WebFetch(domain:docs.rs)",
+            "mcp__github",
+        ] {
+            let rule: PermissionRule = text.parse().expect("rule should parse");
+            assert_eq!(rule.to_string(), text);
+        }
+        assert!("Bash(".parse::<PermissionRule>().is_err());
+        assert!("Bash()".parse::<PermissionRule>().is_err());
+    }
+
+    #[test]
+    fn bash_prefix_rules_cover_every_subcommand() {
+        let rules = rules(&["Bash(git status:*)", "Bash(git diff:*)"], &[], &[]);
+        let bash = |command: &str| json!({ "command": command });
+        assert_eq!(
+            decision(&rules, "Bash", bash("git status -s")),
+            Some(RuleKind::Allow)
+        );
+        assert_eq!(
+            decision(&rules, "Bash", bash("git status && git diff")),
+            Some(RuleKind::Allow)
+        );
+        assert_eq!(
+            decision(&rules, "Bash", bash("git status && rm -rf /")),
+            None
+        );
+        assert_eq!(decision(&rules, "Bash", bash("git statusx")), None);
+    }
+
+    #[test]
+    fn substitutions_and_redirects_are_never_allowed() {
+        let rules = rules(&["Bash(echo:*)", "Bash(cat:*)", "Bash"], &[], &[]);
+        let bash = |command: &str| json!({ "command": command });
+        assert_eq!(
+            decision(&rules, "Bash", bash("echo hello")),
+            Some(RuleKind::Allow)
+        );
+        for command in [
+            "echo $(rm -rf x)",
+            "echo `rm -rf x`",
+            "cat <(curl https://example.com)",
+            "echo hi > ~/.bashrc",
+            "echo hi >> ~/.bashrc",
+            "ls && echo hi>out.txt",
+        ] {
+            assert_eq!(decision(&rules, "Bash", bash(command)), None, "{}", command);
+        }
+    }
+
+    #[test]
+    fn substitutions_still_match_deny_rules() {
+        let rules = rules(&["Bash(echo:*)"], &["Bash(echo:*)"], &[]);
+        assert_eq!(
+            decision(&rules, "Bash", json!({ "command": "echo $(id)" })),
+            Some(RuleKind::Deny)
+        );
+    }
+
+    #[test]
+    fn deny_rules_see_through_assignments_wrappers_and_paths() {
+        let rules = rules(&["Bash"], &["Bash(rm:*)"], &["Bash(git push:*)"]);
+        let bash = |command: &str| json!({ "command": command });
+        for command in [
+            "FOO=1 rm -rf x",
+            "FOO=1 BAR=2 rm -rf x",
+            "sudo rm -rf x",
+            "sudo -u root rm -rf x",
+            "env rm -rf x",
+            "env FOO=1 rm -rf x",
+            "command rm -rf x",
+            "nohup nice -n 5 rm -rf x",
+            "/bin/rm -rf x",
+            "sudo /usr/bin/rm -rf x",
+        ] {
+            assert_eq!(
+                decision(&rules, "Bash", bash(command)),
+                Some(RuleKind::Deny),
+                "{}",
+                command
+            );
+        }
+        assert_eq!(
+            decision(&rules, "Bash", bash("GIT_TRACE=1 git push origin")),
+            Some(RuleKind::Ask)
+        );
+        assert_eq!(
+            decision(&rules, "Bash", bash("FOO=rm ls")),
+            Some(RuleKind::Allow)
+        );
+    }
+
+    #[test]
+    fn deny_rules_match_commands_inside_substitutions() {
+        let rules = rules(&["Bash"], &["Bash(rm:*)"], &["Bash(curl:*)"]);
+        let bash = |command: &str| json!({ "command": command });
+        for command in [
+            "echo $(rm -rf x)",
+            "echo `rm -rf x`",
+            "echo $(echo $(sudo rm -rf x))",
+            "diff <(rm -rf x) y",
+            "echo $(ls; rm -rf x)",
+        ] {
+            assert_eq!(
+                decision(&rules, "Bash", bash(command)),
+                Some(RuleKind::Deny),
+                "{}",
+                command
+            );
+        }
+        assert_eq!(
+            decision(&rules, "Bash", bash("cat <(curl https://example.com)")),
+            Some(RuleKind::Ask)
+        );
+    }
+
+    #[test]
+    fn deny_wins_over_ask_and_allow() {
+        let rules = rules(&["Bash"], &["Bash(rm:*)"], &["Bash(git push:*)"]);
+        let bash = |command: &str| json!({ "command": command });
+        assert_eq!(
+            decision(&rules, "Bash", bash("ls; rm -rf x")),
+            Some(RuleKind::Deny)
+        );
+        assert_eq!(
+            decision(&rules, "Bash", bash("git push origin")),
+            Some(RuleKind::Ask)
+        );
+        assert_eq!(decision(&rules, "Bash", bash("ls")), Some(RuleKind::Allow));
+    }
+
+    #[test]
+    fn path_rules_resolve_against_project_and_normalize() {
+        let rules = rules(&["Edit(src/**)"], &["Read(//etc/**)"], &[]);
+        let file = |path: &str| json!({ "file_path": path });
+        assert_eq!(
+            decision(&rules, "Write", file("/repo/src/a/b.rs")),
+            Some(RuleKind::Allow)
+        );
+        assert_eq!(
+            decision(&rules, "Edit", file("src/main.rs")),
+            Some(RuleKind::Allow)
+        );
+        assert_eq!(decision(&rules, "Edit", file("/repo/src/../.env")), None);
+        assert_eq!(
+            decision(&rules, "Grep", json!({ "path": "/etc/ssl" })),
+            Some(RuleKind::Deny)
+        );
+    }
+
+    #[test]
+    fn webfetch_and_mcp_rules() {
+        let rules = rules(&["WebFetch(domain:docs.rs)", "mcp__github"], &[], &[]);
+        let fetch = |url: &str| json!({ "url": url });
+        assert_eq!(
+            decision(&rules, "WebFetch", fetch("https://docs.rs/serde")),
+            Some(RuleKind::Allow)
+        );
+        assert_eq!(
+            decision(&rules, "WebFetch", fetch("https://evil.rs/docs.rs")),
+            None
+        );
+        assert_eq!(
+            decision(&rules, "mcp__github__create_issue", json!({})),
+            Some(RuleKind::Allow)
+        );
+        assert_eq!(
+            decision(&rules, "mcp__githubx__create_issue", json!({})),
+            None
+        );
+    }
+
+    #[test]
+    fn suggests_scoped_rules() {
+        let suggested = |tool: &str, input: Value| -> Vec<String> {
+            suggest_rules(tool, &input, "/repo")
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+        assert_eq!(
+            suggested(
+                "Bash",
+                json!({ "command": "git status -s && npm run build" })
+            ),
+            ["Bash(git status:*)", "Bash(npm run:*)"]
+        );
+        assert_eq!(
+            suggested("Write", json!({ "file_path": "/repo/src/lib.rs" })),
+            ["Edit(src/**)"]
+        );
+        assert_eq!(
+            suggested("WebFetch", json!({ "url": "https://Docs.rs/x" })),
+            ["WebFetch(domain:docs.rs)"]
+        );
+    }
+}
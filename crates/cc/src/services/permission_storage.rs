@@ -1,8 +1,19 @@
+use super::permission_rules::{PermissionRule, RuleKind};
+use codexia_shared::config_backup;
+use serde::Serialize;
 use std::fs;
 use std::path::PathBuf;
 
 const SETTINGS_FILE: &str = ".claude/settings.json";
 
+/// The `permissions` rule lists of a project's `.claude/settings.json`.
+#[derive(Debug, Default, Serialize)]
+pub struct ProjectPermissionRules {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+    pub ask: Vec<String>,
+}
+
 fn read_settings(path: &PathBuf) -> Result<serde_json::Value, String> {
     if !path.exists() {
         return Ok(serde_json::json!({}));
@@ -11,9 +22,70 @@ fn read_settings(path: &PathBuf) -> Result<serde_json::Value, String> {
     serde_json::from_str(&content).map_err(|e| e.to_string())
 }
 
-/// Appends `tool_name` to the `permissions.allow` list in `{cwd}/.claude/settings.json`.
+pub fn get_project_rules(cwd: &str) -> Result<ProjectPermissionRules, String> {
+    let settings = read_settings(&PathBuf::from(cwd).join(SETTINGS_FILE))?;
+    let list = |kind: RuleKind| -> Vec<String> {
+        settings
+            .pointer(&format!("/permissions/{}", kind.key()))
+            .and_then(|v| v.as_array())
+            .map(|rules| {
+                rules
+                    .iter()
+                    .filter_map(|rule| rule.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Ok(ProjectPermissionRules {
+        allow: list(RuleKind::Allow),
+        deny: list(RuleKind::Deny),
+        ask: list(RuleKind::Ask),
+    })
+}
+
+/// Appends `rule` (e.g. `Bash(git status:*)` or a bare tool name) to the
+/// `permissions.allow` list in `{cwd}/.claude/settings.json`.
+pub fn add_project_allow_rule(cwd: &str, rule: &str) -> Result<(), String> {
+    add_project_rule(cwd, RuleKind::Allow, rule)
+}
+
+/// Appends `rule` to `permissions.{allow,deny,ask}` in `{cwd}/.claude/settings.json`.
 /// Creates the file and intermediate directories if they don't exist.
-pub fn add_project_allow_rule(cwd: &str, tool_name: &str) -> Result<(), String> {
+pub fn add_project_rule(cwd: &str, kind: RuleKind, rule: &str) -> Result<(), String> {
+    let rule = rule.parse::<PermissionRule>()?.to_string();
+    update_rules(cwd, kind, |rules| {
+        let value = serde_json::Value::String(rule.clone());
+        if !rules.contains(&value) {
+            rules.push(value);
+        }
+    })?;
+    log::info!(
+        "[permission_storage] Added '{}' to project {} rules in {}",
+        rule,
+        kind.key(),
+        cwd
+    );
+    Ok(())
+}
+
+pub fn remove_project_rule(cwd: &str, kind: RuleKind, rule: &str) -> Result<(), String> {
+    let mut found = false;
+    update_rules(cwd, kind, |rules| {
+        let before = rules.len();
+        rules.retain(|existing| existing.as_str() != Some(rule));
+        found = rules.len() != before;
+    })?;
+    if !found {
+        return Err(format!("Rule '{}' not found in {} rules", rule, kind.key()));
+    }
+    Ok(())
+}
+
+fn update_rules(
+    cwd: &str,
+    kind: RuleKind,
+    update: impl FnOnce(&mut Vec<serde_json::Value>),
+) -> Result<(), String> {
     let settings_path = PathBuf::from(cwd).join(SETTINGS_FILE);
 
     if let Some(parent) = settings_path.parent() {
@@ -39,28 +111,18 @@ pub fn add_project_allow_rule(cwd: &str, tool_name: &str) -> Result<(), String>
 
         let perm_obj = permissions.as_object_mut().unwrap();
 
-        let allow = perm_obj
-            .entry("allow")
+        let rules = perm_obj
+            .entry(kind.key())
             .or_insert_with(|| serde_json::json!([]));
 
-        if !allow.is_array() {
-            *allow = serde_json::json!([]);
+        if !rules.is_array() {
+            *rules = serde_json::json!([]);
         }
 
-        let allow_arr = allow.as_array_mut().unwrap();
-        let rule = serde_json::Value::String(tool_name.to_string());
-        if !allow_arr.contains(&rule) {
-            allow_arr.push(rule);
-        }
+        update(rules.as_array_mut().unwrap());
     }
 
     let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, content).map_err(|e| e.to_string())?;
-
-    log::info!(
-        "[permission_storage] Added '{}' to project allow rules at {:?}",
-        tool_name,
-        settings_path
-    );
+    config_backup::write_with_backup(&settings_path, &content)?;
     Ok(())
 }
@@ -0,0 +1,350 @@
+//! Sensitive-path policy for the Claude permission hook.
+//!
+//! Any tool call touching a path that matches the policy (Read, Edit, Write,
+//! Grep, Glob, ... and paths named in Bash commands) prompts in every mode but
+//! `bypassPermissions`, whatever session allowances or allow rules say. The
+//! decision is recorded in the approval audit log with the matched pattern.
+//!
+//! The policy lives in `~/.codexia/cc_sensitive_paths.json`. A pattern without
+//! `/` is matched against the file name (`.env.*`, `*.pem`); one with `/` is
+//! matched against the absolute path, with `~/` expanding to the home directory
+//! (`~/.ssh/**`). Matching is case-insensitive.
+
+use super::permission_rules::{absolute_path, split_command};
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const POLICY_FILE: &str = "cc_sensitive_paths.json";
+
+/// Tools whose path argument is checked against the policy.
+const PATH_TOOLS: &[&str] = &[
+    "Read",
+    "Edit",
+    "Write",
+    "MultiEdit",
+    "NotebookEdit",
+    "Grep",
+    "Glob",
+    "LS",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensitivePathPolicy {
+    #[serde(default = "default_patterns")]
+    pub patterns: Vec<String>,
+}
+
+impl Default for SensitivePathPolicy {
+    fn default() -> Self {
+        Self {
+            patterns: default_patterns(),
+        }
+    }
+}
+
+fn default_patterns() -> Vec<String> {
+    [
+        ".env",
+        ".env.*",
+        "id_rsa",
+        "id_ed25519",
+        "id_ecdsa",
+        "id_dsa",
+        "*.pem",
+        "*.key",
+        "*.p12",
+        "*.pfx",
+        "*.secret",
+        "~/.ssh/**",
+        "~/.aws/credentials",
+        "~/.codexia/secrets.*",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect()
+}
+
+/// A tool call argument that matched the policy.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensitiveMatch {
+    pub path: String,
+    pub pattern: String,
+}
+
+fn codexia_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".codexia"))
+}
+
+pub fn read_policy() -> Result<SensitivePathPolicy, String> {
+    let path = codexia_dir()?.join(POLICY_FILE);
+    if !path.exists() {
+        return Ok(SensitivePathPolicy::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+pub fn write_policy(policy: SensitivePathPolicy) -> Result<(), String> {
+    let mut patterns = Vec::new();
+    for pattern in policy.patterns {
+        let pattern = pattern.trim().to_string();
+        if pattern.is_empty() || patterns.contains(&pattern) {
+            continue;
+        }
+        Pattern::new(&pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+        patterns.push(pattern);
+    }
+
+    let dir = codexia_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let content = serde_json::to_string_pretty(&SensitivePathPolicy { patterns })
+        .map_err(|e| format!("Failed to serialize sensitive path policy: {}", e))?;
+    let path = dir.join(POLICY_FILE);
+    codexia_shared::config_backup::write_private(&path, content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+impl SensitivePathPolicy {
+    /// The first path argument of the call that matches the policy.
+    pub fn check(&self, tool_name: &str, tool_input: &Value, cwd: &str) -> Option<SensitiveMatch> {
+        let field = |key: &str| tool_input.get(key).and_then(Value::as_str);
+        if tool_name == "Bash" {
+            // Arguments resolve against the latest `cd` target, so
+            // `cd ~/.aws && cat credentials` reads `~/.aws/credentials`.
+            let mut dir = cwd.to_string();
+            for part in split_command(field("command").unwrap_or_default()) {
+                let args = command_args(&part);
+                if part.split_whitespace().next() == Some("cd") {
+                    let target = expand_home(args.first().map_or("~", String::as_str));
+                    dir = absolute_path(&target, &dir).to_string_lossy().to_string();
+                }
+                let found = args
+                    .iter()
+                    .find_map(|arg| self.match_path(&absolute_path(arg, &dir)));
+                if found.is_some() {
+                    return found;
+                }
+            }
+            return None;
+        }
+        if !PATH_TOOLS.contains(&tool_name) {
+            return None;
+        }
+
+        let target = field("file_path")
+            .or_else(|| field("notebook_path"))
+            .or_else(|| field("path"));
+        if let Some(found) = target.and_then(|path| self.match_path(&absolute_path(path, cwd))) {
+            return Some(found);
+        }
+        // Glob's `pattern` and Grep's `glob` select files by name.
+        let name_glob = match tool_name {
+            "Glob" => field("pattern"),
+            "Grep" => field("glob"),
+            _ => None,
+        };
+        name_glob.and_then(|glob| self.match_glob(glob, target.unwrap_or(cwd), cwd))
+    }
+
+    fn match_path(&self, path: &Path) -> Option<SensitiveMatch> {
+        let file_name = path.file_name()?.to_string_lossy().to_string();
+        self.patterns.iter().find_map(|pattern| {
+            let matched = if pattern.contains('/') {
+                compile(&expand_home(pattern))
+                    .is_some_and(|compiled| compiled.matches_path_with(path, options()))
+            } else {
+                compile(pattern)
+                    .is_some_and(|compiled| compiled.matches_with(&file_name, options()))
+            };
+            matched.then(|| SensitiveMatch {
+                path: path.to_string_lossy().to_string(),
+                pattern: pattern.clone(),
+            })
+        })
+    }
+
+    /// Whether a file-selecting glob could pick up a sensitive file: the glob is
+    /// resolved under `base` and tested against a sample name for each pattern
+    /// (`*.pem` -> `x.pem`).
+    fn match_glob(&self, glob: &str, base: &str, cwd: &str) -> Option<SensitiveMatch> {
+        let resolved = absolute_path(glob, &absolute_path(base, cwd).to_string_lossy())
+            .to_string_lossy()
+            .to_string();
+        let compiled_glob = compile(&resolved)?;
+        let glob_name = Path::new(&resolved)
+            .file_name()?
+            .to_string_lossy()
+            .to_string();
+        let compiled_name = compile(&glob_name)?;
+        self.patterns.iter().find_map(|pattern| {
+            let matched = if pattern.contains('/') {
+                let sample = expand_home(pattern).replace("**", "x").replace('*', "x");
+                compiled_glob.matches_path_with(Path::new(&sample), options())
+            } else {
+                compiled_name.matches_with(&pattern.replace('*', "x"), options())
+            };
+            matched.then(|| SensitiveMatch {
+                path: resolved.clone(),
+                pattern: pattern.clone(),
+            })
+        })
+    }
+}
+
+/// The arguments of a shell command that could name files: every word after
+/// the program but flags, with quotes, redirections and `--flag=` prefixes
+/// removed. Bare names count too, so `cat id_rsa` is checked against `id_rsa`.
+fn command_args(command: &str) -> Vec<String> {
+    command
+        .split_whitespace()
+        .skip(1)
+        .map(|word| {
+            let word = word.trim_matches(|c| matches!(c, '"' | '\'' | '`' | '(' | ')'));
+            let word =
+                word.trim_start_matches(|c: char| c.is_ascii_digit() || c == '<' || c == '>');
+            word.rsplit_once('=').map_or(word, |(_, value)| value)
+        })
+        .filter(|word| !word.is_empty() && !word.starts_with('-'))
+        .map(expand_home)
+        .collect()
+}
+
+fn expand_home(path: &str) -> String {
+    let Some(home) = dirs::home_dir() else {
+        return path.to_string();
+    };
+    if path == "~" {
+        return home.to_string_lossy().to_string();
+    }
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("{}/{}", home.to_string_lossy(), rest),
+        None => path.to_string(),
+    }
+}
+
+fn compile(pattern: &str) -> Option<Pattern> {
+    match Pattern::new(&pattern.to_lowercase()) {
+        Ok(pattern) => Some(pattern),
+        Err(e) => {
+            log::warn!("[sensitive paths] Invalid pattern '{}': {}", pattern, e);
+            None
+        }
+    }
+}
+
+fn options() -> MatchOptions {
+    MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    }
+}
+
+/// The policy match for a call, using the stored policy (or the defaults if it
+/// cannot be read).
+pub fn check(tool_name: &str, tool_input: &Value, cwd: &str) -> Option<SensitiveMatch> {
+    let policy = read_policy().unwrap_or_else(|e| {
+        log::warn!("[sensitive paths] {}", e);
+        SensitivePathPolicy::default()
+    });
+    policy.check(tool_name, tool_input, cwd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn check(tool: &str, input: Value) -> Option<String> {
+        SensitivePathPolicy::default()
+            .check(tool, &input, "/repo")
+            .map(|found| found.pattern)
+    }
+
+    #[test]
+    fn matches_path_arguments_of_file_tools() {
+        assert_eq!(
+            check("Read", json!({ "file_path": "/repo/.env.local" })).as_deref(),
+            Some(".env.*")
+        );
+        assert_eq!(
+            check("Write", json!({ "file_path": "certs/Server.PEM" })).as_deref(),
+            Some("*.pem")
+        );
+        assert_eq!(
+            check("Grep", json!({ "pattern": "KEY", "path": ".env" })).as_deref(),
+            Some(".env")
+        );
+        assert_eq!(
+            check("Edit", json!({ "file_path": "/repo/src/env.rs" })),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_file_selecting_globs() {
+        assert_eq!(
+            check("Glob", json!({ "pattern": "**/.env*" })).as_deref(),
+            Some(".env")
+        );
+        assert_eq!(
+            check("Grep", json!({ "pattern": "x", "glob": "*.key" })).as_deref(),
+            Some("*.key")
+        );
+        assert_eq!(check("Glob", json!({ "pattern": "src/**/*.rs" })), None);
+    }
+
+    #[test]
+    fn matches_paths_named_in_bash_commands() {
+        assert_eq!(
+            check("Bash", json!({ "command": "cat .env" })).as_deref(),
+            Some(".env")
+        );
+        assert_eq!(
+            check(
+                "Bash",
+                json!({ "command": "ls && grep TOKEN < config/.env.prod" })
+            )
+            .as_deref(),
+            Some(".env.*")
+        );
+        assert_eq!(
+            check("Bash", json!({ "command": "cargo build --release" })),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_bare_names_and_cd_targets_in_bash_commands() {
+        assert_eq!(
+            check("Bash", json!({ "command": "cat id_rsa" })).as_deref(),
+            Some("id_rsa")
+        );
+        assert_eq!(
+            check("Bash", json!({ "command": "cp id_ed25519 /tmp/k" })).as_deref(),
+            Some("id_ed25519")
+        );
+        assert_eq!(
+            check("Bash", json!({ "command": "cd ~/.aws && cat credentials" })).as_deref(),
+            Some("~/.aws/credentials")
+        );
+        assert_eq!(
+            check(
+                "Bash",
+                json!({ "command": "cd ~ && cd .aws; cat credentials" })
+            )
+            .as_deref(),
+            Some("~/.aws/credentials")
+        );
+        assert_eq!(
+            check("Bash", json!({ "command": "cd src && cat credentials" })),
+            None
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! Idle reaping and the live-session cap for Claude clients.
+//!
+//! Every live session holds a Claude CLI subprocess. [`run_session_reaper`]
+//! disconnects sessions idle past `idle_timeout_secs`, and creating a client
+//! beyond `max_live_sessions` first disconnects the least recently active
+//! ones. A reaped session keeps its options and CLI session id, so the next
+//! message sent to it resumes the conversation transparently. Session-scoped
+//! "always allow" answers do not survive a resume.
+//!
+//! The limits live in `~/.codexia/cc_session_limits.json`, together with the
+//! default permission prompt timeout.
+
+use super::session_service;
+use crate::state::CCState;
+use crate::types::PermissionTimeout;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const LIMITS_FILE: &str = "cc_session_limits.json";
+const REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLimits {
+    /// Disconnect sessions idle this long. 0 disables reaping.
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+    /// Most sessions with a running CLI process. 0 means no cap.
+    #[serde(default = "default_max_live_sessions")]
+    pub max_live_sessions: usize,
+    /// Prompt deadline for sessions whose options set none, so a headless
+    /// server does not wait forever on an unanswered prompt.
+    #[serde(default)]
+    pub permission_timeout: PermissionTimeout,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            idle_timeout_secs: default_idle_timeout_secs(),
+            max_live_sessions: default_max_live_sessions(),
+            permission_timeout: PermissionTimeout::default(),
+        }
+    }
+}
+
+fn default_idle_timeout_secs() -> u64 {
+    30 * 60
+}
+
+fn default_max_live_sessions() -> usize {
+    8
+}
+
+fn codexia_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".codexia"))
+}
+
+pub fn read_limits() -> Result<SessionLimits, String> {
+    let path = codexia_dir()?.join(LIMITS_FILE);
+    if !path.exists() {
+        return Ok(SessionLimits::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+pub fn write_limits(limits: SessionLimits) -> Result<(), String> {
+    if limits.permission_timeout.timeout_secs == Some(0) {
+        return Err("permissionTimeout.timeoutSecs must be at least 1".to_string());
+    }
+    let dir = codexia_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let content = serde_json::to_string_pretty(&limits)
+        .map_err(|e| format!("Failed to serialize session limits: {}", e))?;
+    let path = dir.join(LIMITS_FILE);
+    codexia_shared::config_backup::write_private(&path, content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// A live session as seen by the reaper.
+#[derive(Debug, Clone)]
+struct LiveSession {
+    session_id: String,
+    last_active_at: i64,
+    /// Sending, queued or waiting on a permission prompt; never reaped.
+    busy: bool,
+}
+
+async fn live_sessions(state: &CCState) -> Vec<LiveSession> {
+    let ids: Vec<String> = state.clients.lock().await.keys().cloned().collect();
+    let prompting: HashSet<String> = state
+        .list_pending_permissions(None)
+        .iter()
+        .filter_map(|request| request["sessionId"].as_str().map(str::to_string))
+        .collect();
+    ids.into_iter()
+        .map(|session_id| LiveSession {
+            last_active_at: state
+                .session_metadata
+                .get(&session_id)
+                .map(|meta| meta.last_active_at)
+                .unwrap_or_default(),
+            busy: state.is_session_busy(&session_id) || prompting.contains(&session_id),
+            session_id,
+        })
+        .collect()
+}
+
+/// Sessions idle for at least `idle_timeout_secs` at `now` (Unix milliseconds).
+fn idle_sessions(sessions: &[LiveSession], now: i64, idle_timeout_secs: u64) -> Vec<String> {
+    if idle_timeout_secs == 0 {
+        return Vec::new();
+    }
+    let cutoff = now.saturating_sub((idle_timeout_secs as i64).saturating_mul(1000));
+    sessions
+        .iter()
+        .filter(|session| !session.busy && session.last_active_at <= cutoff)
+        .map(|session| session.session_id.clone())
+        .collect()
+}
+
+/// Least recently active sessions to disconnect so one more fits under
+/// `max_live_sessions`. Errors when too many of them are busy.
+fn lru_evictions(sessions: &[LiveSession], max_live_sessions: usize) -> Result<Vec<String>, String> {
+    if max_live_sessions == 0 || sessions.len() < max_live_sessions {
+        return Ok(Vec::new());
+    }
+    let needed = sessions.len() + 1 - max_live_sessions;
+    let mut idle: Vec<&LiveSession> = sessions.iter().filter(|session| !session.busy).collect();
+    if idle.len() < needed {
+        return Err(format!(
+            "Live session limit of {} reached and every session is busy",
+            max_live_sessions
+        ));
+    }
+    idle.sort_by_key(|session| session.last_active_at);
+    Ok(idle.into_iter().take(needed).map(|session| session.session_id.clone()).collect())
+}
+
+pub(crate) async fn hibernate(session_id: &str, reason: &str, state: &CCState) {
+    match state.hibernate_client(session_id).await {
+        Ok(true) => {
+            log::info!("[CC lifecycle] Disconnected session {} ({})", session_id, reason);
+            state.emit("cc-session-dormant", json!({ "sessionId": session_id, "reason": reason }));
+        }
+        Ok(false) => {}
+        Err(e) => log::warn!("[CC lifecycle] Failed to disconnect session {}: {}", session_id, e),
+    }
+}
+
+/// Make room for a client about to be created for `session_id` by evicting
+/// the least recently active sessions over the cap.
+pub(crate) async fn make_room(session_id: &str, state: &CCState) -> Result<(), String> {
+    let limits = read_limits().unwrap_or_else(|e| {
+        log::warn!("[CC lifecycle] {}; using default limits", e);
+        SessionLimits::default()
+    });
+    // Replacing an existing client does not add a process.
+    let sessions: Vec<LiveSession> = live_sessions(state)
+        .await
+        .into_iter()
+        .filter(|session| session.session_id != session_id)
+        .collect();
+    for evicted in lru_evictions(&sessions, limits.max_live_sessions)? {
+        hibernate(&evicted, "evicted", state).await;
+    }
+    Ok(())
+}
+
+/// Disconnect every session idle past the timeout. Returns the reaped ids.
+pub async fn reap_idle_sessions(state: &CCState, limits: &SessionLimits) -> Vec<String> {
+    let sessions = live_sessions(state).await;
+    let now = chrono::Utc::now().timestamp_millis();
+    let idle = idle_sessions(&sessions, now, limits.idle_timeout_secs);
+    for session_id in &idle {
+        hibernate(session_id, "idle", state).await;
+    }
+    idle
+}
+
+/// Reap idle sessions once a minute, for as long as the app runs.
+pub async fn run_session_reaper(state: CCState) {
+    let mut interval = tokio::time::interval(REAP_INTERVAL);
+    loop {
+        interval.tick().await;
+        match read_limits() {
+            Ok(limits) => {
+                reap_idle_sessions(&state, &limits).await;
+            }
+            Err(e) => log::warn!("[CC lifecycle] Skipping reap: {}", e),
+        }
+    }
+}
+
+/// Recreate the client of a dormant session. A live session is left as is.
+///
+/// Concurrent sends to the same dormant session wait on one resume. The
+/// dormant entry stays until the new client is registered, so a send never
+/// finds the session in neither map.
+pub(crate) async fn ensure_live(session_id: &str, state: &CCState) -> Result<(), String> {
+    if state.get_client(session_id).await.is_some() {
+        return Ok(());
+    }
+    let lock = state
+        .resume_locks
+        .entry(session_id.to_string())
+        .or_default()
+        .clone();
+    let _resuming = lock.lock().await;
+    if state.get_client(session_id).await.is_some() {
+        return Ok(());
+    }
+    let options = state
+        .dormant_sessions
+        .get(session_id)
+        .map(|dormant| dormant.options.clone())
+        .ok_or("Client not found")?;
+    log::info!("[CC lifecycle] Resuming dormant session {}", session_id);
+    // `create_client` drops the dormant entry once the client is in place.
+    session_service::create_session_client(session_id, &options, None, state)
+        .await
+        .map_err(|e| format!("Failed to resume session {}: {}", session_id, e))?;
+    state.emit("cc-session-resumed", json!({ "sessionId": session_id }));
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStatus {
+    pub session_id: String,
+    /// False for a dormant session.
+    pub live: bool,
+    pub busy: bool,
+    pub queued: usize,
+    pub cwd: String,
+    /// Unix milliseconds.
+    pub last_active_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CCStatus {
+    pub live_sessions: usize,
+    pub dormant_sessions: usize,
+    pub busy_sessions: usize,
+    pub pending_permissions: usize,
+    /// Claude CLI processes started by this app; None where `ps` is unavailable.
+    pub claude_processes: Option<usize>,
+    /// Resident memory of those processes.
+    pub claude_rss_bytes: Option<u64>,
+    /// Resident memory of this app's own process.
+    pub app_rss_bytes: Option<u64>,
+    pub limits: SessionLimits,
+    pub sessions: Vec<SessionStatus>,
+}
+
+pub async fn status(state: &CCState) -> CCStatus {
+    let live = live_sessions(state).await;
+    let mut sessions: Vec<SessionStatus> = live
+        .iter()
+        .map(|session| SessionStatus {
+            session_id: session.session_id.clone(),
+            live: true,
+            busy: session.busy,
+            queued: state.list_queued_messages(&session.session_id).len(),
+            cwd: state
+                .session_metadata
+                .get(&session.session_id)
+                .map(|meta| meta.options.cwd.clone())
+                .unwrap_or_default(),
+            last_active_at: session.last_active_at,
+        })
+        .collect();
+    sessions.extend(state.dormant_sessions.iter().map(|entry| SessionStatus {
+        session_id: entry.key().clone(),
+        live: false,
+        busy: false,
+        queued: state.list_queued_messages(entry.key()).len(),
+        cwd: entry.options.cwd.clone(),
+        last_active_at: entry.last_active_at,
+    }));
+    sessions.sort_by_key(|session| std::cmp::Reverse(session.last_active_at));
+
+    // `ps` can take a while on a busy machine.
+    let processes = tokio::task::spawn_blocking(process_table)
+        .await
+        .ok()
+        .flatten();
+    let own_pid = std::process::id();
+    let claude = processes.as_ref().map(|table| claude_processes(table, own_pid));
+
+    CCStatus {
+        live_sessions: live.len(),
+        dormant_sessions: state.dormant_sessions.len(),
+        busy_sessions: live.iter().filter(|session| session.busy).count(),
+        pending_permissions: state.pending_permissions.len(),
+        claude_processes: claude.as_ref().map(Vec::len),
+        claude_rss_bytes: claude.map(|found| found.iter().map(|process| process.rss_bytes).sum()),
+        app_rss_bytes: processes
+            .as_ref()
+            .and_then(|table| table.get(&own_pid))
+            .map(|process| process.rss_bytes),
+        limits: read_limits().unwrap_or_default(),
+        sessions,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ProcessInfo {
+    pid: u32,
+    ppid: u32,
+    rss_bytes: u64,
+    args: String,
+}
+
+#[cfg(unix)]
+fn process_table() -> Option<HashMap<u32, ProcessInfo>> {
+    let output = std::process::Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid=,rss=,args="])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| parse_ps(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(unix))]
+fn process_table() -> Option<HashMap<u32, ProcessInfo>> {
+    None
+}
+
+/// Parse `ps -o pid=,ppid=,rss=,args=` output; rss is in KiB.
+fn parse_ps(output: &str) -> HashMap<u32, ProcessInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let ppid = fields.next()?.parse().ok()?;
+            let rss_kib: u64 = fields.next()?.parse().ok()?;
+            let args = fields.collect::<Vec<_>>().join(" ");
+            Some((pid, ProcessInfo { pid, ppid, rss_bytes: rss_kib * 1024, args }))
+        })
+        .collect()
+}
+
+/// Descendants of `root` whose command line mentions claude.
+fn claude_processes(table: &HashMap<u32, ProcessInfo>, root: u32) -> Vec<ProcessInfo> {
+    let is_descendant = |process: &ProcessInfo| {
+        let mut ppid = process.ppid;
+        // Bounded walk in case of a pid cycle from a racing ps snapshot.
+        for _ in 0..table.len() {
+            if ppid == root {
+                return true;
+            }
+            match table.get(&ppid) {
+                Some(parent) if parent.pid != parent.ppid => ppid = parent.ppid,
+                _ => return false,
+            }
+        }
+        false
+    };
+    table
+        .values()
+        .filter(|process| process.args.to_lowercase().contains("claude") && is_descendant(process))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, last_active_at: i64, busy: bool) -> LiveSession {
+        LiveSession { session_id: id.to_string(), last_active_at, busy }
+    }
+
+    #[test]
+    fn idle_sessions_skip_busy_and_recent() {
+        let sessions = [
+            session("old", 0, false),
+            session("busy", 0, true),
+            session("recent", 59_000, false),
+        ];
+        assert_eq!(idle_sessions(&sessions, 60_000, 60), ["old"]);
+        assert!(idle_sessions(&sessions, 60_000, 0).is_empty());
+    }
+
+    #[test]
+    fn lru_evicts_oldest_idle_sessions() {
+        let sessions = [
+            session("a", 30, false),
+            session("b", 10, true),
+            session("c", 20, false),
+        ];
+        assert!(lru_evictions(&sessions, 4).unwrap().is_empty());
+        assert_eq!(lru_evictions(&sessions, 3).unwrap(), ["c"]);
+        assert_eq!(lru_evictions(&sessions, 2).unwrap(), ["c", "a"]);
+        assert!(lru_evictions(&sessions, 1).is_err());
+        assert!(lru_evictions(&sessions, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn claude_processes_are_descendants_only() {
+        let table = parse_ps(
+            "  100     1  2000 /app/codexia\n\
+               200   100  1000 node /usr/bin/claude --output-format stream-json\n\
+               300   200   500 claude-mcp-helper\n\
+               400     1  9000 claude --print\n\
+               bad line\n",
+        );
+        assert_eq!(table.len(), 4);
+        let mut found: Vec<u32> = claude_processes(&table, 100).iter().map(|p| p.pid).collect();
+        found.sort();
+        assert_eq!(found, [200, 300]);
+        assert_eq!(table[&200].rss_bytes, 1000 * 1024);
+    }
+}
@@ -1,3 +1,4 @@
+use codexia_shared::config_backup;
 use std::fs;
 
 pub fn get_settings() -> Result<serde_json::Value, String> {
@@ -40,8 +41,7 @@ pub fn update_settings(settings: serde_json::Value) -> Result<(), String> {
     let content = serde_json::to_string_pretty(&settings)
         .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-    fs::write(&settings_path, content)
-        .map_err(|e| format!("Failed to write settings.json: {}", e))?;
+    config_backup::write_with_backup(&settings_path, &content)?;
 
     Ok(())
 }
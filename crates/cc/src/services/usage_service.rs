@@ -0,0 +1,248 @@
+//! Live token usage and cost for Claude sessions.
+//!
+//! Every message of a session is fed to its [`UsageTracker`] as JSON. Usage is
+//! keyed by API message id so the same call reported by a `message_start`
+//! stream event, later `message_delta` events and the final assistant message
+//! is only counted once. The `result` message closing a query carries the CLI's
+//! own usage and cost, which replace the running estimate for that query. The
+//! cost is cumulative over the CLI process, so only its growth since the
+//! previous result is added.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Cumulative usage of a session, emitted as `cc-usage`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub total_cost_usd: f64,
+    /// True while a query is running and its cost is estimated from token counts.
+    pub cost_estimated: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct TokenUsage {
+    input: u64,
+    output: u64,
+    cache_creation: u64,
+    cache_read: u64,
+}
+
+impl TokenUsage {
+    fn from_json(usage: &Value) -> Self {
+        let field = |name: &str| usage.get(name).and_then(Value::as_u64).unwrap_or(0);
+        Self {
+            input: field("input_tokens"),
+            output: field("output_tokens"),
+            cache_creation: field("cache_creation_input_tokens"),
+            cache_read: field("cache_read_input_tokens"),
+        }
+    }
+
+    /// Counts only grow while a message streams, so keep the larger of each.
+    fn merge(&mut self, other: TokenUsage) {
+        self.input = self.input.max(other.input);
+        self.output = self.output.max(other.output);
+        self.cache_creation = self.cache_creation.max(other.cache_creation);
+        self.cache_read = self.cache_read.max(other.cache_read);
+    }
+
+    fn add(&mut self, other: TokenUsage) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_creation += other.cache_creation;
+        self.cache_read += other.cache_read;
+    }
+
+    /// Rough USD cost at list prices per million tokens for the model family.
+    fn estimated_cost(&self, model: Option<&str>) -> f64 {
+        let model = model.unwrap_or_default();
+        let (input, output) = if model.contains("opus-4-5") || model.contains("opus-4-6") {
+            (5.0, 25.0)
+        } else if model.contains("opus") {
+            (15.0, 75.0)
+        } else if model.contains("haiku") {
+            (1.0, 5.0)
+        } else {
+            (3.0, 15.0)
+        };
+        (self.input as f64 * input
+            + self.output as f64 * output
+            + self.cache_creation as f64 * input * 1.25
+            + self.cache_read as f64 * input * 0.1)
+            / 1_000_000.0
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    committed: TokenUsage,
+    committed_cost: f64,
+    /// `total_cost_usd` of the current CLI process's last result.
+    process_cost: f64,
+    /// API calls of the running query: message id -> (model, usage).
+    current: HashMap<String, (Option<String>, TokenUsage)>,
+    /// Id of the message being streamed; `message_delta` events carry none.
+    streaming_id: Option<String>,
+}
+
+impl UsageTracker {
+    /// Fold one serialized `Message` into the totals. Returns the new snapshot
+    /// when the message changed them.
+    pub fn observe(&mut self, message: &Value) -> Option<SessionUsage> {
+        let before = self.snapshot();
+        match message.get("type").and_then(Value::as_str) {
+            Some("stream_event") => self.observe_stream_event(&message["event"]),
+            Some("assistant") => {
+                let inner = &message["message"];
+                if let (Some(id), Some(usage)) = (inner["id"].as_str(), inner.get("usage")) {
+                    self.update(id, inner["model"].as_str(), TokenUsage::from_json(usage));
+                }
+            }
+            Some("result") => {
+                let mut estimated = TokenUsage::default();
+                for (_, usage) in self.current.values() {
+                    estimated.add(*usage);
+                }
+                let reported = message.get("usage").map(TokenUsage::from_json);
+                let cost = match message["total_cost_usd"].as_f64() {
+                    Some(total) => {
+                        // A total below the last one comes from a new process.
+                        let previous = if total >= self.process_cost { self.process_cost } else { 0.0 };
+                        self.process_cost = total;
+                        total - previous
+                    }
+                    None => self.current_cost(),
+                };
+                self.committed.add(reported.unwrap_or(estimated));
+                self.committed_cost += cost;
+                self.current.clear();
+                self.streaming_id = None;
+            }
+            _ => {}
+        }
+        let after = self.snapshot();
+        (after != before).then_some(after)
+    }
+
+    /// The session got a new CLI process, e.g. resumed after hibernation,
+    /// whose reported cost starts from zero again.
+    pub fn restart_process(&mut self) {
+        self.process_cost = 0.0;
+    }
+
+    fn observe_stream_event(&mut self, event: &Value) {
+        match event.get("type").and_then(Value::as_str) {
+            Some("message_start") => {
+                let inner = &event["message"];
+                let Some(id) = inner["id"].as_str() else { return };
+                self.streaming_id = Some(id.to_string());
+                let usage = inner.get("usage").map(TokenUsage::from_json).unwrap_or_default();
+                self.update(id, inner["model"].as_str(), usage);
+            }
+            Some("message_delta") => {
+                if let (Some(id), Some(usage)) = (self.streaming_id.clone(), event.get("usage")) {
+                    self.update(&id, None, TokenUsage::from_json(usage));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, id: &str, model: Option<&str>, usage: TokenUsage) {
+        let entry = self.current.entry(id.to_string()).or_default();
+        if entry.0.is_none() {
+            entry.0 = model.map(str::to_string);
+        }
+        entry.1.merge(usage);
+    }
+
+    fn current_cost(&self) -> f64 {
+        self.current
+            .values()
+            .map(|(model, usage)| usage.estimated_cost(model.as_deref()))
+            .sum()
+    }
+
+    pub fn snapshot(&self) -> SessionUsage {
+        let mut tokens = self.committed;
+        for (_, usage) in self.current.values() {
+            tokens.add(*usage);
+        }
+        SessionUsage {
+            input_tokens: tokens.input,
+            output_tokens: tokens.output,
+            cache_creation_input_tokens: tokens.cache_creation,
+            cache_read_input_tokens: tokens.cache_read,
+            total_cost_usd: self.committed_cost + self.current_cost(),
+            cost_estimated: !self.current.is_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn stream_events_and_assistant_messages_count_once() {
+        let mut tracker = UsageTracker::default();
+        tracker.observe(&json!({
+            "type": "stream_event",
+            "event": {
+                "type": "message_start",
+                "message": { "id": "m1", "model": "claude-sonnet-4", "usage": { "input_tokens": 100, "output_tokens": 1 } }
+            }
+        }));
+        let live = tracker
+            .observe(&json!({
+                "type": "stream_event",
+                "event": { "type": "message_delta", "usage": { "output_tokens": 40 } }
+            }))
+            .expect("delta changes usage");
+        assert_eq!((live.input_tokens, live.output_tokens), (100, 40));
+        assert!(live.cost_estimated);
+
+        // The complete assistant message repeats the same call's usage.
+        assert!(tracker
+            .observe(&json!({
+                "type": "assistant",
+                "message": { "id": "m1", "model": "claude-sonnet-4", "usage": { "input_tokens": 100, "output_tokens": 40 } }
+            }))
+            .is_none());
+
+        let done = tracker
+            .observe(&json!({
+                "type": "result",
+                "total_cost_usd": 0.5,
+                "usage": { "input_tokens": 120, "output_tokens": 45 }
+            }))
+            .unwrap();
+        assert_eq!((done.input_tokens, done.output_tokens), (120, 45));
+        assert_eq!(done.total_cost_usd, 0.5);
+        assert!(!done.cost_estimated);
+    }
+
+    #[test]
+    fn queries_accumulate_across_results() {
+        let mut tracker = UsageTracker::default();
+        // The CLI reports the cost of the whole process with every result.
+        for total in [0.25, 0.75] {
+            tracker.observe(&json!({ "type": "result", "total_cost_usd": total, "usage": { "input_tokens": 10 } }));
+        }
+        let usage = tracker.snapshot();
+        assert_eq!(usage.input_tokens, 20);
+        assert_eq!(usage.total_cost_usd, 0.75);
+
+        // A resumed session's new process counts from zero.
+        tracker.restart_process();
+        tracker.observe(&json!({ "type": "result", "total_cost_usd": 0.25 }));
+        assert_eq!(tracker.snapshot().total_cost_usd, 1.0);
+    }
+}
@@ -1,8 +1,11 @@
+use crate::services::usage_service::{SessionUsage, UsageTracker};
+use crate::types::{AgentOptions, PermissionTimeout};
 use codexia_shared::event_sink::EventSink;
 use claude_agent_sdk_rs::{ClaudeAgentOptions, ClaudeClient};
 use dashmap::DashMap;
+use serde::Serialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
 use tokio::sync::{oneshot, Mutex as AsyncMutex, RwLock};
 
@@ -11,15 +14,85 @@ pub type ClientId = String;
 /// Session-specific metadata
 pub struct SessionMetadata {
     pub permission_mode: Option<String>,
+    pub permission_timeout: PermissionTimeout,
+    /// Interrupt the session once its cost reaches this many USD.
+    pub budget_guard_usd: Option<f64>,
+    /// Options the client was created with, reused to resume it after reaping.
+    pub options: AgentOptions,
+    /// Claude CLI session id reported by the session's messages.
+    pub sdk_session_id: Option<String>,
+    /// Unix milliseconds of the last message sent or received.
+    pub last_active_at: i64,
+}
+
+impl SessionMetadata {
+    /// Options that recreate this session as it is now: runtime permission
+    /// changes applied and resuming the CLI session once one exists.
+    fn resume_options(&self) -> AgentOptions {
+        let mut options = self.options.clone();
+        options.permission_mode = self.permission_mode.clone();
+        options.permission_timeout_secs = self.permission_timeout.timeout_secs;
+        options.permission_fallback = Some(self.permission_timeout.fallback);
+        if let Some(sdk_session_id) = &self.sdk_session_id {
+            options.resume = Some(sdk_session_id.clone());
+            options.continue_conversation = None;
+        }
+        options
+    }
+}
+
+/// A session whose client was disconnected while idle. The next message sent
+/// to it recreates the client from `options`.
+#[derive(Debug, Clone)]
+pub struct DormantSession {
+    pub options: AgentOptions,
+    /// Unix milliseconds.
+    pub last_active_at: i64,
+    /// Unix milliseconds.
+    pub reaped_at: i64,
+}
+
+/// A permission prompt waiting for a decision.
+pub struct PendingPermission {
+    pub sender: oneshot::Sender<String>,
+    /// The `cc-permission-request` payload, replayed to clients that reconnect.
+    pub request: Value,
+}
+
+/// A message waiting for the session's current exchange to finish.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedMessage {
+    pub id: String,
+    pub message: String,
+    pub image_paths: Vec<String>,
+    /// Unix milliseconds.
+    pub queued_at: i64,
+}
+
+/// Per-session FIFO of messages. `busy` is set while a worker is draining it,
+/// so at most one exchange runs on a `ClaudeClient` at a time.
+#[derive(Default)]
+pub struct MessageQueue {
+    pub busy: bool,
+    pub pending: VecDeque<QueuedMessage>,
 }
 
 #[derive(Clone)]
 pub struct CCState {
     pub clients: Arc<AsyncMutex<HashMap<ClientId, Arc<RwLock<ClaudeClient>>>>>,
     /// Pending permission requests: request_id -> oneshot sender for the decision
-    pub pending_permissions: Arc<DashMap<String, oneshot::Sender<String>>>,
+    pub pending_permissions: Arc<DashMap<String, PendingPermission>>,
     /// Session metadata (permission_mode, etc.)
     pub session_metadata: Arc<DashMap<ClientId, SessionMetadata>>,
+    /// Sessions reaped while idle, resumed on their next message.
+    pub dormant_sessions: Arc<DashMap<ClientId, DormantSession>>,
+    /// Held while a dormant session is resumed, so concurrent sends create one client.
+    pub resume_locks: Arc<DashMap<ClientId, Arc<AsyncMutex<()>>>>,
+    /// Messages waiting to be sent, per session.
+    pub message_queues: Arc<DashMap<ClientId, MessageQueue>>,
+    /// Running token usage and cost per session.
+    pub session_usage: Arc<DashMap<ClientId, UsageTracker>>,
     /// Arc<Mutex<String>> for each session's effective ID, shared with permission hooks.
     pub session_arcs: Arc<DashMap<String, Arc<Mutex<String>>>>,
     /// Event sink for emitting events to the frontend (Tauri or WebSocket).
@@ -37,6 +110,10 @@ impl CCState {
             clients: Arc::new(AsyncMutex::new(HashMap::new())),
             pending_permissions: Arc::new(DashMap::new()),
             session_metadata: Arc::new(DashMap::new()),
+            dormant_sessions: Arc::new(DashMap::new()),
+            resume_locks: Arc::new(DashMap::new()),
+            session_usage: Arc::new(DashMap::new()),
+            message_queues: Arc::new(DashMap::new()),
             session_arcs: Arc::new(DashMap::new()),
             sink,
         }
@@ -55,7 +132,7 @@ impl CCState {
         &self,
         client_id: String,
         options: ClaudeAgentOptions,
-        permission_mode: Option<String>,
+        metadata: SessionMetadata,
     ) -> Result<(), String> {
         let mut clients = self.clients.lock().await;
 
@@ -69,11 +146,14 @@ impl CCState {
         let client = ClaudeClient::new(options);
         clients.insert(client_id.clone(), Arc::new(RwLock::new(client)));
 
+        // The new CLI process reports its cost from zero.
+        if let Some(mut usage) = self.session_usage.get_mut(&client_id) {
+            usage.restart_process();
+        }
+
         // Store session metadata
-        self.session_metadata.insert(
-            client_id,
-            SessionMetadata { permission_mode },
-        );
+        self.dormant_sessions.remove(&client_id);
+        self.session_metadata.insert(client_id, metadata);
 
         Ok(())
     }
@@ -85,10 +165,69 @@ impl CCState {
             client.disconnect().await.map_err(|e| e.to_string())?;
         }
         self.session_metadata.remove(client_id);
+        self.dormant_sessions.remove(client_id);
+        self.resume_locks.remove(client_id);
+        self.session_usage.remove(client_id);
+        self.message_queues.remove(client_id);
         self.session_arcs.remove(client_id);
+        // Dropping the senders resolves the waiting hooks as denied.
+        self.pending_permissions
+            .retain(|_, pending| pending.request["sessionId"].as_str() != Some(client_id));
         Ok(())
     }
 
+    /// Disconnect a live client but keep what is needed to resume it: its
+    /// options become a dormant session, while usage and queue are kept.
+    /// Returns false when the session has no live client or is busy.
+    pub async fn hibernate_client(&self, client_id: &str) -> Result<bool, String> {
+        let client = {
+            let mut clients = self.clients.lock().await;
+            // Checked under the clients lock: a send marks the session busy
+            // before it looks up the client, so it either sees the client
+            // removed and resumes it, or keeps it from being removed here.
+            if self.is_session_busy(client_id) || self.has_pending_permission(client_id) {
+                return Ok(false);
+            }
+            clients.remove(client_id)
+        };
+        let Some(client) = client else {
+            return Ok(false);
+        };
+        self.session_arcs.remove(client_id);
+        self.pending_permissions
+            .retain(|_, pending| pending.request["sessionId"].as_str() != Some(client_id));
+        if let Some((_, metadata)) = self.session_metadata.remove(client_id) {
+            self.dormant_sessions.insert(
+                client_id.to_string(),
+                DormantSession {
+                    options: metadata.resume_options(),
+                    last_active_at: metadata.last_active_at,
+                    reaped_at: chrono::Utc::now().timestamp_millis(),
+                },
+            );
+        }
+        let mut client = client.write().await;
+        client.disconnect().await.map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    fn has_pending_permission(&self, client_id: &str) -> bool {
+        self.pending_permissions
+            .iter()
+            .any(|pending| pending.request["sessionId"].as_str() == Some(client_id))
+    }
+
+    /// Mark the session active now, recording the CLI session id when a
+    /// message carries one.
+    pub fn touch_session(&self, session_id: &str, sdk_session_id: Option<&str>) {
+        if let Some(mut meta) = self.session_metadata.get_mut(session_id) {
+            meta.last_active_at = chrono::Utc::now().timestamp_millis();
+            if let Some(sdk_session_id) = sdk_session_id.filter(|id| !id.is_empty()) {
+                meta.sdk_session_id = Some(sdk_session_id.to_string());
+            }
+        }
+    }
+
     pub fn get_permission_mode(&self, session_id: &str) -> Option<String> {
         self.session_metadata.get(session_id).and_then(|m| m.permission_mode.clone())
     }
@@ -99,9 +238,151 @@ impl CCState {
         }
     }
 
+    pub fn get_permission_timeout(&self, session_id: &str) -> PermissionTimeout {
+        self.session_metadata
+            .get(session_id)
+            .map(|m| m.permission_timeout)
+            .unwrap_or_default()
+    }
+
+    pub fn set_permission_timeout(&self, session_id: &str, timeout: PermissionTimeout) -> Result<(), String> {
+        let mut meta = self
+            .session_metadata
+            .get_mut(session_id)
+            .ok_or_else(|| format!("Session not found: {}", session_id))?;
+        meta.permission_timeout = timeout;
+        Ok(())
+    }
+
+    pub fn get_budget_guard(&self, session_id: &str) -> Option<f64> {
+        self.session_metadata.get(session_id).and_then(|m| m.budget_guard_usd)
+    }
+
+    /// Fold a serialized message into the session's usage; returns the new
+    /// totals when they changed.
+    pub fn observe_usage(&self, session_id: &str, message: &Value) -> Option<SessionUsage> {
+        self.session_usage
+            .entry(session_id.to_string())
+            .or_default()
+            .observe(message)
+    }
+
+    pub fn get_usage(&self, session_id: &str) -> SessionUsage {
+        self.session_usage
+            .get(session_id)
+            .map(|tracker| tracker.snapshot())
+            .unwrap_or_default()
+    }
+
+    /// Add a message to the session queue, at the front when steering. Returns
+    /// true when no worker is draining the queue and the caller must start one.
+    pub fn enqueue_message(&self, session_id: &str, message: QueuedMessage, front: bool) -> bool {
+        let start_worker = {
+            let mut queue = self.message_queues.entry(session_id.to_string()).or_default();
+            if front {
+                queue.pending.push_front(message);
+            } else {
+                queue.pending.push_back(message);
+            }
+            !std::mem::replace(&mut queue.busy, true)
+        };
+        self.emit_queue(session_id);
+        start_worker
+    }
+
+    /// Take the next message for the session worker, or mark the session idle
+    /// when the queue is empty.
+    pub fn next_queued_message(&self, session_id: &str) -> Option<QueuedMessage> {
+        let next = {
+            let mut queue = self.message_queues.get_mut(session_id)?;
+            let next = queue.pending.pop_front();
+            queue.busy = next.is_some();
+            next
+        };
+        if next.is_some() {
+            self.emit_queue(session_id);
+        }
+        next
+    }
+
+    pub fn is_session_busy(&self, session_id: &str) -> bool {
+        self.message_queues.get(session_id).is_some_and(|queue| queue.busy)
+    }
+
+    pub fn list_queued_messages(&self, session_id: &str) -> Vec<QueuedMessage> {
+        self.message_queues
+            .get(session_id)
+            .map(|queue| queue.pending.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn cancel_queued_message(&self, session_id: &str, message_id: &str) -> Result<(), String> {
+        {
+            let mut queue = self
+                .message_queues
+                .get_mut(session_id)
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            let index = queue
+                .pending
+                .iter()
+                .position(|queued| queued.id == message_id)
+                .ok_or_else(|| format!("Queued message not found: {}", message_id))?;
+            queue.pending.remove(index);
+        }
+        self.emit_queue(session_id);
+        Ok(())
+    }
+
+    /// Reorder the queue to `message_ids`, which must list every queued message once.
+    pub fn reorder_queued_messages(&self, session_id: &str, message_ids: &[String]) -> Result<(), String> {
+        {
+            let mut queue = self
+                .message_queues
+                .get_mut(session_id)
+                .ok_or_else(|| format!("Session not found: {}", session_id))?;
+            let complete = message_ids.len() == queue.pending.len()
+                && queue.pending.iter().all(|queued| message_ids.contains(&queued.id));
+            if !complete {
+                return Err("Reorder must list every queued message exactly once".to_string());
+            }
+            let mut pending: Vec<QueuedMessage> = queue.pending.drain(..).collect();
+            queue.pending = message_ids
+                .iter()
+                .filter_map(|id| {
+                    let index = pending.iter().position(|queued| &queued.id == id)?;
+                    Some(pending.swap_remove(index))
+                })
+                .collect();
+        }
+        self.emit_queue(session_id);
+        Ok(())
+    }
+
+    fn emit_queue(&self, session_id: &str) {
+        self.emit(
+            "cc-queue-updated",
+            serde_json::json!({
+                "sessionId": session_id,
+                "queue": self.list_queued_messages(session_id),
+            }),
+        );
+    }
+
+    /// Outstanding permission prompts, oldest first, optionally for one session.
+    pub fn list_pending_permissions(&self, session_id: Option<&str>) -> Vec<Value> {
+        let mut requests: Vec<Value> = self
+            .pending_permissions
+            .iter()
+            .map(|entry| entry.value().request.clone())
+            .filter(|request| session_id.is_none_or(|id| request["sessionId"].as_str() == Some(id)))
+            .collect();
+        requests.sort_by_key(|request| request["createdAt"].as_i64().unwrap_or_default());
+        requests
+    }
+
     pub fn resolve_permission(&self, request_id: &str, decision: String) -> Result<(), String> {
-        if let Some((_, tx)) = self.pending_permissions.remove(request_id) {
-            let _ = tx.send(decision);
+        if let Some((_, pending)) = self.pending_permissions.remove(request_id) {
+            let _ = pending.sender.send(decision);
             Ok(())
         } else {
             Err(format!("Permission request not found: {}", request_id))
@@ -114,3 +395,99 @@ impl Default for CCState {
         Self::new(Arc::new(NoOpSink))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(id: &str) -> QueuedMessage {
+        QueuedMessage {
+            id: id.to_string(),
+            message: format!("message {}", id),
+            image_paths: Vec::new(),
+            queued_at: 0,
+        }
+    }
+
+    fn ids(state: &CCState) -> Vec<String> {
+        state.list_queued_messages("s").into_iter().map(|m| m.id).collect()
+    }
+
+    #[test]
+    fn queue_starts_one_worker_and_steering_jumps_ahead() {
+        let state = CCState::default();
+        assert!(state.enqueue_message("s", queued("a"), false));
+        assert!(!state.enqueue_message("s", queued("b"), false));
+        assert!(!state.enqueue_message("s", queued("c"), true));
+        assert_eq!(ids(&state), ["c", "a", "b"]);
+
+        assert_eq!(state.next_queued_message("s").unwrap().id, "c");
+        assert!(state.is_session_busy("s"));
+        state.next_queued_message("s");
+        state.next_queued_message("s");
+        assert!(state.next_queued_message("s").is_none());
+        assert!(!state.is_session_busy("s"));
+        assert!(state.enqueue_message("s", queued("d"), false));
+    }
+
+    #[test]
+    fn resume_options_carry_runtime_permissions_and_cli_session() {
+        let metadata = SessionMetadata {
+            permission_mode: Some("acceptEdits".to_string()),
+            permission_timeout: PermissionTimeout { timeout_secs: Some(30), ..Default::default() },
+            budget_guard_usd: None,
+            options: AgentOptions {
+                cwd: "/tmp".to_string(),
+                permission_mode: Some("default".to_string()),
+                continue_conversation: Some(true),
+                ..Default::default()
+            },
+            sdk_session_id: Some("cli-session".to_string()),
+            last_active_at: 0,
+        };
+        let options = metadata.resume_options();
+        assert_eq!(options.permission_mode.as_deref(), Some("acceptEdits"));
+        assert_eq!(options.permission_timeout_secs, Some(30));
+        assert_eq!(options.resume.as_deref(), Some("cli-session"));
+        assert_eq!(options.continue_conversation, None);
+    }
+
+    #[tokio::test]
+    async fn hibernate_skips_busy_sessions() {
+        let state = CCState::default();
+        let metadata = SessionMetadata {
+            permission_mode: None,
+            permission_timeout: PermissionTimeout::default(),
+            budget_guard_usd: None,
+            options: AgentOptions::default(),
+            sdk_session_id: None,
+            last_active_at: 0,
+        };
+        state
+            .create_client("s".to_string(), ClaudeAgentOptions::default(), metadata)
+            .await
+            .unwrap();
+        state.enqueue_message("s", queued("a"), false);
+
+        assert!(!state.hibernate_client("s").await.unwrap());
+        assert!(state.get_client("s").await.is_some());
+        assert!(state.dormant_sessions.is_empty());
+    }
+
+    #[test]
+    fn cancel_and_reorder_validate_ids() {
+        let state = CCState::default();
+        for id in ["a", "b", "c"] {
+            state.enqueue_message("s", queued(id), false);
+        }
+        state.cancel_queued_message("s", "b").unwrap();
+        assert!(state.cancel_queued_message("s", "b").is_err());
+
+        assert!(state.reorder_queued_messages("s", &["c".to_string()]).is_err());
+        assert_eq!(ids(&state), ["a", "c"]);
+        state
+            .reorder_queued_messages("s", &["c".to_string(), "a".to_string()])
+            .unwrap();
+        assert_eq!(ids(&state), ["c", "a"]);
+    }
+}
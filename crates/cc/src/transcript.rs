@@ -0,0 +1,502 @@
+//! Export and import of Claude Code session transcripts
+//! (`~/.claude/projects/<encoded cwd>/<session id>.jsonl`).
+//!
+//! Export folds the JSONL records into a codex [`Rollout`] so sessions render
+//! exactly like codex thread exports. Import writes a transcript back under
+//! `~/.claude/projects` so it can be continued with `resume_session`.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use codexia_codex::export::{ExportOptions, ThreadExport, TranscriptAgent, export_transcript};
+use codexia_codex::rollout::{Rollout, RolloutEvent, TokenUsage};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::db::join_tags;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedSession {
+    pub session_id: String,
+    pub cwd: String,
+    pub path: String,
+}
+
+pub(crate) fn projects_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".claude").join("projects"))
+}
+
+/// Claude Code stores a project's sessions in a directory named after its cwd
+/// with every non-alphanumeric character replaced by `-`.
+pub(crate) fn project_dir_name(cwd: &str) -> String {
+    cwd.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+pub(crate) fn validate_session_id(session_id: &str) -> Result<(), String> {
+    let valid = !session_id.is_empty()
+        && session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid session id: {}", session_id))
+    }
+}
+
+/// The transcript file of `session_id`, searching every project directory.
+pub fn find_session_file(session_id: &str) -> Result<PathBuf, String> {
+    validate_session_id(session_id)?;
+    let file_name = format!("{}.jsonl", session_id);
+    let entries = fs::read_dir(projects_dir()?)
+        .map_err(|e| format!("Failed to read Claude projects directory: {}", e))?;
+    entries
+        .flatten()
+        .map(|entry| entry.path().join(&file_name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| format!("Session '{}' not found", session_id))
+}
+
+pub fn export_session(session_id: &str, options: &ExportOptions) -> Result<ThreadExport, String> {
+    let path = find_session_file(session_id)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read session {}: {}", path.display(), e))?;
+    let mut rollout = parse_transcript(&content);
+    if rollout.meta.id.is_empty() {
+        rollout.meta.id = session_id.to_string();
+    }
+    export_transcript(rollout, &path, TranscriptAgent::Claude, options)
+}
+
+/// Fold Claude Code JSONL records into a [`Rollout`]. Sidechain (subagent) and
+/// meta records are skipped, as are thinking blocks.
+pub fn parse_transcript(content: &str) -> Rollout {
+    let mut rollout = Rollout::default();
+    rollout.meta.source = "claude".to_string();
+    let mut usage = TokenUsage::default();
+    let mut counted_messages: HashSet<String> = HashSet::new();
+
+    for record in content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        let str_field = |key: &str| record.get(key).and_then(Value::as_str);
+        if rollout.meta.id.is_empty()
+            && let Some(session_id) = str_field("sessionId")
+        {
+            rollout.meta.id = session_id.to_string();
+        }
+        if rollout.meta.cwd.is_empty()
+            && let Some(cwd) = str_field("cwd")
+        {
+            rollout.meta.cwd = cwd.to_string();
+        }
+        if rollout.meta.cli_version.is_none() {
+            rollout.meta.cli_version = str_field("version").map(str::to_string);
+        }
+
+        let kind = str_field("type").unwrap_or_default();
+        if !matches!(kind, "user" | "assistant")
+            || record.get("isSidechain").and_then(Value::as_bool) == Some(true)
+            || record.get("isMeta").and_then(Value::as_bool) == Some(true)
+        {
+            continue;
+        }
+        let timestamp = str_field("timestamp").map(str::to_string);
+        if rollout.meta.timestamp.is_none() {
+            rollout.meta.timestamp = timestamp.clone();
+        }
+        let Some(message) = record.get("message") else {
+            continue;
+        };
+
+        if kind == "assistant" {
+            rollout.meta.model_provider = Some("anthropic".to_string());
+            if rollout.meta.model.is_none() {
+                rollout.meta.model = message
+                    .get("model")
+                    .and_then(Value::as_str)
+                    // Local notices such as API errors use a `<synthetic>` model.
+                    .filter(|model| !model.starts_with('<'))
+                    .map(str::to_string);
+            }
+            // Streamed responses repeat the same message id with the same usage.
+            let message_id = message
+                .get("id")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if let Some(message_usage) = message.get("usage")
+                && (message_id.is_empty() || counted_messages.insert(message_id.to_string()))
+            {
+                add_usage(&mut usage, message_usage);
+            }
+        }
+
+        match message.get("content") {
+            Some(Value::String(text)) => push_message(&mut rollout, kind, text, &timestamp),
+            Some(Value::Array(blocks)) => {
+                for block in blocks {
+                    push_block(&mut rollout, kind, block, &timestamp);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if usage.total_tokens > 0 {
+        rollout.token_usage = Some(usage);
+    }
+    rollout
+}
+
+fn push_message(rollout: &mut Rollout, role: &str, text: &str, timestamp: &Option<String>) {
+    if text.trim().is_empty() {
+        return;
+    }
+    rollout.events.push(RolloutEvent::Message {
+        role: role.to_string(),
+        text: text.to_string(),
+        timestamp: timestamp.clone(),
+    });
+}
+
+fn push_block(rollout: &mut Rollout, role: &str, block: &Value, timestamp: &Option<String>) {
+    let field = |key: &str| block.get(key).and_then(Value::as_str);
+    match field("type").unwrap_or_default() {
+        "text" => push_message(rollout, role, field("text").unwrap_or_default(), timestamp),
+        "tool_use" => {
+            let input = block.get("input").cloned().unwrap_or(Value::Null);
+            let name = field("name").unwrap_or("tool").to_string();
+            let command = (name == "Bash")
+                .then(|| input.get("command").and_then(Value::as_str))
+                .flatten()
+                .map(str::to_string);
+            rollout.events.push(RolloutEvent::ToolCall {
+                call_id: field("id").map(str::to_string),
+                name,
+                command,
+                input: serde_json::to_string_pretty(&input).unwrap_or_default(),
+                timestamp: timestamp.clone(),
+            });
+        }
+        "tool_result" => rollout.events.push(RolloutEvent::ToolOutput {
+            call_id: field("tool_use_id").map(str::to_string),
+            output: tool_result_text(block.get("content")),
+            timestamp: timestamp.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Tool results are either a string or a list of content blocks.
+fn tool_result_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|block| match block.get("type").and_then(Value::as_str) {
+                Some("text") => block
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                Some("image") => Some("[image]".to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn add_usage(usage: &mut TokenUsage, message_usage: &Value) {
+    let tokens = |key: &str| message_usage.get(key).and_then(Value::as_u64).unwrap_or(0);
+    let input = tokens("input_tokens") + tokens("cache_creation_input_tokens");
+    let cached = tokens("cache_read_input_tokens");
+    let output = tokens("output_tokens");
+    usage.input_tokens += input + cached;
+    usage.cached_input_tokens += cached;
+    usage.output_tokens += output;
+    usage.total_tokens += input + cached + output;
+}
+
+/// Rename a session the way Claude Code's `/rename` does: append a
+/// `custom-title` record, the last of which wins.
+pub fn set_custom_title(session_id: &str, title: &str) -> Result<(), String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Title cannot be empty".to_string());
+    }
+    append_metadata_record(
+        session_id,
+        json!({ "type": "custom-title", "customTitle": title, "sessionId": session_id }),
+    )
+}
+
+/// Replace the tags of a session. Claude Code keeps one `tag` per session, so
+/// the tags are appended as a single comma separated `tag` record; an empty
+/// list clears them. Returns the normalized tags.
+pub fn set_tags(session_id: &str, tags: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.contains([',', '\n', '\r']) {
+            return Err(format!("Tag cannot contain commas or line breaks: {}", tag));
+        }
+        if !tag.is_empty() && !normalized.iter().any(|existing| existing == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    append_metadata_record(
+        session_id,
+        json!({ "type": "tag", "tag": join_tags(&normalized), "sessionId": session_id }),
+    )?;
+    Ok(normalized)
+}
+
+fn append_metadata_record(session_id: &str, record: Value) -> Result<(), String> {
+    let path = find_session_file(session_id)?;
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut last = [b'\n'];
+    if file.seek(SeekFrom::End(-1)).is_ok() {
+        let _ = file.read_exact(&mut last);
+    }
+    let separator = if last[0] == b'\n' { "" } else { "\n" };
+    // A single write keeps the line intact next to a running CLI appending too.
+    file.write_all(format!("{}{}\n", separator, record).as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    if let Err(e) = crate::scan::refresh_session_file(&path) {
+        log::warn!("Failed to refresh session cache after updating {}: {}", session_id, e);
+    }
+    Ok(())
+}
+
+/// Write a JSONL transcript into `~/.claude/projects`. When `cwd` is given and
+/// differs from the recorded one (e.g. the project lives elsewhere on this
+/// machine), the `cwd` of every record is rewritten to match.
+pub fn import_session(content: &str, cwd: Option<&str>) -> Result<ImportedSession, String> {
+    let imported = import_session_in(&projects_dir()?, content, cwd)?;
+    if let Err(e) = crate::scan::sync_project_session_cache(&imported.cwd, false) {
+        log::warn!("Failed to refresh session cache after import: {}", e);
+    }
+    Ok(imported)
+}
+
+fn import_session_in(
+    projects_dir: &Path,
+    content: &str,
+    cwd: Option<&str>,
+) -> Result<ImportedSession, String> {
+    let mut records = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Value = serde_json::from_str(line)
+            .map_err(|e| format!("Line {} is not valid JSON: {}", index + 1, e))?;
+        if !record.is_object() {
+            return Err(format!("Line {} is not a JSON object", index + 1));
+        }
+        records.push((line.to_string(), record));
+    }
+
+    let first_str = |key: &str| {
+        records
+            .iter()
+            .find_map(|(_, record)| record.get(key).and_then(Value::as_str))
+            .map(str::to_string)
+    };
+    let session_id = first_str("sessionId").ok_or("Transcript has no sessionId")?;
+    validate_session_id(&session_id)?;
+    let recorded_cwd = first_str("cwd");
+    let target_cwd = cwd
+        .map(|cwd| cwd.trim_end_matches('/').to_string())
+        .filter(|cwd| !cwd.is_empty())
+        .or_else(|| recorded_cwd.clone())
+        .ok_or("Transcript has no cwd; pass the project directory to import into")?;
+
+    if let Some(recorded_cwd) = recorded_cwd.filter(|recorded| *recorded != target_cwd) {
+        for (line, record) in &mut records {
+            if let Some(Value::String(record_cwd)) = record.get_mut("cwd")
+                && let Some(rest) = record_cwd.strip_prefix(&recorded_cwd)
+                && (rest.is_empty() || rest.starts_with('/'))
+            {
+                *record_cwd = format!("{}{}", target_cwd, rest);
+                *line = record.to_string();
+            }
+        }
+    }
+
+    let mut output = String::new();
+    for (line, _) in &records {
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    let dir = projects_dir.join(project_dir_name(&target_cwd));
+    let path = dir.join(format!("{}.jsonl", session_id));
+    if let Ok(existing) = fs::read_to_string(&path)
+        && existing != output
+    {
+        return Err(format!(
+            "Session '{}' already exists at {}",
+            session_id,
+            path.display()
+        ));
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    fs::write(&path, output).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(ImportedSession {
+        session_id,
+        cwd: target_cwd,
+        path: path.to_string_lossy().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = "0a1b2c3d-0000-4000-8000-000000000001";
+
+    fn temp_projects_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "codexia-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ))
+    }
+
+    fn transcript(cwd: &str) -> String {
+        [
+            json!({ "type": "summary", "summary": "Fix the build" }),
+            json!({
+                "type": "user", "sessionId": SESSION, "cwd": cwd, "version": "2.0.1",
+                "timestamp": "2026-01-01T00:00:00Z",
+                "message": { "role": "user", "content": "run the tests" },
+            }),
+            json!({
+                "type": "assistant", "sessionId": SESSION, "cwd": format!("{}/sub", cwd),
+                "message": {
+                    "id": "msg_1", "model": "claude-sonnet-4-5",
+                    "usage": { "input_tokens": 10, "cache_read_input_tokens": 5, "output_tokens": 2 },
+                    "content": [
+                        { "type": "thinking", "thinking": "hmm" },
+                        { "type": "tool_use", "id": "tool_1", "name": "Bash", "input": { "command": "cargo test" } },
+                    ],
+                },
+            }),
+            // Streaming repeats the message with the same usage.
+            json!({
+                "type": "assistant", "sessionId": SESSION, "cwd": cwd,
+                "message": {
+                    "id": "msg_1", "model": "claude-sonnet-4-5",
+                    "usage": { "input_tokens": 10, "cache_read_input_tokens": 5, "output_tokens": 2 },
+                    "content": [{ "type": "text", "text": "Running them now." }],
+                },
+            }),
+            json!({
+                "type": "user", "sessionId": SESSION, "cwd": cwd,
+                "message": { "role": "user", "content": [
+                    { "type": "tool_result", "tool_use_id": "tool_1", "content": [{ "type": "text", "text": "ok" }] },
+                ] },
+            }),
+            json!({
+                "type": "assistant", "sessionId": SESSION, "cwd": cwd, "isSidechain": true,
+                "message": { "id": "msg_2", "content": "subagent chatter" },
+            }),
+        ]
+        .iter()
+        .map(|record| format!("{}\n", record))
+        .collect()
+    }
+
+    #[test]
+    fn parse_transcript_folds_records_into_a_rollout() {
+        let rollout = parse_transcript(&transcript("/work/app"));
+        assert_eq!(rollout.meta.id, SESSION);
+        assert_eq!(rollout.meta.cwd, "/work/app");
+        assert_eq!(rollout.meta.cli_version.as_deref(), Some("2.0.1"));
+        assert_eq!(rollout.meta.model_provider.as_deref(), Some("anthropic"));
+        assert_eq!(rollout.meta.model.as_deref(), Some("claude-sonnet-4-5"));
+
+        let kinds: Vec<String> = rollout
+            .events
+            .iter()
+            .map(|event| match event {
+                RolloutEvent::Message { role, text, .. } => format!("{}: {}", role, text),
+                RolloutEvent::ToolCall { name, command, .. } => {
+                    format!("call {} {}", name, command.as_deref().unwrap_or_default())
+                }
+                RolloutEvent::ToolOutput { output, .. } => format!("output {}", output),
+                RolloutEvent::Patch { .. } => "patch".to_string(),
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "user: run the tests",
+                "call Bash cargo test",
+                "assistant: Running them now.",
+                "output ok",
+            ]
+        );
+        let usage = rollout.token_usage.unwrap();
+        assert_eq!((usage.input_tokens, usage.cached_input_tokens), (15, 5));
+        assert_eq!(usage.total_tokens, 17);
+    }
+
+    #[test]
+    fn import_rewrites_cwd_under_the_recorded_root() {
+        let dir = temp_projects_dir("import-cwd");
+        let imported =
+            import_session_in(&dir, &transcript("/old/app"), Some("/new/app/")).unwrap();
+        assert_eq!(imported.session_id, SESSION);
+        assert_eq!(imported.cwd, "/new/app");
+
+        let path = dir.join("-new-app").join(format!("{}.jsonl", SESSION));
+        assert_eq!(imported.path, path.to_string_lossy());
+        let written = fs::read_to_string(&path).unwrap();
+        let cwds: Vec<String> = written
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter_map(|record| record.get("cwd").and_then(Value::as_str).map(str::to_string))
+            .collect();
+        assert_eq!(cwds[..2], ["/new/app", "/new/app/sub"]);
+        assert!(!written.contains("/old/app"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_refuses_to_overwrite_a_different_session() {
+        let dir = temp_projects_dir("import-conflict");
+        let content = transcript("/work/app");
+        import_session_in(&dir, &content, None).unwrap();
+        // Importing the same transcript again is a no-op.
+        import_session_in(&dir, &content, None).unwrap();
+
+        let changed = content.replace("run the tests", "run the linter");
+        let err = import_session_in(&dir, &changed, None).unwrap_err();
+        assert!(err.contains("already exists"), "{}", err);
+
+        assert!(import_session_in(&dir, "not json\n", None).is_err());
+        let no_cwd = format!("{}\n", json!({ "type": "user", "sessionId": SESSION }));
+        assert!(import_session_in(&dir, &no_cwd, None).unwrap_err().contains("no cwd"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,20 +1,43 @@
+use crate::services::session_lifecycle;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use claude_agent_sdk_rs::{ClaudeAgentOptions, PermissionMode};
+use claude_agent_sdk_rs::{ClaudeAgentOptions, PermissionMode, SystemPrompt};
 use claude_agent_sdk_rs::types::mcp::{
     McpHttpServerConfig, McpServerConfig, McpServers, McpSseServerConfig, McpStdioServerConfig,
 };
 
+/// Parameters for `cc_connect`: the session to (re)connect plus the full
+/// [`AgentOptions`] set, flattened so `cwd`, `model`, `maxTurns`, ... sit at the top level.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CCConnectParams {
     pub session_id: String,
-    pub cwd: String,
-    pub model: Option<String>,
-    pub permission_mode: Option<String>,
     pub resume_id: Option<String>,
+    #[serde(flatten)]
+    pub options: AgentOptions,
+}
+
+/// Decision applied to a permission prompt nobody answers in time,
+/// e.g. when `codexia-web` runs headless.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionFallback {
+    #[default]
+    Deny,
+    /// Allow read-only tools, deny everything else.
+    AllowReadOnly,
+    Allow,
+}
+
+/// Per-session permission prompt deadline. `timeout_secs: None` waits indefinitely.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionTimeout {
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub fallback: PermissionFallback,
 }
 
 /// MCP server configuration for serialization
@@ -58,6 +81,16 @@ pub struct AgentOptions {
     pub mcp_servers: Option<HashMap<String, McpServerConfigSerde>>,
     pub resume: Option<String>,
     pub continue_conversation: Option<bool>,
+    /// Replaces Claude Code's default system prompt.
+    pub system_prompt: Option<String>,
+    /// Stream partial assistant messages as `cc-stream-event`s. Defaults to true.
+    pub include_partial_messages: Option<bool>,
+    /// Interrupt the session as soon as its live cost reaches `max_budget_usd`,
+    /// instead of leaving the limit to the CLI at the end of a turn.
+    pub budget_guard: Option<bool>,
+    /// Seconds to wait for a permission decision before applying `permission_fallback`.
+    pub permission_timeout_secs: Option<u64>,
+    pub permission_fallback: Option<PermissionFallback>,
 }
 
 pub(crate) fn parse_permission_mode(mode: &str) -> Option<PermissionMode> {
@@ -87,7 +120,83 @@ impl From<McpServerConfigSerde> for McpServerConfig {
 }
 
 impl AgentOptions {
-    pub fn to_claude_options(&self, resume_id: Option<String>) -> ClaudeAgentOptions {
+    /// Reject options the CLI would fail on later, with a message naming the field.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.cwd.trim().is_empty() {
+            return Err("cwd is required".to_string());
+        }
+        if !Path::new(&self.cwd).is_dir() {
+            return Err(format!("cwd is not a directory: {}", self.cwd));
+        }
+        if let Some(mode) = &self.permission_mode
+            && parse_permission_mode(mode).is_none()
+        {
+            return Err(format!("Invalid permission mode: {}", mode));
+        }
+        if self.max_turns == Some(0) {
+            return Err("maxTurns must be at least 1".to_string());
+        }
+        if let Some(budget) = self.max_budget_usd
+            && !(budget.is_finite() && budget > 0.0)
+        {
+            return Err(format!("maxBudgetUsd must be a positive amount, got {}", budget));
+        }
+        if self.permission_timeout_secs == Some(0) {
+            return Err("permissionTimeoutSecs must be at least 1".to_string());
+        }
+        let allowed = self.allowed_tools.iter().flatten();
+        let disallowed = self.disallowed_tools.iter().flatten();
+        if let Some(tool) = allowed.clone().chain(disallowed.clone()).find(|tool| tool.trim().is_empty()) {
+            return Err(format!("Invalid empty tool name {:?}", tool));
+        }
+        if let Some(tool) = allowed.into_iter().find(|tool| disallowed.clone().any(|d| d == *tool)) {
+            return Err(format!("Tool {} is both allowed and disallowed", tool));
+        }
+        for (name, server) in self.mcp_servers.iter().flatten() {
+            match server {
+                McpServerConfigSerde::Stdio { command, .. } if command.trim().is_empty() => {
+                    return Err(format!("MCP server {} has no command", name));
+                }
+                McpServerConfigSerde::Http { url, .. } | McpServerConfigSerde::Sse { url, .. }
+                    if reqwest::Url::parse(url).is_err() =>
+                {
+                    return Err(format!("MCP server {} has an invalid url: {}", name, url));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The prompt deadline for a new session. Whatever the options leave unset
+    /// comes from the server default in `cc_session_limits.json`.
+    pub fn permission_timeout(&self) -> PermissionTimeout {
+        let default = session_lifecycle::read_limits()
+            .map(|limits| limits.permission_timeout)
+            .unwrap_or_else(|e| {
+                log::warn!("[CC] {}; prompts wait indefinitely by default", e);
+                PermissionTimeout::default()
+            });
+        self.permission_timeout_or(default)
+    }
+
+    fn permission_timeout_or(&self, default: PermissionTimeout) -> PermissionTimeout {
+        PermissionTimeout {
+            timeout_secs: self.permission_timeout_secs.or(default.timeout_secs),
+            fallback: self.permission_fallback.unwrap_or(default.fallback),
+        }
+    }
+
+    pub fn budget_guard_usd(&self) -> Option<f64> {
+        self.max_budget_usd.filter(|_| self.budget_guard.unwrap_or(false))
+    }
+
+    /// `env` is passed to the Claude process, e.g. the stored provider secrets.
+    pub fn to_claude_options(
+        &self,
+        resume_id: Option<String>,
+        env: HashMap<String, String>,
+    ) -> ClaudeAgentOptions {
         let permission_mode = self.permission_mode.as_deref().and_then(parse_permission_mode);
 
         let mcp_servers = if let Some(servers) = &self.mcp_servers {
@@ -114,8 +223,78 @@ impl AgentOptions {
             mcp_servers,
             resume: resume_id.or_else(|| self.resume.clone()),
             continue_conversation: self.continue_conversation.unwrap_or(false),
+            system_prompt: self.system_prompt.clone().map(SystemPrompt::Text),
+            include_partial_messages: self.include_partial_messages.unwrap_or(true),
+            env,
             stderr_callback: Some(Arc::new(|msg| log::error!("[CC STDERR] {}", msg))),
             ..Default::default()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> AgentOptions {
+        AgentOptions {
+            cwd: std::env::temp_dir().to_string_lossy().to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validate_accepts_defaults_and_rejects_bad_limits() {
+        assert!(options().validate().is_ok());
+        assert!(AgentOptions { cwd: String::new(), ..options() }.validate().is_err());
+        assert!(AgentOptions { max_turns: Some(0), ..options() }.validate().is_err());
+        assert!(AgentOptions { max_budget_usd: Some(-1.0), ..options() }.validate().is_err());
+        assert!(AgentOptions { permission_mode: Some("yolo".into()), ..options() }.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_conflicting_tools_and_bad_mcp_servers() {
+        let conflicting = AgentOptions {
+            allowed_tools: Some(vec!["Bash".into(), "Read".into()]),
+            disallowed_tools: Some(vec!["Bash".into()]),
+            ..options()
+        };
+        assert!(conflicting.validate().unwrap_err().contains("Bash"));
+
+        let bad_url = AgentOptions {
+            mcp_servers: Some(HashMap::from([(
+                "remote".to_string(),
+                McpServerConfigSerde::Http { url: "not a url".into(), headers: None },
+            )])),
+            ..options()
+        };
+        assert!(bad_url.validate().unwrap_err().contains("remote"));
+    }
+
+    #[test]
+    fn permission_timeout_falls_back_to_the_server_default() {
+        let default = PermissionTimeout { timeout_secs: Some(120), fallback: PermissionFallback::AllowReadOnly };
+        assert_eq!(options().permission_timeout_or(default), default);
+        let own = AgentOptions { permission_timeout_secs: Some(30), ..options() };
+        assert_eq!(own.permission_timeout_or(default), PermissionTimeout { timeout_secs: Some(30), ..default });
+        let own = AgentOptions { permission_fallback: Some(PermissionFallback::Deny), ..own };
+        assert_eq!(
+            own.permission_timeout_or(default),
+            PermissionTimeout { timeout_secs: Some(30), fallback: PermissionFallback::Deny }
+        );
+    }
+
+    #[test]
+    fn connect_params_flatten_agent_options() {
+        let params: CCConnectParams = serde_json::from_value(serde_json::json!({
+            "sessionId": "s1",
+            "cwd": "/tmp",
+            "maxTurns": 3,
+            "systemPrompt": "Be brief",
+        }))
+        .unwrap();
+        assert_eq!(params.session_id, "s1");
+        assert_eq!(params.options.max_turns, Some(3));
+        assert_eq!(params.options.system_prompt.as_deref(), Some("Be brief"));
+    }
+}
@@ -1,6 +1,7 @@
 use super::server_request::handle_server_request;
 use codexia_shared::event_sink::EventSink;
 use codexia_db::automation_runs::sync_automation_run_status;
+use crate::rate_limits;
 use codex_app_server_protocol::{
     ClientInfo, InitializeCapabilities, InitializeParams, InitializeResponse,
     JSONRPCMessage, JSONRPCResponse, RequestId, ServerNotification, ServerRequest,
@@ -97,9 +98,14 @@ pub async fn connect_codex(event_sink: Arc<dyn EventSink>) -> Result<Arc<CodexAp
     let codex_bin =
         discover_codex_command().ok_or_else(|| "Unable to locate codex binary".to_string())?;
 
+    // Provider keys saved in the secret store are not in the shell environment.
+    let stored_env = tokio::task::spawn_blocking(crate::secrets::stored_env)
+        .await
+        .unwrap_or_default();
     let mut command = {
         let mut cmd = Command::new(codex_bin);
         cmd.arg("app-server");
+        cmd.envs(stored_env);
         cmd
     };
 
@@ -209,6 +215,10 @@ pub async fn connect_codex(event_sink: Arc<dyn EventSink>) -> Result<Arc<CodexAp
                             match serde_json::to_value(&server_notification) {
                                 Ok(payload) => {
                                     sync_automation_run_status(&payload);
+                                    rate_limits::record_notification(
+                                        &event_sink_clone,
+                                        &payload,
+                                    );
                                     event_sink_clone.emit("codex:notification", payload);
                                 }
                                 Err(err) => {
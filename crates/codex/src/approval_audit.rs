@@ -0,0 +1,126 @@
+//! Codex approval decisions for the codexia-db audit log.
+//!
+//! Approval requests are remembered by request id when they arrive so the
+//! response, which only carries the id and the decision, can be recorded with
+//! the thread, tool and command it answered. Requests never answered (thread
+//! closed, app server restarted) are forgotten after [`PENDING_TTL`].
+
+use codex_app_server_protocol::RequestId;
+use codexia_db::approval_audit::{self, ApprovalRecord, DecidedBy};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How long an unanswered request is remembered. A later answer is still
+/// recorded, only without the request details.
+const PENDING_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+struct PendingApproval {
+    thread_id: String,
+    tool_name: &'static str,
+    input_summary: String,
+    reason: Option<String>,
+    tracked_at: Instant,
+}
+
+fn pending() -> &'static Mutex<HashMap<String, PendingApproval>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, PendingApproval>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn key(request_id: &RequestId) -> String {
+    serde_json::to_string(request_id).unwrap_or_default()
+}
+
+/// Remember an approval request. `params` is the request payload;
+/// `tool_name` is `commandExecution` or `fileChange`.
+pub(crate) fn track(request_id: &RequestId, tool_name: &'static str, params: &Value) {
+    let field = |name: &str| params.get(name).and_then(Value::as_str).map(str::to_string);
+    let input_summary = match tool_name {
+        "commandExecution" => crate::export::redact_secrets(&field("command").unwrap_or_default()),
+        _ => field("grantRoot").or_else(|| field("itemId")).unwrap_or_default(),
+    };
+    let approval = PendingApproval {
+        thread_id: field("threadId").unwrap_or_default(),
+        tool_name,
+        input_summary,
+        reason: field("reason"),
+        tracked_at: Instant::now(),
+    };
+    if let Ok(mut pending) = pending().lock() {
+        prune(&mut pending, approval.tracked_at);
+        pending.insert(key(request_id), approval);
+    }
+}
+
+fn prune(pending: &mut HashMap<String, PendingApproval>, now: Instant) {
+    pending.retain(|_, approval| now.duration_since(approval.tracked_at) < PENDING_TTL);
+}
+
+/// Record the user's answer to a tracked request once it was delivered.
+/// Unknown ids are recorded with an empty thread so no decision goes unaudited.
+pub fn record_decision(request_id: &RequestId, decision: &impl Serialize) {
+    let approval = pending()
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.remove(&key(request_id)));
+    let (thread_id, tool_name, input_summary, reason) = match approval {
+        Some(approval) => (
+            approval.thread_id,
+            approval.tool_name,
+            approval.input_summary,
+            approval.reason,
+        ),
+        None => (String::new(), "unknown", String::new(), None),
+    };
+    let record = ApprovalRecord {
+        id: 0,
+        recorded_at: chrono::Utc::now().timestamp_millis(),
+        agent: "codex".to_string(),
+        session_id: thread_id,
+        tool_name: tool_name.to_string(),
+        input_summary,
+        decision: decision_label(&serde_json::to_value(decision).unwrap_or(Value::Null)),
+        decided_by: DecidedBy::User,
+        reason,
+    };
+    approval_audit::enqueue(record);
+}
+
+/// `accept`, `decline`, ... or the variant name of a decision carrying data,
+/// e.g. `acceptWithExecpolicyAmendment`.
+fn decision_label(decision: &Value) -> String {
+    match decision {
+        Value::String(decision) => decision.clone(),
+        Value::Object(map) => map.keys().next().cloned().unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approval(tracked_at: Instant) -> PendingApproval {
+        PendingApproval {
+            thread_id: "thread".to_string(),
+            tool_name: "commandExecution",
+            input_summary: "ls".to_string(),
+            reason: None,
+            tracked_at,
+        }
+    }
+
+    #[test]
+    fn unanswered_requests_are_pruned_after_the_ttl() {
+        let now = Instant::now();
+        let mut pending = HashMap::new();
+        pending.insert("old".to_string(), approval(now));
+        pending.insert("new".to_string(), approval(now + PENDING_TTL / 2));
+
+        prune(&mut pending, now + PENDING_TTL);
+        assert_eq!(pending.keys().collect::<Vec<_>>(), vec!["new"]);
+    }
+}
@@ -15,19 +15,112 @@ fn is_enabled_true(enabled: &bool) -> bool {
     *enabled
 }
 
-fn inline_env_table(table: &mut Table) {
-    if let Some(env_entry) = table.get_mut("env") {
-        if env_entry.is_table() {
-            let env_item = std::mem::take(env_entry);
-            if let Item::Table(env_table) = env_item {
-                *env_entry = Item::Value(Value::InlineTable(env_table.into_inline_table()));
-            } else {
-                *env_entry = env_item;
+/// Keys of a server table that [`McpServerConfig`] reads and writes. Any other
+/// key, such as `enabled_tools`, is kept when a server is rewritten.
+const MANAGED_KEYS: &[&str] = &[
+    "type",
+    "command",
+    "args",
+    "env",
+    "cwd",
+    "url",
+    "http_headers",
+    "env_http_headers",
+    "bearer_token_env_var",
+    "startup_timeout_sec",
+    "startup_timeout_ms",
+    "tool_timeout_sec",
+    "enabled",
+];
+
+/// Write string maps such as `env` as inline tables, the way codex documents them.
+fn inline_map_tables(table: &mut Table) {
+    for key in ["env", "http_headers", "env_http_headers"] {
+        if let Some(entry) = table.get_mut(key) {
+            if entry.is_table() {
+                let item = std::mem::take(entry);
+                if let Item::Table(map) = item {
+                    *entry = Item::Value(Value::InlineTable(map.into_inline_table()));
+                } else {
+                    *entry = item;
+                }
             }
         }
     }
 }
 
+/// Startup and tool-call timeouts, in seconds as codex reads them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawMcpTimeouts")]
+pub struct McpTimeouts {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup_timeout_sec: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_timeout_sec: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct RawMcpTimeouts {
+    #[serde(default)]
+    startup_timeout_sec: Option<f64>,
+    /// Older spelling of `startup_timeout_sec`, still accepted by codex.
+    #[serde(default)]
+    startup_timeout_ms: Option<u64>,
+    #[serde(default)]
+    tool_timeout_sec: Option<f64>,
+}
+
+impl From<RawMcpTimeouts> for McpTimeouts {
+    fn from(raw: RawMcpTimeouts) -> Self {
+        Self {
+            startup_timeout_sec: raw
+                .startup_timeout_sec
+                .or(raw.startup_timeout_ms.map(|ms| ms as f64 / 1000.0)),
+            tool_timeout_sec: raw.tool_timeout_sec,
+        }
+    }
+}
+
+/// Authentication for http and sse servers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct McpHttpAuth {
+    /// Static request headers. Claude's `headers` is accepted as an alias.
+    #[serde(default, alias = "headers", skip_serializing_if = "Option::is_none")]
+    pub http_headers: Option<HashMap<String, String>>,
+    /// Request headers read from environment variables: header name to variable name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_http_headers: Option<HashMap<String, String>>,
+    /// Environment variable holding a token sent as `Authorization: Bearer`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_token_env_var: Option<String>,
+}
+
+impl McpHttpAuth {
+    /// Headers to send, resolving environment-backed ones. Variables that are
+    /// not set are skipped.
+    pub fn resolve_headers(&self) -> Vec<(String, String)> {
+        let mut headers: Vec<(String, String)> = self
+            .http_headers
+            .iter()
+            .flatten()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        for (name, var) in self.env_http_headers.iter().flatten() {
+            if let Ok(value) = std::env::var(var) {
+                headers.push((name.clone(), value));
+            }
+        }
+        if let Some(token) = self
+            .bearer_token_env_var
+            .as_ref()
+            .and_then(|var| std::env::var(var).ok())
+        {
+            headers.push(("Authorization".to_string(), format!("Bearer {}", token)));
+        }
+        headers
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type")]
 pub enum McpServerConfig {
@@ -37,23 +130,46 @@ pub enum McpServerConfig {
         args: Vec<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         env: Option<HashMap<String, String>>,
+        /// Directory the server is started in.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cwd: Option<String>,
+        #[serde(flatten)]
+        timeouts: McpTimeouts,
         #[serde(default = "default_enabled", skip_serializing_if = "is_enabled_true")]
         enabled: bool,
     },
     #[serde(rename = "http")]
     Http {
         url: String,
+        #[serde(flatten)]
+        auth: McpHttpAuth,
+        #[serde(flatten)]
+        timeouts: McpTimeouts,
         #[serde(default = "default_enabled", skip_serializing_if = "is_enabled_true")]
         enabled: bool,
     },
     #[serde(rename = "sse")]
     Sse {
         url: String,
+        #[serde(flatten)]
+        auth: McpHttpAuth,
+        #[serde(flatten)]
+        timeouts: McpTimeouts,
         #[serde(default = "default_enabled", skip_serializing_if = "is_enabled_true")]
         enabled: bool,
     },
 }
 
+impl McpServerConfig {
+    pub fn timeouts(&self) -> &McpTimeouts {
+        match self {
+            McpServerConfig::Stdio { timeouts, .. }
+            | McpServerConfig::Http { timeouts, .. }
+            | McpServerConfig::Sse { timeouts, .. } => timeouts,
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for McpServerConfig {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -68,71 +184,123 @@ impl<'de> Deserialize<'de> for McpServerConfig {
                 args: Vec<String>,
                 #[serde(default)]
                 env: Option<HashMap<String, String>>,
+                #[serde(default)]
+                cwd: Option<String>,
+                #[serde(flatten)]
+                timeouts: McpTimeouts,
                 #[serde(default = "default_enabled")]
                 enabled: bool,
             },
             #[serde(rename = "http")]
             Http {
                 url: String,
+                #[serde(flatten)]
+                auth: McpHttpAuth,
+                #[serde(flatten)]
+                timeouts: McpTimeouts,
                 #[serde(default = "default_enabled")]
                 enabled: bool,
             },
             #[serde(rename = "sse")]
             Sse {
                 url: String,
+                #[serde(flatten)]
+                auth: McpHttpAuth,
+                #[serde(flatten)]
+                timeouts: McpTimeouts,
                 #[serde(default = "default_enabled")]
                 enabled: bool,
             },
         }
 
+        /// Codex's own form: no `type`, the transport follows from `command` or `url`.
         #[derive(Deserialize)]
-        struct LegacyStdioConfig {
-            command: String,
-            #[serde(default)]
-            args: Vec<String>,
-            #[serde(default)]
-            env: Option<HashMap<String, String>>,
-            #[serde(default = "default_enabled")]
-            enabled: bool,
+        #[serde(untagged)]
+        enum UntypedMcpServerConfig {
+            Stdio {
+                command: String,
+                #[serde(default)]
+                args: Vec<String>,
+                #[serde(default)]
+                env: Option<HashMap<String, String>>,
+                #[serde(default)]
+                cwd: Option<String>,
+                #[serde(flatten)]
+                timeouts: McpTimeouts,
+                #[serde(default = "default_enabled")]
+                enabled: bool,
+            },
+            Http {
+                url: String,
+                #[serde(flatten)]
+                auth: McpHttpAuth,
+                #[serde(flatten)]
+                timeouts: McpTimeouts,
+                #[serde(default = "default_enabled")]
+                enabled: bool,
+            },
         }
 
         #[derive(Deserialize)]
         #[serde(untagged)]
         enum McpServerConfigHelper {
             Tagged(TaggedMcpServerConfig),
-            Legacy(LegacyStdioConfig),
+            Untyped(UntypedMcpServerConfig),
         }
 
-        match McpServerConfigHelper::deserialize(deserializer)? {
+        Ok(match McpServerConfigHelper::deserialize(deserializer)? {
             McpServerConfigHelper::Tagged(TaggedMcpServerConfig::Stdio {
                 command,
                 args,
                 env,
+                cwd,
+                timeouts,
                 enabled,
-            }) => Ok(McpServerConfig::Stdio {
+            })
+            | McpServerConfigHelper::Untyped(UntypedMcpServerConfig::Stdio {
                 command,
                 args,
                 env,
+                cwd,
+                timeouts,
                 enabled,
-            }),
-            McpServerConfigHelper::Tagged(TaggedMcpServerConfig::Http { url, enabled }) => {
-                Ok(McpServerConfig::Http { url, enabled })
-            }
-            McpServerConfigHelper::Tagged(TaggedMcpServerConfig::Sse { url, enabled }) => {
-                Ok(McpServerConfig::Sse { url, enabled })
-            }
-            McpServerConfigHelper::Legacy(LegacyStdioConfig {
+            }) => McpServerConfig::Stdio {
                 command,
                 args,
                 env,
+                cwd,
+                timeouts,
                 enabled,
-            }) => Ok(McpServerConfig::Stdio {
-                command,
-                args,
-                env,
+            },
+            McpServerConfigHelper::Tagged(TaggedMcpServerConfig::Http {
+                url,
+                auth,
+                timeouts,
                 enabled,
-            }),
-        }
+            })
+            | McpServerConfigHelper::Untyped(UntypedMcpServerConfig::Http {
+                url,
+                auth,
+                timeouts,
+                enabled,
+            }) => McpServerConfig::Http {
+                url,
+                auth,
+                timeouts,
+                enabled,
+            },
+            McpServerConfigHelper::Tagged(TaggedMcpServerConfig::Sse {
+                url,
+                auth,
+                timeouts,
+                enabled,
+            }) => McpServerConfig::Sse {
+                url,
+                auth,
+                timeouts,
+                enabled,
+            },
+        })
     }
 }
 
@@ -178,7 +346,14 @@ pub async fn add_mcp_server(name: String, config: McpServerConfig) -> Result<(),
     };
 
     let mut server_table = serialize_to_table(&config)?;
-    inline_env_table(&mut server_table);
+    inline_map_tables(&mut server_table);
+    if let Some(previous) = mcp_servers_table.get(&name).and_then(Item::as_table) {
+        for (key, item) in previous.iter() {
+            if !MANAGED_KEYS.contains(&key) && !server_table.contains_key(key) {
+                server_table.insert(key, item.clone());
+            }
+        }
+    }
     mcp_servers_table.insert(&name, Item::Table(server_table));
 
     write_document_with_backup(&config_path, &doc)?;
@@ -258,3 +433,51 @@ pub async fn set_mcp_server_enabled(name: String, enabled: bool) -> Result<(), S
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_auth_and_timeouts_round_trip() {
+        let config: McpServerConfig = toml::from_str(
+            r#"
+            url = "https://example.com/mcp"
+            bearer_token_env_var = "EXAMPLE_TOKEN"
+            startup_timeout_ms = 2500
+            tool_timeout_sec = 60
+            http_headers = { "X-Team" = "core" }
+            "#,
+        )
+        .unwrap();
+        let McpServerConfig::Http { auth, timeouts, .. } = &config else {
+            panic!("expected an http server");
+        };
+        assert_eq!(auth.bearer_token_env_var.as_deref(), Some("EXAMPLE_TOKEN"));
+        assert_eq!(timeouts.startup_timeout_sec, Some(2.5));
+        assert_eq!(timeouts.tool_timeout_sec, Some(60.0));
+
+        let mut table = serialize_to_table(&config).unwrap();
+        inline_map_tables(&mut table);
+        let written = table.to_string();
+        assert!(written.contains("type = \"http\""));
+        assert!(written.contains("startup_timeout_sec = 2.5"));
+        assert!(written.contains("http_headers = {"));
+        let reread: McpServerConfig = toml::from_str(&written).unwrap();
+        assert_eq!(reread.timeouts(), config.timeouts());
+    }
+
+    #[test]
+    fn claude_headers_are_kept_as_http_headers() {
+        let config: McpServerConfig = serde_json::from_value(serde_json::json!({
+            "type": "sse",
+            "url": "https://example.com/sse",
+            "headers": { "Authorization": "Bearer x" },
+        }))
+        .unwrap();
+        let McpServerConfig::Sse { auth, .. } = config else {
+            panic!("expected an sse server");
+        };
+        assert_eq!(auth.resolve_headers(), [("Authorization".to_string(), "Bearer x".to_string())]);
+    }
+}
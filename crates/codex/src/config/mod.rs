@@ -1,5 +1,6 @@
 pub mod mcp;
 pub mod provider;
+pub mod settings;
 pub mod toml_helpers;
 
 use serde::{Deserialize, Serialize};
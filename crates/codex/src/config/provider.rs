@@ -1,9 +1,15 @@
-//! Reads `llms.json` at compile time and writes all `model_providers` entries
-//! to the Codex app-server config on startup.
+//! Writes the merged provider registry (bundled `llms.json` plus
+//! `~/.codexia/providers.json`) to the Codex app-server config as
+//! `model_providers` entries, on startup and whenever the registry changes.
 
+use super::get_config_path;
+use super::toml_helpers::write_document_with_backup;
 use crate::app_server::CodexAppServer;
-use crate::providers::RootConfig;
+use crate::providers::load_providers;
 use serde_json::json;
+use std::fs;
+use std::str::FromStr;
+use toml_edit::{Document, Item};
 
 /// Maximum number of retry attempts when the app-server is not yet initialized.
 const MAX_INIT_RETRIES: u32 = 10;
@@ -13,9 +19,10 @@ const RETRY_DELAY_MS: u64 = 200;
 
 /// Shared retry loop used by [`write_model_providers`].
 ///
-/// Sends `config/value/write` with upsert merge strategy. Retries on
-/// "Not initialized" errors up to [`MAX_INIT_RETRIES`] times.
-async fn upsert_config_value(
+/// Sends `config/value/write` with the replace merge strategy, so keys removed
+/// from the registry (a header, an `env_key`) are removed from config.toml too.
+/// Retries on "Not initialized" errors up to [`MAX_INIT_RETRIES`] times.
+async fn replace_config_value(
     client: &CodexAppServer,
     key_path: &str,
     value: serde_json::Value,
@@ -24,7 +31,7 @@ async fn upsert_config_value(
     let write_params = json!({
         "keyPath": key_path,
         "value": value,
-        "mergeStrategy": "upsert"
+        "mergeStrategy": "replace"
     });
 
     loop {
@@ -52,20 +59,18 @@ async fn upsert_config_value(
 /// cannot be overridden via `config/value/write`.
 const BUILTIN_PROVIDER_IDS: &[&str] = &["ollama"];
 
-/// Reads all providers from the bundled `llms.json` and writes each one to
+/// Reads all providers from the merged registry and writes each one to
 /// the Codex app-server under `model_providers.<provider_name>`.
 ///
 /// Built-in provider IDs (e.g. `ollama`) are skipped since the app-server
 /// rejects attempts to override them.
 ///
 /// Each provider entry is transformed from the `llms.json` schema into the
-/// app-server config schema: the `model_provider` field is renamed to `name`.
+/// app-server config schema: the `model_provider` field is renamed to `name`,
+/// `headers` becomes `http_headers`, and an empty `env_key` is omitted. The
+/// whole `model_providers.<id>` table is replaced on every write.
 pub async fn write_model_providers(client: &CodexAppServer) -> Result<(), String> {
-    let json_str = include_str!("../llms.json");
-    let config: RootConfig =
-        serde_json::from_str(json_str).map_err(|e| format!("Failed to parse llms.json: {}", e))?;
-
-    for provider in config.data {
+    for provider in load_providers()? {
         // Skip reserved built-in providers — the app-server rejects overrides.
         if BUILTIN_PROVIDER_IDS.contains(&provider.model_provider.as_str()) {
             log::debug!(
@@ -75,15 +80,20 @@ pub async fn write_model_providers(client: &CodexAppServer) -> Result<(), String
             continue;
         }
 
-        let provider_value = json!({
+        let mut provider_value = json!({
             "name": provider.model_provider,
-            "env_key": provider.env_key,
             "base_url": provider.base_url,
         });
+        if !provider.env_key.is_empty() {
+            provider_value["env_key"] = json!(provider.env_key);
+        }
+        if !provider.headers.is_empty() {
+            provider_value["http_headers"] = json!(provider.headers);
+        }
 
         let key_path = format!("model_providers.{}", provider.model_provider);
 
-        match upsert_config_value(client, &key_path, provider_value).await {
+        match replace_config_value(client, &key_path, provider_value).await {
             Ok(_) => {
                 log::debug!("Config written for provider: {}", provider.model_provider);
             }
@@ -95,3 +105,45 @@ pub async fn write_model_providers(client: &CodexAppServer) -> Result<(), String
 
     Ok(())
 }
+
+/// Remove `[model_providers.<provider_id>]` from config.toml, used when a
+/// user-only provider is deleted from the registry.
+pub fn remove_model_provider(provider_id: &str) -> Result<(), String> {
+    let config_path = get_config_path()?;
+    if !config_path.exists() {
+        return Ok(());
+    }
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to read config file: {}", e))?;
+    let mut doc = Document::from_str(&content)
+        .map_err(|e| format!("Failed to parse config file: {}", e))?;
+    let removed = doc
+        .get_mut("model_providers")
+        .and_then(Item::as_table_like_mut)
+        .and_then(|providers| providers.remove(provider_id))
+        .is_some();
+    if removed {
+        write_document_with_backup(&config_path, &doc)?;
+    }
+    Ok(())
+}
+
+/// Re-sync the app-server after the provider registry changed. `deleted` names a
+/// provider just removed from the user file; it is dropped from config.toml
+/// unless a bundled provider with the same id takes its place.
+pub async fn resync_model_providers(
+    client: Option<&CodexAppServer>,
+    deleted: Option<&str>,
+) -> Result<(), String> {
+    if let Some(id) = deleted
+        && !load_providers()?
+            .iter()
+            .any(|provider| provider.model_provider == id)
+    {
+        remove_model_provider(id)?;
+    }
+    match client {
+        Some(client) => write_model_providers(client).await,
+        None => Ok(()),
+    }
+}
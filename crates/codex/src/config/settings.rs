@@ -0,0 +1,803 @@
+//! Typed read/write access to the non-MCP parts of `~/.codex/config.toml`:
+//! model defaults, sandbox and approval policy, `shell_environment_policy`,
+//! named `[profiles.*]` and `[projects.*]` trust levels.
+//!
+//! Writes go through `toml_edit` so comments, ordering and keys Codexia does not
+//! model are left untouched. Only the managed keys a write changes are validated
+//! before the document replaces the file, so values Codexia does not model never
+//! block an unrelated edit. Reads keep enum values Codexia does not know as
+//! `Other` and skip a profile or project entry they cannot read.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::{Document, Item, Table, Value};
+
+use super::get_config_path;
+use super::toml_helpers::{serialize_to_table, write_document_with_backup};
+
+/// Implemented by the config enums, which keep values Codexia does not know
+/// as `Other` so a newer codex option never breaks reading config.toml.
+trait OpenEnum {
+    /// The raw value when it is not one Codexia knows.
+    fn other(&self) -> Option<&str>;
+}
+
+/// Reject a changed `key` whose value Codexia does not know.
+fn check_known(key: &str, value: Option<&impl OpenEnum>) -> Result<(), String> {
+    match value.and_then(OpenEnum::other) {
+        Some(value) => Err(format!("Invalid config: unknown {} '{}'", key, value)),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    None,
+    Minimal,
+    Low,
+    Medium,
+    High,
+    #[serde(rename = "xhigh")]
+    XHigh,
+    /// A value this version of Codexia does not know, kept as written.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl OpenEnum for ReasoningEffort {
+    fn other(&self) -> Option<&str> {
+        match self {
+            Self::Other(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SandboxMode {
+    ReadOnly,
+    WorkspaceWrite,
+    DangerFullAccess,
+    /// A value this version of Codexia does not know, kept as written.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl OpenEnum for SandboxMode {
+    fn other(&self) -> Option<&str> {
+        match self {
+            Self::Other(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApprovalPolicy {
+    Untrusted,
+    OnFailure,
+    OnRequest,
+    Never,
+    /// A value this version of Codexia does not know, kept as written.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl OpenEnum for ApprovalPolicy {
+    fn other(&self) -> Option<&str> {
+        match self {
+            Self::Other(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustLevel {
+    Trusted,
+    Untrusted,
+    /// A value this version of Codexia does not know, kept as written.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl OpenEnum for TrustLevel {
+    fn other(&self) -> Option<&str> {
+        match self {
+            Self::Other(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellEnvironmentInherit {
+    All,
+    Core,
+    None,
+    /// A value this version of Codexia does not know, kept as written.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl OpenEnum for ShellEnvironmentInherit {
+    fn other(&self) -> Option<&str> {
+        match self {
+            Self::Other(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// `[sandbox_workspace_write]`, only consulted when `sandbox_mode = "workspace-write"`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SandboxWorkspaceWrite {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub writable_roots: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_access: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_tmpdir_env_var: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_slash_tmp: Option<bool>,
+}
+
+impl SandboxWorkspaceWrite {
+    const KEYS: &'static [&'static str] = &[
+        "writable_roots",
+        "network_access",
+        "exclude_tmpdir_env_var",
+        "exclude_slash_tmp",
+    ];
+}
+
+/// `[shell_environment_policy]`: which environment variables reach spawned commands.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShellEnvironmentPolicy {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inherit: Option<ShellEnvironmentInherit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_default_excludes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include_only: Vec<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub set: HashMap<String, String>,
+}
+
+impl ShellEnvironmentPolicy {
+    const KEYS: &'static [&'static str] = &[
+        "inherit",
+        "ignore_default_excludes",
+        "exclude",
+        "include_only",
+        "set",
+    ];
+}
+
+/// Top-level settings Codexia edits. `None` / empty means "not set in config.toml".
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CodexSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_reasoning_effort: Option<ReasoningEffort>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<ApprovalPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox_mode: Option<SandboxMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell_environment_policy: Option<ShellEnvironmentPolicy>,
+    /// Name of the profile applied by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+impl CodexSettings {
+    const KEYS: &'static [&'static str] = &[
+        "model",
+        "model_provider",
+        "model_reasoning_effort",
+        "approval_policy",
+        "sandbox_mode",
+        "sandbox_workspace_write",
+        "shell_environment_policy",
+        "profile",
+    ];
+}
+
+/// A named `[profiles.<name>]` table.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_reasoning_effort: Option<ReasoningEffort>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<ApprovalPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox_mode: Option<SandboxMode>,
+}
+
+impl ConfigProfile {
+    const KEYS: &'static [&'static str] = &[
+        "model",
+        "model_provider",
+        "model_reasoning_effort",
+        "approval_policy",
+        "sandbox_mode",
+    ];
+
+    fn check_known(&self) -> Result<(), String> {
+        check_known("model_reasoning_effort", self.model_reasoning_effort.as_ref())?;
+        check_known("approval_policy", self.approval_policy.as_ref())?;
+        check_known("sandbox_mode", self.sandbox_mode.as_ref())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust_level: Option<TrustLevel>,
+}
+
+/// The parts of config.toml Codexia reads. Keys that are not listed here are
+/// ignored so newer codex options survive a round trip.
+#[derive(Debug, Clone)]
+struct ConfigSchema {
+    settings: CodexSettings,
+    profiles: BTreeMap<String, ConfigProfile>,
+    projects: BTreeMap<String, ProjectConfig>,
+}
+
+pub async fn read_codex_settings() -> Result<CodexSettings, String> {
+    Ok(read_schema()?.settings)
+}
+
+/// Replace the settings Codexia manages. Fields left as `None` are removed from
+/// config.toml; every other key is preserved.
+pub async fn write_codex_settings(settings: CodexSettings) -> Result<(), String> {
+    update_document(|doc| {
+        let source = serialize_to_table(&settings)?;
+        merge_managed_keys(doc.as_table_mut(), &source, CodexSettings::KEYS);
+
+        if let Some(target) = doc
+            .get_mut("sandbox_workspace_write")
+            .and_then(Item::as_table_mut)
+            && let Some(source) = source
+                .get("sandbox_workspace_write")
+                .and_then(Item::as_table)
+        {
+            merge_managed_keys(target, source, SandboxWorkspaceWrite::KEYS);
+        }
+        if let Some(target) = doc
+            .get_mut("shell_environment_policy")
+            .and_then(Item::as_table_mut)
+        {
+            if let Some(source) = source
+                .get("shell_environment_policy")
+                .and_then(Item::as_table)
+            {
+                merge_managed_keys(target, source, ShellEnvironmentPolicy::KEYS);
+            }
+            inline_table_entry(target, "set");
+        }
+        Ok(())
+    })
+}
+
+pub async fn list_profiles() -> Result<BTreeMap<String, ConfigProfile>, String> {
+    Ok(read_schema()?.profiles)
+}
+
+pub async fn create_profile(name: String, profile: ConfigProfile) -> Result<(), String> {
+    validate_profile_name(&name)?;
+    update_document(|doc| {
+        let profiles = profiles_table(doc)?;
+        if profiles.contains_key(&name) {
+            return Err(format!("Profile '{}' already exists", name));
+        }
+        profiles.insert(&name, Item::Table(serialize_to_table(&profile)?));
+        Ok(())
+    })
+}
+
+/// Update the fields Codexia models on an existing profile, keeping any other keys.
+pub async fn update_profile(name: String, profile: ConfigProfile) -> Result<(), String> {
+    update_document(|doc| {
+        let target = profiles_table(doc)?
+            .get_mut(&name)
+            .and_then(Item::as_table_mut)
+            .ok_or_else(|| format!("Profile '{}' not found", name))?;
+        let source = serialize_to_table(&profile)?;
+        merge_managed_keys(target, &source, ConfigProfile::KEYS);
+        Ok(())
+    })
+}
+
+/// Copy `[profiles.<source>]` verbatim (including keys Codexia does not model) to `target`.
+pub async fn clone_profile(source: String, target: String) -> Result<(), String> {
+    validate_profile_name(&target)?;
+    update_document(|doc| {
+        let profiles = profiles_table(doc)?;
+        if profiles.contains_key(&target) {
+            return Err(format!("Profile '{}' already exists", target));
+        }
+        let original = profiles
+            .get(&source)
+            .and_then(Item::as_table)
+            .ok_or_else(|| format!("Profile '{}' not found", source))?;
+        // A fresh table has no document position, so it is emitted after the
+        // existing profiles instead of sharing the source's slot and comments.
+        let mut copy = Table::new();
+        for (key, item) in original.iter() {
+            copy.insert(key, item.clone());
+        }
+        profiles.insert(&target, Item::Table(copy));
+        Ok(())
+    })
+}
+
+/// Delete a profile. If it was the active `profile`, that key is removed too.
+pub async fn delete_profile(name: String) -> Result<(), String> {
+    update_document(|doc| {
+        if profiles_table(doc)?.remove(&name).is_none() {
+            return Err(format!("Profile '{}' not found", name));
+        }
+        if doc.get("profile").and_then(Item::as_str) == Some(name.as_str()) {
+            doc.remove("profile");
+        }
+        Ok(())
+    })
+}
+
+pub async fn set_active_profile(name: Option<String>) -> Result<(), String> {
+    update_document(|doc| {
+        match name {
+            Some(name) => {
+                doc.insert("profile", toml_edit::value(name));
+            }
+            None => {
+                doc.remove("profile");
+            }
+        }
+        Ok(())
+    })
+}
+
+pub async fn read_project_trust() -> Result<BTreeMap<String, TrustLevel>, String> {
+    Ok(read_schema()?
+        .projects
+        .into_iter()
+        .filter_map(|(path, project)| project.trust_level.map(|level| (path, level)))
+        .collect())
+}
+
+/// Set or clear `[projects."<path>"].trust_level`.
+pub async fn set_project_trust(
+    path: String,
+    trust_level: Option<TrustLevel>,
+) -> Result<(), String> {
+    if !Path::new(&path).is_absolute() {
+        return Err(format!("Project path must be absolute: {}", path));
+    }
+    update_document(|doc| edit_project_trust(doc, &path, trust_level))
+}
+
+fn edit_project_trust(
+    doc: &mut Document,
+    path: &str,
+    trust_level: Option<TrustLevel>,
+) -> Result<(), String> {
+    let projects = implicit_table(doc.as_table_mut(), "projects")?;
+    match trust_level {
+        Some(level) => {
+            let project = implicit_table(projects, path)?;
+            project.set_implicit(false);
+            project.insert("trust_level", toml_edit::value(trust_level_str(&level)));
+        }
+        None => {
+            if let Some(project) = projects.get_mut(path).and_then(Item::as_table_mut) {
+                project.remove("trust_level");
+                if project.is_empty() {
+                    projects.remove(path);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check the managed keys that differ between `before` and `after` against
+/// their types plus the cross-field rules codex enforces at startup. Keys an edit
+/// did not touch are not re-checked, so an existing value Codexia does not model
+/// (a newer enum variant, an unusual profile name) does not block the write.
+pub fn validate_config_changes(before: &Document, after: &Document) -> Result<(), String> {
+    let invalid = |e: toml::de::Error| format!("Invalid config: {}", e);
+
+    let settings_table = changed_keys(Some(before.as_table()), after.as_table(), CodexSettings::KEYS);
+    let settings: CodexSettings = toml::from_str(&settings_table.to_string()).map_err(invalid)?;
+    check_known("model_reasoning_effort", settings.model_reasoning_effort.as_ref())?;
+    check_known("approval_policy", settings.approval_policy.as_ref())?;
+    check_known("sandbox_mode", settings.sandbox_mode.as_ref())?;
+    check_known(
+        "shell_environment_policy.inherit",
+        settings
+            .shell_environment_policy
+            .as_ref()
+            .and_then(|policy| policy.inherit.as_ref()),
+    )?;
+    if let Some(sandbox) = &settings.sandbox_workspace_write
+        && let Some(root) = sandbox
+            .writable_roots
+            .iter()
+            .find(|root| !Path::new(root).is_absolute())
+    {
+        return Err(format!(
+            "Invalid config: writable root must be absolute: {}",
+            root
+        ));
+    }
+    if let Some(policy) = &settings.shell_environment_policy
+        && let Some(key) = policy
+            .set
+            .keys()
+            .find(|key| key.is_empty() || key.contains('='))
+    {
+        return Err(format!(
+            "Invalid config: bad environment variable name '{}'",
+            key
+        ));
+    }
+
+    let before_profiles = sub_tables(before, "profiles");
+    for (name, profile) in sub_tables(after, "profiles") {
+        let previous = before_profiles.get(name).copied();
+        if previous.is_none() {
+            validate_profile_name(name).map_err(|e| format!("Invalid config: {}", e))?;
+        }
+        let changed = changed_keys(previous, profile, ConfigProfile::KEYS);
+        toml::from_str::<ConfigProfile>(&changed.to_string())
+            .map_err(invalid)?
+            .check_known()?;
+    }
+
+    let before_projects = sub_tables(before, "projects");
+    for (path, project) in sub_tables(after, "projects") {
+        let changed = changed_keys(before_projects.get(path).copied(), project, &["trust_level"]);
+        let project = toml::from_str::<ProjectConfig>(&changed.to_string()).map_err(invalid)?;
+        check_known("trust_level", project.trust_level.as_ref())?;
+    }
+
+    let dangling = dangling_active_profile(after);
+    if dangling.is_some() && dangling != dangling_active_profile(before) {
+        return Err(format!(
+            "Invalid config: active profile '{}' is not defined",
+            dangling.unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
+/// The `keys` of `after` whose value differs from `before`, as a document so
+/// nested tables are serialized too.
+fn changed_keys(before: Option<&Table>, after: &Table, keys: &[&str]) -> Document {
+    let mut changed = Document::new();
+    for key in keys {
+        if let Some(item) = after.get(key)
+            && before.and_then(|table| table.get(key)).map(ToString::to_string)
+                != Some(item.to_string())
+        {
+            changed.insert(key, item.clone());
+        }
+    }
+    changed
+}
+
+/// The table-valued entries of the top-level table `key`, such as each profile.
+fn sub_tables<'a>(doc: &'a Document, key: &str) -> BTreeMap<&'a str, &'a Table> {
+    doc.get(key)
+        .and_then(Item::as_table)
+        .map(|table| {
+            table
+                .iter()
+                .filter_map(|(name, item)| item.as_table().map(|table| (name, table)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The active `profile` when it names no `[profiles.<name>]` table.
+fn dangling_active_profile(doc: &Document) -> Option<String> {
+    let profile = doc.get("profile").and_then(Item::as_str)?;
+    let defined = doc
+        .get("profiles")
+        .and_then(Item::as_table_like)
+        .is_some_and(|profiles| profiles.contains_key(profile));
+    (!defined).then(|| profile.to_string())
+}
+
+fn read_schema() -> Result<ConfigSchema, String> {
+    let config_path = get_config_path()?;
+    if !config_path.exists() {
+        return parse_schema("");
+    }
+    let content = std::fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to read config file: {}", e))?;
+    parse_schema(&content)
+}
+
+/// Parse the managed parts of config.toml. Profiles and projects are read one
+/// entry at a time, so an entry Codexia cannot read is skipped on its own.
+fn parse_schema(content: &str) -> Result<ConfigSchema, String> {
+    let mut root: toml::Table =
+        toml::from_str(content).map_err(|e| format!("Failed to parse config file: {}", e))?;
+    let profiles = parse_entries(root.remove("profiles"), "profiles");
+    let projects = parse_entries(root.remove("projects"), "projects");
+    let settings = toml::Value::Table(root)
+        .try_into()
+        .map_err(|e| format!("Failed to parse config file: {}", e))?;
+    Ok(ConfigSchema { settings, profiles, projects })
+}
+
+fn parse_entries<T: DeserializeOwned>(table: Option<toml::Value>, key: &str) -> BTreeMap<String, T> {
+    let Some(toml::Value::Table(entries)) = table else {
+        return BTreeMap::new();
+    };
+    entries
+        .into_iter()
+        .filter_map(|(name, value)| match value.try_into() {
+            Ok(entry) => Some((name, entry)),
+            Err(e) => {
+                log::warn!("Skipping [{}.\"{}\"] in config.toml: {}", key, name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Load config.toml, apply `edit`, validate the result and write it back.
+fn update_document<F>(edit: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Document) -> Result<(), String>,
+{
+    let config_path = get_config_path()?;
+    let mut doc = if config_path.exists() {
+        let content = std::fs::read_to_string(&config_path)
+            .map_err(|e| format!("Failed to read config file: {}", e))?;
+        Document::from_str(&content).map_err(|e| format!("Failed to parse config file: {}", e))?
+    } else {
+        Document::new()
+    };
+
+    apply_edit(&mut doc, edit)?;
+    write_document_with_backup(&config_path, &doc)
+}
+
+/// Apply `edit` and validate what it changed, leaving `doc` untouched on error.
+fn apply_edit<F>(doc: &mut Document, edit: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Document) -> Result<(), String>,
+{
+    let mut edited = doc.clone();
+    edit(&mut edited)?;
+    validate_config_changes(doc, &edited)?;
+    *doc = edited;
+    Ok(())
+}
+
+/// Set each of `keys` from `source`, or remove it when `source` does not have it.
+/// Existing sub-tables are merged in place so their comments and unknown keys survive.
+fn merge_managed_keys(target: &mut Table, source: &Table, keys: &[&str]) {
+    for key in keys {
+        match source.get(key) {
+            Some(Item::Table(_)) if target.get(key).is_some_and(Item::is_table) => {
+                // Merged field by field by the caller.
+            }
+            Some(item) => {
+                // Keep the comment attached to a value that is only being changed.
+                if let (Some(Item::Value(existing_value)), Item::Value(new_value)) =
+                    (target.get_mut(key), item)
+                {
+                    let decor = existing_value.decor().clone();
+                    let mut new_value = new_value.clone();
+                    *new_value.decor_mut() = decor;
+                    *existing_value = new_value;
+                    continue;
+                }
+                target.insert(key, item.clone());
+            }
+            None => {
+                target.remove(key);
+            }
+        }
+    }
+}
+
+fn inline_table_entry(table: &mut Table, key: &str) {
+    if let Some(entry) = table.get_mut(key)
+        && entry.is_table()
+        && let Item::Table(inner) = std::mem::take(entry)
+    {
+        *entry = Item::Value(Value::InlineTable(inner.into_inline_table()));
+    }
+}
+
+fn implicit_table<'a>(parent: &'a mut Table, key: &str) -> Result<&'a mut Table, String> {
+    let entry = parent.entry(key).or_insert_with(|| {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        Item::Table(table)
+    });
+    entry
+        .as_table_mut()
+        .ok_or_else(|| format!("`{}` in config.toml is not a table", key))
+}
+
+fn profiles_table(doc: &mut Document) -> Result<&mut Table, String> {
+    implicit_table(doc.as_table_mut(), "profiles")
+}
+
+fn validate_profile_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Profile name '{}' may only contain letters, digits, '-' and '_'",
+            name
+        ))
+    }
+}
+
+fn trust_level_str(level: &TrustLevel) -> &str {
+    match level {
+        TrustLevel::Trusted => "trusted",
+        TrustLevel::Untrusted => "untrusted",
+        TrustLevel::Other(value) => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Document {
+        Document::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn merge_managed_keys_keeps_comments_and_unmodeled_keys() {
+        let mut doc = parse(
+            "# default model\nmodel = \"o3\" # pinned\napproval_policy = \"never\"\nhide_agent_reasoning = true\n",
+        );
+        let settings = CodexSettings {
+            model: Some("gpt-5".to_string()),
+            sandbox_mode: Some(SandboxMode::ReadOnly),
+            ..Default::default()
+        };
+        let source = serialize_to_table(&settings).unwrap();
+        merge_managed_keys(doc.as_table_mut(), &source, CodexSettings::KEYS);
+
+        let written = doc.to_string();
+        assert!(written.contains("# default model\nmodel = \"gpt-5\" # pinned\n"));
+        assert!(written.contains("hide_agent_reasoning = true"));
+        assert!(written.contains("sandbox_mode = \"read-only\""));
+        assert!(!written.contains("approval_policy"));
+    }
+
+    #[test]
+    fn unmodeled_values_do_not_block_unrelated_writes() {
+        let mut doc = parse(concat!(
+            "approval_policy = \"ask-later\"\n",
+            "profile = \"team.dev\"\n",
+            "[profiles.\"team.dev\"]\n",
+            "sandbox_mode = \"future-mode\"\n",
+            "[mcp_servers.legacy]\n",
+            "transport = \"pipe\"\n",
+            "[agents]\n",
+            "max_threads = \"many\"\n",
+        ));
+        apply_edit(&mut doc, |doc| {
+            edit_project_trust(doc, "/work/app", Some(TrustLevel::Trusted))
+        })
+        .unwrap();
+        assert!(doc.to_string().contains("trust_level = \"trusted\""));
+        assert!(doc.to_string().contains("approval_policy = \"ask-later\""));
+    }
+
+    #[test]
+    fn changed_keys_are_still_validated() {
+        let mut doc = parse("model = \"o3\"\n");
+        let err = apply_edit(&mut doc, |doc| {
+            doc.insert("approval_policy", toml_edit::value("sometimes"));
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(err.starts_with("Invalid config"), "{}", err);
+        assert_eq!(doc.to_string(), "model = \"o3\"\n");
+
+        let err = apply_edit(&mut doc, |doc| {
+            doc.insert("profile", toml_edit::value("missing"));
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(err.contains("'missing' is not defined"), "{}", err);
+
+        let err = apply_edit(&mut doc, |doc| {
+            profiles_table(doc)?.insert("a.b", Item::Table(Table::new()));
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(err.contains("Profile name 'a.b'"), "{}", err);
+
+        let err = apply_edit(&mut doc, |doc| {
+            let mut sandbox = Table::new();
+            sandbox.insert("writable_roots", toml_edit::value(Value::from_iter(["rel/dir"])));
+            doc.insert("sandbox_workspace_write", Item::Table(sandbox));
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(err.contains("writable root must be absolute"), "{}", err);
+
+        let err = apply_edit(&mut doc, |doc| {
+            edit_project_trust(doc, "/work/app", Some(TrustLevel::Other("maybe".to_string())))
+        })
+        .unwrap_err();
+        assert!(err.contains("unknown trust_level 'maybe'"), "{}", err);
+    }
+
+    #[test]
+    fn reads_keep_unknown_values_and_skip_unreadable_entries() {
+        let schema = parse_schema(concat!(
+            "approval_policy = \"ask-later\"\n",
+            "sandbox_mode = \"read-only\"\n",
+            "[profiles.fast]\n",
+            "model_reasoning_effort = \"turbo\"\n",
+            "[profiles.broken]\n",
+            "model = 5\n",
+            "[projects.\"/work/app\"]\n",
+            "trust_level = \"partial\"\n",
+            "[projects.\"/work/lib\"]\n",
+            "trust_level = \"trusted\"\n",
+        ))
+        .unwrap();
+        assert_eq!(schema.settings.approval_policy, Some(ApprovalPolicy::Other("ask-later".to_string())));
+        assert_eq!(schema.settings.sandbox_mode, Some(SandboxMode::ReadOnly));
+        assert_eq!(schema.profiles.keys().collect::<Vec<_>>(), ["fast"]);
+        assert_eq!(
+            schema.profiles["fast"].model_reasoning_effort,
+            Some(ReasoningEffort::Other("turbo".to_string()))
+        );
+        assert_eq!(schema.projects["/work/lib"].trust_level, Some(TrustLevel::Trusted));
+
+        // An unknown value read back and written unchanged is not re-validated.
+        let mut doc = parse("approval_policy = \"ask-later\"\nmodel = \"o3\"\n");
+        let settings = schema.settings.clone();
+        apply_edit(&mut doc, |doc| {
+            let source = serialize_to_table(&settings)?;
+            merge_managed_keys(doc.as_table_mut(), &source, CodexSettings::KEYS);
+            Ok(())
+        })
+        .unwrap();
+        assert!(doc.to_string().contains("approval_policy = \"ask-later\""));
+    }
+}
@@ -1,9 +1,6 @@
+use codexia_shared::config_backup;
 use serde::Serialize;
-use std::{
-    fs,
-    path::{Path, PathBuf},
-    str::FromStr,
-};
+use std::{fs, path::Path, str::FromStr};
 use toml_edit::{Document, Table};
 
 pub fn serialize_to_table<T: Serialize>(value: &T) -> Result<Table, String> {
@@ -14,34 +11,19 @@ pub fn serialize_to_table<T: Serialize>(value: &T) -> Result<Table, String> {
     Ok(doc.as_table().clone())
 }
 
-fn make_backup_path(target: &Path) -> PathBuf {
-    target.with_extension("toml.bak")
-}
-
+/// Write `doc` to `path`, keeping the previous content in the shared config
+/// backup history. The original content is put back if the written file does
+/// not parse.
 pub fn write_document_with_backup(path: &Path, doc: &Document) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
-
-    let backup_path = if path.exists() {
-        let backup = make_backup_path(path);
-        fs::copy(path, &backup).map_err(|e| format!("Failed to backup config file: {}", e))?;
-        Some(backup)
-    } else {
-        None
-    };
-
-    let content = doc.to_string();
-    fs::write(path, content.as_bytes())
-        .map_err(|e| format!("Failed to write config file: {}", e))?;
+    let previous = fs::read_to_string(path).ok();
+    config_backup::write_with_backup(path, &doc.to_string())?;
 
     let written_content = fs::read_to_string(path)
         .map_err(|e| format!("Failed to read config file after writing: {}", e))?;
 
     if let Err(parse_error) = Document::from_str(&written_content) {
-        if let Some(ref backup) = backup_path {
-            fs::copy(backup, path)
+        if let Some(previous) = previous {
+            fs::write(path, previous)
                 .map_err(|e| format!("Failed to restore config from backup: {}", e))?;
         }
         return Err(format!(
@@ -50,9 +32,5 @@ pub fn write_document_with_backup(path: &Path, doc: &Document) -> Result<(), Str
         ));
     }
 
-    if let Some(backup) = backup_path {
-        fs::remove_file(backup).map_err(|e| format!("Failed to remove config backup: {}", e))?;
-    }
-
     Ok(())
 }
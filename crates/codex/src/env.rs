@@ -5,6 +5,11 @@ use std::process::Command;
 use winreg::{enums::*, RegKey};
 
 pub fn get_env(key: String) -> Result<String, String> {
+    // Keys saved through Codexia take precedence over the shell environment.
+    if let Some(value) = crate::secrets::get_secret(&key) {
+        return Ok(value);
+    }
+
     #[cfg(target_os = "windows")]
     {
         let root = RegKey::predef(HKEY_CURRENT_USER);
@@ -76,8 +81,7 @@ pub fn set_env(key: String, value: String) -> Result<(), String> {
 
     #[cfg(not(any(target_os = "windows", target_os = "macos")))]
     {
-        let _ = key;
-        let _ = value;
+        crate::secrets::set_secret(&key, &value)?;
         Ok(())
     }
 }
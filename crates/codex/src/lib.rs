@@ -1,8 +1,15 @@
 pub mod app_server;
+pub mod approval_audit;
 pub mod config;
 pub mod env;
+pub mod mcp_health;
+pub mod export;
 pub mod providers;
+pub mod rate_limits;
+pub mod rollout;
 pub mod scan;
+pub mod search;
+pub mod secrets;
 mod server_request;
 pub mod utils;
 
@@ -0,0 +1,731 @@
+//! Health checks for MCP servers.
+//!
+//! A probe starts a stdio server (or connects to an http / sse URL), performs
+//! the MCP `initialize` handshake and lists the server's tools, prompts and
+//! resources. Results are cached per server name and config, like provider
+//! model discovery, so settings and marketplace views can show them cheaply.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+use crate::config::mcp::{McpHttpAuth, McpServerConfig};
+
+pub const DEFAULT_PROBE_TIMEOUT_SECS: u64 = 20;
+/// How long a healthy result is reused.
+pub const HEALTH_CACHE_TTL: Duration = Duration::from_secs(300);
+/// Failures are retried sooner so a fixed server is picked up quickly.
+pub const FAILED_HEALTH_CACHE_TTL: Duration = Duration::from_secs(30);
+const PROTOCOL_VERSION: &str = "2025-03-26";
+/// Only the end of a server's stderr is kept.
+const MAX_STDERR_LEN: usize = 4000;
+/// Stop following `nextCursor` after this many pages.
+const MAX_LIST_PAGES: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpHealthStatus {
+    Healthy,
+    /// The server failed to start, rejected the handshake or a listing.
+    Failed,
+    TimedOut,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct McpListItem {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Set for resources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct McpHealth {
+    pub name: String,
+    pub status: McpHealthStatus,
+    /// `serverInfo` from the `initialize` result.
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub protocol_version: Option<String>,
+    pub tools: Vec<McpListItem>,
+    pub prompts: Vec<McpListItem>,
+    pub resources: Vec<McpListItem>,
+    pub error: Option<String>,
+    /// Tail of a stdio server's stderr, kept when the probe did not succeed.
+    pub stderr: Option<String>,
+    pub latency_ms: u64,
+    /// Unix milliseconds.
+    pub checked_at: i64,
+}
+
+/// Probe the servers in codex `config.toml`, or only `names` when given.
+/// Disabled servers are probed too; cached results are reused unless
+/// `force_refresh` is set.
+pub async fn check_mcp_servers(
+    names: Option<Vec<String>>,
+    force_refresh: bool,
+    timeout_secs: Option<u64>,
+) -> Result<Vec<McpHealth>, String> {
+    let servers = crate::config::mcp::read_mcp_servers().await?;
+    let mut selected: Vec<(String, McpServerConfig)> = match names {
+        Some(names) => names
+            .into_iter()
+            .map(|name| {
+                let config = servers
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| format!("MCP server '{}' not found", name))?;
+                Ok((name, config))
+            })
+            .collect::<Result<_, String>>()?,
+        None => servers.into_iter().collect(),
+    };
+    selected.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut result: Vec<Option<McpHealth>> = vec![None; selected.len()];
+    let mut tasks = tokio::task::JoinSet::new();
+    for (index, (name, config)) in selected.into_iter().enumerate() {
+        if !force_refresh && let Some(cached) = cached_health(&health_cache_key(&name, &config)) {
+            result[index] = Some(cached);
+            continue;
+        }
+        tasks.spawn(async move { (index, probe_mcp_server(&name, &config, timeout_secs).await) });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        let (index, health) = joined.map_err(|e| format!("MCP probe task failed: {}", e))?;
+        result[index] = Some(health);
+    }
+    Ok(result.into_iter().flatten().collect())
+}
+
+/// Probe one server config, which need not be in `config.toml` yet, and
+/// cache the result.
+pub async fn probe_mcp_server(
+    name: &str,
+    config: &McpServerConfig,
+    timeout_secs: Option<u64>,
+) -> McpHealth {
+    // A configured startup timeout longer than the default also bounds the probe.
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or_else(|| {
+        config
+            .timeouts()
+            .startup_timeout_sec
+            .map_or(DEFAULT_PROBE_TIMEOUT_SECS, |secs| {
+                (secs.ceil() as u64).max(DEFAULT_PROBE_TIMEOUT_SECS)
+            })
+    }));
+    let started = Instant::now();
+    let stderr = Arc::new(Mutex::new(String::new()));
+    let mut health = McpHealth {
+        name: name.to_string(),
+        status: McpHealthStatus::Healthy,
+        server_name: None,
+        server_version: None,
+        protocol_version: None,
+        tools: Vec::new(),
+        prompts: Vec::new(),
+        resources: Vec::new(),
+        error: None,
+        stderr: None,
+        latency_ms: 0,
+        checked_at: chrono::Utc::now().timestamp_millis(),
+    };
+
+    match tokio::time::timeout(timeout, run_probe(config, &stderr, &mut health)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            health.status = McpHealthStatus::Failed;
+            health.error = Some(e);
+        }
+        Err(_) => {
+            health.status = McpHealthStatus::TimedOut;
+            health.error = Some(format!("No complete handshake within {}s", timeout.as_secs()));
+        }
+    }
+    health.latency_ms = started.elapsed().as_millis() as u64;
+    if health.status != McpHealthStatus::Healthy {
+        health.stderr = stderr
+            .lock()
+            .ok()
+            .map(|stderr| stderr.trim().to_string())
+            .filter(|stderr| !stderr.is_empty());
+    }
+    store_health(health_cache_key(name, config), &health);
+    health
+}
+
+async fn run_probe(
+    config: &McpServerConfig,
+    stderr: &Arc<Mutex<String>>,
+    health: &mut McpHealth,
+) -> Result<(), String> {
+    let mut transport = match config {
+        McpServerConfig::Stdio { command, args, env, cwd, .. } => {
+            Transport::spawn(command, args, env.as_ref(), cwd.as_deref(), stderr)?
+        }
+        McpServerConfig::Http { url, auth, .. } => Transport::Http(HttpTransport {
+            client: http_client(auth)?,
+            url: url.clone(),
+            session_id: None,
+            next_id: 0,
+        }),
+        McpServerConfig::Sse { url, auth, .. } => {
+            Transport::connect_sse(url, http_client(auth)?).await?
+        }
+    };
+
+    let initialized = transport
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "codexia", "version": env!("CARGO_PKG_VERSION") },
+            }),
+        )
+        .await?;
+    health.protocol_version = initialized["protocolVersion"].as_str().map(str::to_string);
+    health.server_name = initialized["serverInfo"]["name"].as_str().map(str::to_string);
+    health.server_version = initialized["serverInfo"]["version"].as_str().map(str::to_string);
+    transport.notify("notifications/initialized").await?;
+
+    let capabilities = &initialized["capabilities"];
+    if capabilities.get("tools").is_some() {
+        health.tools = transport.list_all("tools/list", "tools").await?;
+    }
+    if capabilities.get("prompts").is_some() {
+        health.prompts = transport.list_all("prompts/list", "prompts").await?;
+    }
+    if capabilities.get("resources").is_some() {
+        health.resources = transport.list_all("resources/list", "resources").await?;
+    }
+    transport.close().await;
+    Ok(())
+}
+
+enum Transport {
+    Stdio(StdioTransport),
+    Http(HttpTransport),
+    Sse(SseTransport),
+}
+
+struct StdioTransport {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    next_id: u64,
+}
+
+struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    /// `Mcp-Session-Id` assigned by the server on `initialize`.
+    session_id: Option<String>,
+    next_id: u64,
+}
+
+struct SseTransport {
+    client: reqwest::Client,
+    /// Where requests are posted, announced by the `endpoint` event.
+    endpoint: String,
+    events: SseStream,
+    next_id: u64,
+}
+
+impl Transport {
+    fn spawn(
+        command: &str,
+        args: &[String],
+        env: Option<&HashMap<String, String>>,
+        cwd: Option<&str>,
+        stderr: &Arc<Mutex<String>>,
+    ) -> Result<Self, String> {
+        let mut command_builder = Command::new(command);
+        if let Some(cwd) = cwd {
+            command_builder.current_dir(cwd);
+        }
+        let mut child = command_builder
+            .args(args)
+            .envs(env.into_iter().flatten())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start '{}': {}", command, e))?;
+        let stdin = child.stdin.take().ok_or("Failed to open server stdin")?;
+        let stdout = child.stdout.take().ok_or("Failed to open server stdout")?;
+        if let Some(mut pipe) = child.stderr.take() {
+            let stderr = Arc::clone(stderr);
+            tokio::spawn(async move {
+                let mut buffer = [0u8; 1024];
+                while let Ok(read) = pipe.read(&mut buffer).await {
+                    if read == 0 {
+                        break;
+                    }
+                    if let Ok(mut stderr) = stderr.lock() {
+                        stderr.push_str(&String::from_utf8_lossy(&buffer[..read]));
+                        if stderr.len() > MAX_STDERR_LEN {
+                            let mut cut = stderr.len() - MAX_STDERR_LEN;
+                            while !stderr.is_char_boundary(cut) {
+                                cut += 1;
+                            }
+                            stderr.drain(..cut);
+                        }
+                    }
+                }
+            });
+        }
+        Ok(Transport::Stdio(StdioTransport {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            next_id: 0,
+        }))
+    }
+
+    async fn connect_sse(url: &str, client: reqwest::Client) -> Result<Self, String> {
+        let response = client
+            .get(url)
+            .header("Accept", "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+        let response = check_status(url, response).await?;
+        let mut events = SseStream::new(response);
+        let endpoint = loop {
+            let event = events.next().await?.ok_or("SSE stream closed before the endpoint event")?;
+            if event.event == "endpoint" {
+                break event.data;
+            }
+        };
+        let endpoint = reqwest::Url::parse(url)
+            .and_then(|base| base.join(endpoint.trim()))
+            .map_err(|e| format!("Invalid SSE endpoint '{}': {}", endpoint, e))?;
+        Ok(Transport::Sse(SseTransport {
+            client,
+            endpoint: endpoint.to_string(),
+            events,
+            next_id: 0,
+        }))
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        match self {
+            Transport::Stdio(stdio) => stdio.request(method, params).await,
+            Transport::Http(http) => http.request(method, params).await,
+            Transport::Sse(sse) => sse.request(method, params).await,
+        }
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        let message = json!({ "jsonrpc": "2.0", "method": method });
+        match self {
+            Transport::Stdio(stdio) => stdio.send(&message).await,
+            Transport::Http(http) => http.post(&message).await.map(|_| ()),
+            Transport::Sse(sse) => sse.post(&message).await,
+        }
+    }
+
+    /// Follow `nextCursor` through a paginated listing.
+    async fn list_all(&mut self, method: &str, key: &str) -> Result<Vec<McpListItem>, String> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+        for _ in 0..MAX_LIST_PAGES {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let page = self.request(method, params).await?;
+            items.extend(page[key].as_array().into_iter().flatten().map(|item| McpListItem {
+                name: item["name"].as_str().unwrap_or_default().to_string(),
+                description: item["description"].as_str().map(str::to_string),
+                uri: item["uri"].as_str().map(str::to_string),
+            }));
+            cursor = page["nextCursor"].as_str().map(str::to_string);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    async fn close(self) {
+        if let Transport::Stdio(mut stdio) = self {
+            drop(stdio.stdin);
+            let _ = stdio.child.kill().await;
+        }
+    }
+}
+
+/// A client that sends the server's configured headers with every request.
+fn http_client(auth: &McpHttpAuth) -> Result<reqwest::Client, String> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in auth.resolve_headers() {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| format!("Invalid header name '{}': {}", name, e))?;
+        let value = reqwest::header::HeaderValue::from_str(&value)
+            .map_err(|e| format!("Invalid value for header '{}': {}", name, e))?;
+        headers.insert(name, value);
+    }
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// A request with the transport's next id.
+fn rpc_request(next_id: &mut u64, method: &str, params: Value) -> (u64, Value) {
+    let id = *next_id;
+    *next_id += 1;
+    (id, json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+}
+
+/// The result of `message` if it answers request `id`.
+fn rpc_response(message: &Value, id: u64) -> Option<Result<Value, String>> {
+    if message.get("method").is_some() || message["id"].as_u64() != Some(id) {
+        return None;
+    }
+    Some(match message.get("error") {
+        Some(error) => Err(format!(
+            "Server error {}: {}",
+            error["code"],
+            error["message"].as_str().unwrap_or("unknown error")
+        )),
+        None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+    })
+}
+
+impl StdioTransport {
+    async fn send(&mut self, message: &Value) -> Result<(), String> {
+        let mut line = message.to_string();
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to server: {}", e))?;
+        self.stdin.flush().await.map_err(|e| format!("Failed to write to server: {}", e))
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let (id, message) = rpc_request(&mut self.next_id, method, params);
+        self.send(&message).await?;
+        loop {
+            let line = self
+                .stdout
+                .next_line()
+                .await
+                .map_err(|e| format!("Failed to read from server: {}", e))?;
+            let Some(line) = line else {
+                let status = self.child.try_wait().ok().flatten();
+                return Err(match status {
+                    Some(status) => format!("Server exited ({}) before answering {}", status, method),
+                    None => format!("Server closed stdout before answering {}", method),
+                });
+            };
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            if let Some(response) = rpc_response(&message, id) {
+                return response;
+            }
+            // Answer server requests such as `ping` so the server is not left waiting.
+            if let (Some(request_id), Some(method)) = (message.get("id"), message["method"].as_str()) {
+                let reply = if method == "ping" {
+                    json!({ "jsonrpc": "2.0", "id": request_id, "result": {} })
+                } else {
+                    json!({ "jsonrpc": "2.0", "id": request_id,
+                            "error": { "code": -32601, "message": "Method not found" } })
+                };
+                self.send(&reply).await?;
+            }
+        }
+    }
+}
+
+impl HttpTransport {
+    async fn post(&mut self, message: &Value) -> Result<reqwest::Response, String> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Accept", "application/json, text/event-stream")
+            .json(message);
+        if let Some(session_id) = &self.session_id {
+            request = request.header("Mcp-Session-Id", session_id);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", self.url, e))?;
+        if let Some(session_id) = response.headers().get("mcp-session-id").and_then(|v| v.to_str().ok()) {
+            self.session_id = Some(session_id.to_string());
+        }
+        check_status(&self.url, response).await
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let (id, message) = rpc_request(&mut self.next_id, method, params);
+        let response = self.post(&message).await?;
+        let is_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        if !is_stream {
+            let message: Value = response
+                .json()
+                .await
+                .map_err(|e| format!("Unexpected response from {}: {}", self.url, e))?;
+            return rpc_response(&message, id)
+                .unwrap_or_else(|| Err(format!("{} did not answer {}", self.url, method)));
+        }
+        let mut events = SseStream::new(response);
+        while let Some(event) = events.next().await? {
+            if let Ok(message) = serde_json::from_str::<Value>(&event.data)
+                && let Some(response) = rpc_response(&message, id)
+            {
+                return response;
+            }
+        }
+        Err(format!("{} closed the stream before answering {}", self.url, method))
+    }
+}
+
+impl SseTransport {
+    async fn post(&mut self, message: &Value) -> Result<(), String> {
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .json(message)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", self.endpoint, e))?;
+        check_status(&self.endpoint, response).await.map(|_| ())
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let (id, message) = rpc_request(&mut self.next_id, method, params);
+        self.post(&message).await?;
+        while let Some(event) = self.events.next().await? {
+            if let Ok(message) = serde_json::from_str::<Value>(&event.data)
+                && let Some(response) = rpc_response(&message, id)
+            {
+                return response;
+            }
+        }
+        Err(format!("SSE stream closed before answering {}", method))
+    }
+}
+
+/// Turn a non-success response into an error carrying the start of its body.
+async fn check_status(url: &str, response: reqwest::Response) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let body: String = body.trim().chars().take(500).collect();
+    Err(if body.is_empty() {
+        format!("{} returned HTTP {}", url, status.as_u16())
+    } else {
+        format!("{} returned HTTP {}: {}", url, status.as_u16(), body)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// Incremental parser for `text/event-stream` bodies.
+#[derive(Default)]
+struct SseParser {
+    pending: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.pending.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(SseEvent {
+                        event: self.event.take().unwrap_or_else(|| "message".to_string()),
+                        data: self.data.join("\n"),
+                    });
+                }
+                self.event = None;
+                self.data.clear();
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = Some(value.to_string()),
+                "data" => self.data.push(value.to_string()),
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+struct SseStream {
+    response: reqwest::Response,
+    parser: SseParser,
+    ready: VecDeque<SseEvent>,
+}
+
+impl SseStream {
+    fn new(response: reqwest::Response) -> Self {
+        Self { response, parser: SseParser::default(), ready: VecDeque::new() }
+    }
+
+    async fn next(&mut self) -> Result<Option<SseEvent>, String> {
+        loop {
+            if let Some(event) = self.ready.pop_front() {
+                return Ok(Some(event));
+            }
+            let chunk = self
+                .response
+                .chunk()
+                .await
+                .map_err(|e| format!("Failed to read event stream: {}", e))?;
+            let Some(chunk) = chunk else {
+                return Ok(None);
+            };
+            self.ready.extend(self.parser.push(&chunk));
+        }
+    }
+}
+
+fn health_cache() -> &'static Mutex<HashMap<String, (Instant, McpHealth)>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, (Instant, McpHealth)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Servers are cached by name and config so editing a server invalidates its entry.
+fn health_cache_key(name: &str, config: &McpServerConfig) -> String {
+    format!("{}\n{}", name, serde_json::to_string(config).unwrap_or_default())
+}
+
+fn cached_health(key: &str) -> Option<McpHealth> {
+    let cache = health_cache().lock().ok()?;
+    let (checked_at, health) = cache.get(key)?;
+    let ttl = if health.status == McpHealthStatus::Healthy {
+        HEALTH_CACHE_TTL
+    } else {
+        FAILED_HEALTH_CACHE_TTL
+    };
+    (checked_at.elapsed() < ttl).then(|| health.clone())
+}
+
+fn store_health(key: String, health: &McpHealth) {
+    if let Ok(mut cache) = health_cache().lock() {
+        cache.insert(key, (Instant::now(), health.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    #[test]
+    fn sse_parser_joins_data_lines_across_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"event: endpoint\r\ndata: /mess").is_empty());
+        let events = parser.push(b"ages?id=1\r\n\r\ndata: a\ndata: b\n\n");
+        assert_eq!(
+            events,
+            [
+                SseEvent { event: "endpoint".to_string(), data: "/messages?id=1".to_string() },
+                SseEvent { event: "message".to_string(), data: "a\nb".to_string() },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn probe_lists_tools_over_http() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(POST)
+                    .path("/mcp")
+                    .header("x-api-key", "k1")
+                    .json_body_includes(r#"{ "method": "initialize" }"#);
+                then.status(200).header("Mcp-Session-Id", "s1").json_body(json!({
+                    "jsonrpc": "2.0",
+                    "id": 0,
+                    "result": {
+                        "protocolVersion": PROTOCOL_VERSION,
+                        "capabilities": { "tools": {} },
+                        "serverInfo": { "name": "mock", "version": "1.0.0" },
+                    },
+                }));
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(POST)
+                    .path("/mcp")
+                    .header("mcp-session-id", "s1")
+                    .json_body_includes(r#"{ "method": "notifications/initialized" }"#);
+                then.status(202);
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(POST).path("/mcp").json_body_includes(r#"{ "method": "tools/list" }"#);
+                then.status(200)
+                    .header("Content-Type", "text/event-stream")
+                    .body("event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"tools\":[{\"name\":\"search\"}]}}\n\n");
+            })
+            .await;
+
+        let auth = McpHttpAuth {
+            http_headers: Some(HashMap::from([("X-Api-Key".to_string(), "k1".to_string())])),
+            ..Default::default()
+        };
+        let mut transport = Transport::Http(HttpTransport {
+            client: http_client(&auth).unwrap(),
+            url: server.url("/mcp"),
+            session_id: None,
+            next_id: 0,
+        });
+        let initialized = transport.request("initialize", json!({})).await.unwrap();
+        assert_eq!(initialized["serverInfo"]["name"], "mock");
+        transport.notify("notifications/initialized").await.unwrap();
+        let tools = transport.list_all("tools/list", "tools").await.unwrap();
+        assert_eq!(tools[0].name, "search");
+    }
+
+    #[tokio::test]
+    async fn probe_reports_a_missing_command() {
+        let config = McpServerConfig::Stdio {
+            command: "codexia-missing-mcp-server".to_string(),
+            args: Vec::new(),
+            env: None,
+            cwd: None,
+            timeouts: Default::default(),
+            enabled: true,
+        };
+        let health = probe_mcp_server("missing", &config, Some(5)).await;
+        assert_eq!(health.status, McpHealthStatus::Failed);
+        assert!(health.error.unwrap().contains("codexia-missing-mcp-server"));
+    }
+}
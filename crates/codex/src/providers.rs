@@ -1,21 +1,51 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
 use crate::env::get_env;
+use codexia_shared::config_backup;
+
+pub const DEFAULT_DISCOVER_TIMEOUT_SECS: u64 = 5;
+/// How long a successful `/models` listing is reused.
+pub const MODEL_CACHE_TTL: Duration = Duration::from_secs(300);
+/// Failures are retried sooner so a provider that comes back is picked up quickly.
+pub const FAILED_MODEL_CACHE_TTL: Duration = Duration::from_secs(30);
+/// Per-request timeout for [`test_provider`]; a completion can take longer than a listing.
+pub const DEFAULT_TEST_TIMEOUT_SECS: u64 = 15;
+/// Error bodies longer than this are cut off in test results.
+const MAX_ERROR_BODY_LEN: usize = 2000;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LocalModel {
     pub id: String,
-    pub context_length: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_length: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProviderConfig {
     pub model_provider: String,
     pub base_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub api_key_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub signup_url: Option<String>,
+    /// Environment variable holding the API key. Empty for providers that need no key,
+    /// such as a local vLLM or LM Studio server.
+    #[serde(default)]
     pub env_key: String,
+    #[serde(default)]
     pub auto_discover: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub models: Option<Vec<LocalModel>>,
+    /// Extra HTTP headers sent with every request to the provider.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Timeout for `auto_discover` model listing, defaults to [`DEFAULT_DISCOVER_TIMEOUT_SECS`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discover_timeout_secs: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -24,17 +54,41 @@ pub struct RootConfig {
     pub data: Vec<ProviderConfig>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderSource {
+    /// Shipped in the bundled `llms.json`.
+    Bundled,
+    /// Defined only in the user providers file.
+    User,
+    /// A bundled provider replaced by an entry in the user providers file.
+    Override,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProviderEntry {
+    #[serde(flatten)]
+    pub config: ProviderConfig,
+    pub source: ProviderSource,
+}
+
+/// An entry of an OpenAI-compatible `/models` listing. Only `id` is required:
+/// local servers (LM Studio, vLLM, Ollama proxies) often omit the rest.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RemoteModelItem {
     pub id: String,
-    pub object: String,
-    pub created: u64,
-    pub owned_by: String,
+    #[serde(default)]
+    pub object: Option<String>,
+    #[serde(default)]
+    pub created: Option<u64>,
+    #[serde(default)]
+    pub owned_by: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RemoteModelResponse {
-    pub object: String,
+    #[serde(default)]
+    pub object: Option<String>,
     pub data: Vec<RemoteModelItem>,
 }
 
@@ -53,69 +107,809 @@ pub struct FrontendModel {
     pub context_length: Option<u32>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderModelsStatus {
+    Ok,
+    /// The endpoint answered 401/403, or the provider's key is not set.
+    Unauthorized,
+    /// Connection failure or timeout.
+    Unreachable,
+    /// Any other non-success status or an unparseable body.
+    Error,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FrontendProviderModels {
     pub provider: String,
     pub models: Vec<FrontendModel>,
+    pub status: ProviderModelsStatus,
+    /// Why `models` is empty when `status` is not `ok`.
+    pub error: Option<String>,
 }
 
-pub async fn load_env_keys() -> Result<Vec<EnvStatusItem>, String> {
+/// Outcome of one request made by [`test_provider`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProviderEndpointCheck {
+    pub url: String,
+    /// HTTP status, `None` when no response was received.
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub ok: bool,
+    /// Response body of a failed request, or the connection error.
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProviderTestResult {
+    pub provider: String,
+    pub base_url: String,
+    pub env_key: String,
+    /// Whether `env_key` resolved to a value. Always false for providers without a key.
+    pub api_key_set: bool,
+    /// The model the completion was tried with.
+    pub model: Option<String>,
+    /// Whether `model` is in the `/models` listing, `None` if the listing failed.
+    pub model_found: Option<bool>,
+    pub models: ProviderEndpointCheck,
+    /// `None` when there was no model to try.
+    pub completion: Option<ProviderEndpointCheck>,
+}
+
+/// User-defined providers, in the same `{"object": "list", "data": [...]}` shape as
+/// the bundled `llms.json`. An entry with the same `model_provider` as a bundled
+/// one replaces it.
+pub fn user_providers_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join(".codexia").join("providers.json"))
+}
+
+pub fn bundled_providers() -> Result<Vec<ProviderConfig>, String> {
     let json_str = include_str!("./llms.json");
-    let config: RootConfig = serde_json::from_str(json_str).map_err(|e| e.to_string())?;
+    let config: RootConfig =
+        serde_json::from_str(json_str).map_err(|e| format!("Failed to parse llms.json: {}", e))?;
+    Ok(config.data)
+}
 
-    let mut result = Vec::new();
+pub fn read_user_providers() -> Result<Vec<ProviderConfig>, String> {
+    let path = user_providers_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let config: RootConfig = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    Ok(config.data)
+}
 
-    for provider in config.data {
-        let is_env_set = get_env(provider.env_key.clone()).is_ok();
+/// Bundled providers merged with the user providers file, in bundled order
+/// followed by user-only providers. An unreadable user file is logged and
+/// ignored so the bundled providers stay available.
+pub fn list_providers() -> Result<Vec<ProviderEntry>, String> {
+    let user = read_user_providers().unwrap_or_else(|e| {
+        log::error!("Ignoring user providers: {}", e);
+        Vec::new()
+    });
+    Ok(merge_providers(bundled_providers()?, user))
+}
 
-        result.push(EnvStatusItem {
-            provider: provider.model_provider,
-            env_key: provider.env_key,
-            is_env_set,
-            api_key_url: provider.api_key_url,
-            signup_url: provider.signup_url,
-        });
+fn merge_providers(bundled: Vec<ProviderConfig>, user: Vec<ProviderConfig>) -> Vec<ProviderEntry> {
+    let mut entries: Vec<ProviderEntry> = bundled
+        .into_iter()
+        .map(|config| ProviderEntry {
+            config,
+            source: ProviderSource::Bundled,
+        })
+        .collect();
+
+    for provider in user {
+        match entries
+            .iter_mut()
+            .find(|entry| entry.config.model_provider == provider.model_provider)
+        {
+            Some(entry) => {
+                entry.config = provider;
+                entry.source = ProviderSource::Override;
+            }
+            None => entries.push(ProviderEntry {
+                config: provider,
+                source: ProviderSource::User,
+            }),
+        }
     }
+    entries
+}
 
-    Ok(result)
+pub fn load_providers() -> Result<Vec<ProviderConfig>, String> {
+    Ok(list_providers()?
+        .into_iter()
+        .map(|entry| entry.config)
+        .collect())
 }
 
-pub async fn load_and_fetch_models() -> Result<Vec<FrontendProviderModels>, String> {
-    let json_str = include_str!("./llms.json");
-    let config: RootConfig = serde_json::from_str(json_str).map_err(|e| e.to_string())?;
-
-    let client = reqwest::Client::new();
-    let mut result = Vec::new();
-
-    for provider in config.data {
-        let mut frontend_models = Vec::new();
-
-        if provider.auto_discover {
-            let url = format!("{}/models", provider.base_url.trim_end_matches('/'));
-            if let Ok(resp) = client.get(&url).send().await {
-                if let Ok(remote_data) = resp.json::<RemoteModelResponse>().await {
-                    for model in remote_data.data {
-                        frontend_models.push(FrontendModel {
-                            id: model.id,
-                            context_length: None,
-                        });
-                    }
-                }
+/// Add a provider to the user providers file, replacing an entry with the same id.
+pub fn upsert_user_provider(provider: ProviderConfig) -> Result<(), String> {
+    validate_provider(&provider)?;
+    let mut providers = read_user_providers()?;
+    match providers
+        .iter_mut()
+        .find(|existing| existing.model_provider == provider.model_provider)
+    {
+        Some(existing) => *existing = provider,
+        None => providers.push(provider),
+    }
+    write_user_providers(providers)
+}
+
+/// Remove a provider from the user providers file. A bundled provider it
+/// overrode becomes visible again.
+pub fn delete_user_provider(model_provider: &str) -> Result<(), String> {
+    let mut providers = read_user_providers()?;
+    let before = providers.len();
+    providers.retain(|provider| provider.model_provider != model_provider);
+    if providers.len() == before {
+        return Err(format!("User provider '{}' not found", model_provider));
+    }
+    write_user_providers(providers)
+}
+
+fn write_user_providers(providers: Vec<ProviderConfig>) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(&RootConfig {
+        object: "list".to_string(),
+        data: providers,
+    })
+    .map_err(|e| format!("Failed to serialize providers: {}", e))?;
+    config_backup::write_with_backup(&user_providers_path()?, &content)?;
+    clear_models_cache();
+    Ok(())
+}
+
+fn validate_provider(provider: &ProviderConfig) -> Result<(), String> {
+    let id = &provider.model_provider;
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid provider id '{}': use letters, digits, '-' or '_'",
+            id
+        ));
+    }
+    if !provider.base_url.starts_with("http://") && !provider.base_url.starts_with("https://") {
+        return Err(format!(
+            "Invalid base_url for '{}': must start with http:// or https://",
+            id
+        ));
+    }
+    if let Some(name) = provider.headers.keys().find(|name| {
+        name.is_empty()
+            || !name
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+    }) {
+        return Err(format!("Invalid header name '{}' for '{}'", name, id));
+    }
+    Ok(())
+}
+
+pub async fn load_env_keys() -> Result<Vec<EnvStatusItem>, String> {
+    // `get_env` reads the secret store and may shell out to the user's login shell.
+    tokio::task::spawn_blocking(|| {
+        let mut result = Vec::new();
+
+        for provider in load_providers()? {
+            if provider.env_key.is_empty() {
+                continue;
             }
-        } else if let Some(static_models) = provider.models {
-            for model in static_models {
-                frontend_models.push(FrontendModel {
+            let is_env_set = get_env(provider.env_key.clone()).is_ok();
+
+            result.push(EnvStatusItem {
+                provider: provider.model_provider,
+                env_key: provider.env_key,
+                is_env_set,
+                api_key_url: provider.api_key_url,
+                signup_url: provider.signup_url,
+            });
+        }
+
+        Ok(result)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Model lists for every provider. `auto_discover` providers are queried
+/// concurrently, each with its own timeout; results are cached for
+/// [`MODEL_CACHE_TTL`] (or [`FAILED_MODEL_CACHE_TTL`] after a failure) unless
+/// `force_refresh` is set.
+pub async fn fetch_models(force_refresh: bool) -> Result<Vec<FrontendProviderModels>, String> {
+    let providers = load_providers()?;
+    // Building a client loads the native root certificates, so only do it on a cache miss.
+    let mut client: Option<reqwest::Client> = None;
+    let mut tasks = tokio::task::JoinSet::new();
+    let mut result: Vec<Option<FrontendProviderModels>> = vec![None; providers.len()];
+
+    for (index, provider) in providers.into_iter().enumerate() {
+        if !provider.auto_discover {
+            let models = provider
+                .models
+                .unwrap_or_default()
+                .into_iter()
+                .map(|model| FrontendModel {
                     id: model.id,
-                    context_length: Some(model.context_length),
-                });
-            }
+                    context_length: model.context_length,
+                })
+                .collect();
+            result[index] = Some(FrontendProviderModels {
+                provider: provider.model_provider,
+                models,
+                status: ProviderModelsStatus::Ok,
+                error: None,
+            });
+            continue;
+        }
+
+        let cache_key = discovery_cache_key(&provider);
+        if !force_refresh && let Some(cached) = cached_discovery(&cache_key) {
+            result[index] = Some(cached);
+            continue;
         }
 
-        result.push(FrontendProviderModels {
-            provider: provider.model_provider,
-            models: frontend_models,
+        let client = client.get_or_insert_with(reqwest::Client::new).clone();
+        tasks.spawn(async move {
+            let api_key = provider_api_key(&provider).await;
+            let models = discover_models(&client, &provider, api_key).await;
+            store_discovery(cache_key, &models);
+            (index, models)
         });
     }
 
-    Ok(result)
-}
\ No newline at end of file
+    while let Some(joined) = tasks.join_next().await {
+        let (index, models) = joined.map_err(|e| format!("Model discovery task failed: {}", e))?;
+        result[index] = Some(models);
+    }
+
+    Ok(result.into_iter().flatten().collect())
+}
+
+/// Query `{base_url}/models`, authenticating with `api_key` (resolved from
+/// `env_key`) when set.
+async fn discover_models(
+    client: &reqwest::Client,
+    provider: &ProviderConfig,
+    api_key: Option<String>,
+) -> FrontendProviderModels {
+    let mut models = FrontendProviderModels {
+        provider: provider.model_provider.clone(),
+        models: Vec::new(),
+        status: ProviderModelsStatus::Ok,
+        error: None,
+    };
+
+    let url = format!("{}/models", provider.base_url.trim_end_matches('/'));
+    let mut request = client.get(&url).timeout(Duration::from_secs(
+        provider
+            .discover_timeout_secs
+            .unwrap_or(DEFAULT_DISCOVER_TIMEOUT_SECS),
+    ));
+    if let Some(key) = &api_key {
+        request = request.bearer_auth(key);
+    }
+    for (name, value) in &provider.headers {
+        request = request.header(name, value);
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            models.status = ProviderModelsStatus::Unreachable;
+            models.error = Some(if e.is_timeout() {
+                format!("Timed out requesting {}", url)
+            } else {
+                format!("Failed to reach {}: {}", url, e)
+            });
+            return models;
+        }
+    };
+
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        models.status = ProviderModelsStatus::Unauthorized;
+        models.error = Some(if api_key.is_none() && !provider.env_key.is_empty() {
+            format!("{} is not set", provider.env_key)
+        } else {
+            format!("{} rejected the API key (HTTP {})", url, status.as_u16())
+        });
+        return models;
+    }
+    if !status.is_success() {
+        models.status = ProviderModelsStatus::Error;
+        models.error = Some(format!("{} returned HTTP {}", url, status.as_u16()));
+        return models;
+    }
+
+    match response.json::<RemoteModelResponse>().await {
+        Ok(remote_data) => {
+            models.models = remote_data
+                .data
+                .into_iter()
+                .map(|model| FrontendModel {
+                    id: model.id,
+                    context_length: None,
+                })
+                .collect();
+        }
+        Err(e) => {
+            models.status = ProviderModelsStatus::Error;
+            models.error = Some(format!("Unexpected response from {}: {}", url, e));
+        }
+    }
+    models
+}
+
+/// Check a provider end to end: list `{base_url}/models`, then send a one-token
+/// chat completion. `model` defaults to the model in config.toml when this
+/// provider is the active one, then to the first model the provider lists.
+pub async fn test_provider(
+    model_provider: &str,
+    model: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<ProviderTestResult, String> {
+    let provider = load_providers()?
+        .into_iter()
+        .find(|provider| provider.model_provider == model_provider)
+        .ok_or_else(|| format!("Provider '{}' not found", model_provider))?;
+
+    let model = match model.filter(|model| !model.is_empty()) {
+        Some(model) => Some(model),
+        None => crate::config::settings::read_codex_settings()
+            .await
+            .ok()
+            .filter(|settings| settings.model_provider.as_deref() == Some(model_provider))
+            .and_then(|settings| settings.model)
+            .or_else(|| {
+                provider
+                    .models
+                    .as_ref()
+                    .and_then(|models| models.first())
+                    .map(|model| model.id.clone())
+            }),
+    };
+    let api_key = provider_api_key(&provider).await;
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_TEST_TIMEOUT_SECS));
+
+    Ok(check_provider(&reqwest::Client::new(), &provider, api_key, model, timeout).await)
+}
+
+async fn check_provider(
+    client: &reqwest::Client,
+    provider: &ProviderConfig,
+    api_key: Option<String>,
+    model: Option<String>,
+    timeout: Duration,
+) -> ProviderTestResult {
+    let base_url = provider.base_url.trim_end_matches('/');
+    let authorize = |mut request: reqwest::RequestBuilder| {
+        request = request.timeout(timeout);
+        if let Some(key) = &api_key {
+            request = request.bearer_auth(key);
+        }
+        for (name, value) in &provider.headers {
+            request = request.header(name, value);
+        }
+        request
+    };
+
+    let models_url = format!("{}/models", base_url);
+    let (models, listed) = check_endpoint(&models_url, authorize(client.get(&models_url))).await;
+    let listed_ids = listed.map(|body| {
+        body.get("data")
+            .and_then(|data| data.as_array())
+            .map(|data| {
+                data.iter()
+                    .filter_map(|item| item.get("id")?.as_str().map(str::to_string))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    });
+    let model = model.or_else(|| listed_ids.as_ref().and_then(|ids| ids.first().cloned()));
+    let model_found = match (&model, &listed_ids) {
+        (Some(model), Some(ids)) => Some(ids.contains(model)),
+        _ => None,
+    };
+
+    let completion = match &model {
+        Some(model) => {
+            let body = serde_json::json!({
+                "model": model,
+                "messages": [{ "role": "user", "content": "ping" }],
+                "max_tokens": 1,
+            });
+            let url = format!("{}/chat/completions", base_url);
+            let request = authorize(client.post(&url)).json(&body);
+            Some(check_endpoint(&url, request).await.0)
+        }
+        None => None,
+    };
+
+    ProviderTestResult {
+        provider: provider.model_provider.clone(),
+        base_url: provider.base_url.clone(),
+        env_key: provider.env_key.clone(),
+        api_key_set: api_key.is_some(),
+        model,
+        model_found,
+        models,
+        completion,
+    }
+}
+
+/// Send `request` and time it. A successful response's JSON body is returned alongside.
+async fn check_endpoint(
+    url: &str,
+    request: reqwest::RequestBuilder,
+) -> (ProviderEndpointCheck, Option<serde_json::Value>) {
+    let started = Instant::now();
+    let mut check = ProviderEndpointCheck {
+        url: url.to_string(),
+        status: None,
+        latency_ms: 0,
+        ok: false,
+        error: None,
+    };
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            check.latency_ms = started.elapsed().as_millis() as u64;
+            check.error = Some(if e.is_timeout() {
+                "Timed out".to_string()
+            } else {
+                e.to_string()
+            });
+            return (check, None);
+        }
+    };
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    check.latency_ms = started.elapsed().as_millis() as u64;
+    check.status = Some(status.as_u16());
+    check.ok = status.is_success();
+
+    if !check.ok {
+        check.error = Some(truncate_body(body));
+        return (check, None);
+    }
+    (check, serde_json::from_str(&body).ok())
+}
+
+fn truncate_body(mut body: String) -> String {
+    if body.len() > MAX_ERROR_BODY_LEN {
+        let mut end = MAX_ERROR_BODY_LEN;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("...");
+    }
+    body
+}
+
+/// The provider's API key, if it has an `env_key` that resolves to a value.
+pub(crate) async fn provider_api_key(provider: &ProviderConfig) -> Option<String> {
+    if provider.env_key.is_empty() {
+        return None;
+    }
+    let env_key = provider.env_key.clone();
+    // `get_env` may shell out to the user's login shell.
+    tokio::task::spawn_blocking(move || get_env(env_key))
+        .await
+        .ok()?
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+}
+
+fn discovery_cache() -> &'static Mutex<HashMap<String, (Instant, FrontendProviderModels)>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, (Instant, FrontendProviderModels)>>> =
+        OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Providers are cached by id and endpoint so editing a provider invalidates its entry.
+fn discovery_cache_key(provider: &ProviderConfig) -> String {
+    format!("{}\n{}", provider.model_provider, provider.base_url)
+}
+
+fn cached_discovery(key: &str) -> Option<FrontendProviderModels> {
+    let cache = discovery_cache().lock().ok()?;
+    let (fetched_at, models) = cache.get(key)?;
+    let ttl = if models.status == ProviderModelsStatus::Ok {
+        MODEL_CACHE_TTL
+    } else {
+        FAILED_MODEL_CACHE_TTL
+    };
+    (fetched_at.elapsed() < ttl).then(|| models.clone())
+}
+
+fn store_discovery(key: String, models: &FrontendProviderModels) {
+    if let Ok(mut cache) = discovery_cache().lock() {
+        cache.insert(key, (Instant::now(), models.clone()));
+    }
+}
+
+/// Drop all cached discovery results, e.g. after an API key changed.
+pub fn clear_models_cache() {
+    if let Ok(mut cache) = discovery_cache().lock() {
+        cache.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    fn provider(base_url: String) -> ProviderConfig {
+        ProviderConfig {
+            model_provider: "mock".to_string(),
+            base_url,
+            api_key_url: None,
+            signup_url: None,
+            env_key: "MOCK_API_KEY".to_string(),
+            auto_discover: true,
+            models: None,
+            headers: HashMap::from([("X-Org".to_string(), "codexia".to_string())]),
+            discover_timeout_secs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn discover_models_lists_models_with_auth() {
+        let server = MockServer::start_async().await;
+        let listing = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/v1/models")
+                    .header("authorization", "Bearer test-key")
+                    .header("x-org", "codexia");
+                then.status(200).json_body(json!({
+                    "object": "list",
+                    "data": [{ "id": "small", "object": "model", "created": 0, "owned_by": "me" }],
+                }));
+            })
+            .await;
+
+        let models = discover_models(
+            &reqwest::Client::new(),
+            &provider(server.url("/v1/")),
+            Some("test-key".to_string()),
+        )
+        .await;
+
+        listing.assert_async().await;
+        assert_eq!(models.status, ProviderModelsStatus::Ok);
+        assert_eq!(models.models.len(), 1);
+        assert_eq!(models.models[0].id, "small");
+        assert!(models.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn discover_models_accepts_minimal_listings() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/models");
+                then.status(200).json_body(json!({ "data": [{ "id": "m" }] }));
+            })
+            .await;
+
+        let models = discover_models(&reqwest::Client::new(), &provider(server.url("")), None).await;
+        assert_eq!(models.status, ProviderModelsStatus::Ok);
+        assert_eq!(models.models.iter().map(|model| model.id.as_str()).collect::<Vec<_>>(), ["m"]);
+    }
+
+    #[tokio::test]
+    async fn discover_models_classifies_failures() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/locked/models");
+                then.status(401);
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/broken/models");
+                then.status(500);
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/odd/models");
+                then.status(200).body("not json");
+            })
+            .await;
+        let client = reqwest::Client::new();
+        let discover = |path: &str| {
+            let provider = provider(server.url(path));
+            let client = client.clone();
+            async move { discover_models(&client, &provider, None).await }
+        };
+
+        let locked = discover("/locked").await;
+        assert_eq!(locked.status, ProviderModelsStatus::Unauthorized);
+        assert_eq!(locked.error.as_deref(), Some("MOCK_API_KEY is not set"));
+
+        let broken = discover("/broken").await;
+        assert_eq!(broken.status, ProviderModelsStatus::Error);
+        assert!(broken.error.unwrap().contains("returned HTTP 500"));
+
+        let odd = discover("/odd").await;
+        assert_eq!(odd.status, ProviderModelsStatus::Error);
+        assert!(odd.error.unwrap().starts_with("Unexpected response"));
+
+        let unreachable =
+            discover_models(&client, &provider("http://127.0.0.1:9".to_string()), None).await;
+        assert_eq!(unreachable.status, ProviderModelsStatus::Unreachable);
+        assert!(unreachable.models.is_empty());
+    }
+
+    #[test]
+    fn failed_discoveries_expire_sooner() {
+        let ok = FrontendProviderModels {
+            provider: "cache-ok".to_string(),
+            models: Vec::new(),
+            status: ProviderModelsStatus::Ok,
+            error: None,
+        };
+        let failed = FrontendProviderModels {
+            provider: "cache-failed".to_string(),
+            status: ProviderModelsStatus::Unreachable,
+            ..ok.clone()
+        };
+        let stale = Instant::now() - FAILED_MODEL_CACHE_TTL - Duration::from_secs(1);
+        {
+            let mut cache = discovery_cache().lock().unwrap();
+            cache.insert("cache-ok".to_string(), (stale, ok));
+            cache.insert("cache-failed".to_string(), (stale, failed));
+        }
+        assert!(cached_discovery("cache-ok").is_some());
+        assert!(cached_discovery("cache-failed").is_none());
+    }
+
+    #[test]
+    fn user_providers_override_bundled_ones_in_place() {
+        let named = |id: &str, base_url: &str| ProviderConfig {
+            model_provider: id.to_string(),
+            ..provider(base_url.to_string())
+        };
+        let bundled = vec![named("openai", "https://a"), named("groq", "https://b")];
+        let user = vec![named("local", "http://localhost:8000"), named("openai", "https://proxy")];
+
+        let entries = merge_providers(bundled, user);
+        let summary: Vec<(&str, &str, ProviderSource)> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.config.model_provider.as_str(),
+                    entry.config.base_url.as_str(),
+                    entry.source,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("openai", "https://proxy", ProviderSource::Override),
+                ("groq", "https://b", ProviderSource::Bundled),
+                ("local", "http://localhost:8000", ProviderSource::User),
+            ]
+        );
+    }
+
+    #[test]
+    fn bundled_providers_parse_with_unique_ids() {
+        let providers = bundled_providers().unwrap();
+        let ids: std::collections::HashSet<&str> =
+            providers.iter().map(|provider| provider.model_provider.as_str()).collect();
+        assert_eq!(ids.len(), providers.len());
+    }
+
+    #[tokio::test]
+    async fn test_provider_reports_models_and_completion() {
+        let server = MockServer::start_async().await;
+        let models = server
+            .mock_async(|when, then| {
+                when.method(GET)
+                    .path("/v1/models")
+                    .header("authorization", "Bearer test-key")
+                    .header("x-org", "codexia");
+                then.status(200)
+                    .json_body(json!({ "data": [{ "id": "small" }, { "id": "large" }] }));
+            })
+            .await;
+        let completion = server
+            .mock_async(|when, then| {
+                when.method(POST)
+                    .path("/v1/chat/completions")
+                    .json_body_includes(r#"{ "model": "large", "max_tokens": 1 }"#);
+                then.status(200).json_body(json!({ "choices": [] }));
+            })
+            .await;
+
+        let result = check_provider(
+            &reqwest::Client::new(),
+            &provider(server.url("/v1/")),
+            Some("test-key".to_string()),
+            Some("large".to_string()),
+            Duration::from_secs(5),
+        )
+        .await;
+
+        models.assert_async().await;
+        completion.assert_async().await;
+        assert!(result.api_key_set);
+        assert_eq!(result.model_found, Some(true));
+        assert_eq!(result.models.status, Some(200));
+        assert!(result.models.ok);
+        let completion = result.completion.expect("completion was attempted");
+        assert_eq!(completion.url, server.url("/v1/chat/completions"));
+        assert!(completion.ok);
+        assert!(completion.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_provider_reports_error_bodies() {
+        let server = MockServer::start_async().await;
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/models");
+                then.status(401).body(r#"{"error":"invalid api key"}"#);
+            })
+            .await;
+        server
+            .mock_async(|when, then| {
+                when.method(POST).path("/chat/completions");
+                then.status(404).body(r#"{"error":"model not found"}"#);
+            })
+            .await;
+
+        let result = check_provider(
+            &reqwest::Client::new(),
+            &provider(server.base_url()),
+            None,
+            Some("missing".to_string()),
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert!(!result.api_key_set);
+        assert_eq!(result.model_found, None);
+        assert_eq!(result.models.status, Some(401));
+        assert!(!result.models.ok);
+        assert!(result.models.error.unwrap().contains("invalid api key"));
+        let completion = result.completion.expect("completion was attempted");
+        assert_eq!(completion.status, Some(404));
+        assert!(completion.error.unwrap().contains("model not found"));
+    }
+
+    #[tokio::test]
+    async fn test_provider_reports_unreachable_endpoint() {
+        let result = check_provider(
+            &reqwest::Client::new(),
+            &provider("http://127.0.0.1:9".to_string()),
+            None,
+            None,
+            Duration::from_secs(2),
+        )
+        .await;
+
+        assert_eq!(result.models.status, None);
+        assert!(result.models.error.is_some());
+        assert!(result.model.is_none());
+        assert!(result.completion.is_none());
+    }
+}
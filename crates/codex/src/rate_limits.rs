@@ -0,0 +1,330 @@
+//! Codex account rate-limit history.
+//!
+//! Snapshots from `account/rateLimits/read` responses and
+//! `account/rateLimits/updated` notifications are stored in codexia-db so usage
+//! can be charted over time. A window crossing one of the configured thresholds
+//! emits [`WARNING_EVENT`] once per reset period, and [`automation_defer_reason`]
+//! lets the automation scheduler hold off while a window is nearly exhausted.
+
+use chrono::{TimeZone, Utc};
+use codexia_db::rate_limits::{self, RateLimitSample};
+use codexia_shared::event_sink::EventSink;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+pub const WARNING_EVENT: &str = "codex:rateLimitWarning";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RateLimitAlertSettings {
+    /// Percent-used levels that raise [`WARNING_EVENT`], applied to every window.
+    #[serde(default = "default_thresholds")]
+    pub thresholds: Vec<f64>,
+    /// Scheduled codex automations are deferred while any window is at or
+    /// above this percent. `None` never defers.
+    #[serde(default = "default_defer_automations_at")]
+    pub defer_automations_at: Option<f64>,
+}
+
+impl Default for RateLimitAlertSettings {
+    fn default() -> Self {
+        Self {
+            thresholds: default_thresholds(),
+            defer_automations_at: default_defer_automations_at(),
+        }
+    }
+}
+
+fn default_thresholds() -> Vec<f64> {
+    vec![80.0, 95.0]
+}
+
+fn default_defer_automations_at() -> Option<f64> {
+    Some(95.0)
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join(".codexia").join("rate_limit_alerts.json"))
+}
+
+pub fn read_alert_settings() -> Result<RateLimitAlertSettings, String> {
+    let path = settings_path()?;
+    if !path.exists() {
+        return Ok(RateLimitAlertSettings::default());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+pub fn write_alert_settings(mut settings: RateLimitAlertSettings) -> Result<(), String> {
+    let valid = |percent: f64| percent > 0.0 && percent <= 100.0;
+    if let Some(percent) = settings
+        .thresholds
+        .iter()
+        .chain(settings.defer_automations_at.iter())
+        .find(|percent| !valid(**percent))
+    {
+        return Err(format!(
+            "Invalid threshold {}: must be between 0 and 100",
+            percent
+        ));
+    }
+    settings.thresholds.sort_by(f64::total_cmp);
+    settings.thresholds.dedup();
+
+    let path = settings_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize rate limit settings: {}", e))?;
+    codexia_shared::config_backup::write_private(&path, content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Record the snapshot carried by an `account/rateLimits/updated` notification
+/// on the blocking pool, so the app-server reader never waits on the database.
+/// Other notifications are ignored.
+pub fn record_notification(event_sink: &Arc<dyn EventSink>, payload: &Value) {
+    if payload.get("method").and_then(Value::as_str) != Some("account/rateLimits/updated") {
+        return;
+    }
+    let Some(snapshot) = payload.get("params").and_then(|p| p.get("rateLimits")).cloned() else {
+        return;
+    };
+    let event_sink = Arc::clone(event_sink);
+    tokio::task::spawn_blocking(move || record_snapshots(event_sink.as_ref(), [&snapshot]));
+}
+
+/// Record an `account/rateLimits/read` response, preferring the per-limit view.
+pub fn record_read_response(event_sink: &dyn EventSink, response: &Value) {
+    match response
+        .get("rateLimitsByLimitId")
+        .and_then(Value::as_object)
+    {
+        Some(by_limit) if !by_limit.is_empty() => record_snapshots(event_sink, by_limit.values()),
+        _ => {
+            if let Some(snapshot) = response.get("rateLimits") {
+                record_snapshots(event_sink, [snapshot]);
+            }
+        }
+    }
+}
+
+fn record_snapshots<'a>(
+    event_sink: &dyn EventSink,
+    snapshots: impl IntoIterator<Item = &'a Value>,
+) {
+    let recorded_at = Utc::now().timestamp_millis();
+    let samples: Vec<RateLimitSample> = snapshots
+        .into_iter()
+        .flat_map(|snapshot| samples_from_snapshot(snapshot, recorded_at))
+        .collect();
+    if samples.is_empty() {
+        return;
+    }
+    if let Err(e) = rate_limits::record_samples(&samples) {
+        log::warn!("Failed to record codex rate limits: {}", e);
+    }
+
+    let settings = read_alert_settings().unwrap_or_else(|e| {
+        log::warn!("{}", e);
+        RateLimitAlertSettings::default()
+    });
+    for sample in &samples {
+        if let Some(threshold) = newly_crossed_threshold(sample, &settings.thresholds) {
+            let label = window_label(sample);
+            event_sink.emit(
+                WARNING_EVENT,
+                json!({
+                    "limitId": sample.limit_id,
+                    "window": sample.window,
+                    "windowLabel": label,
+                    "usedPercent": sample.used_percent,
+                    "threshold": threshold,
+                    "resetsAt": sample.resets_at,
+                    "message": format!("{} limit {:.0}% used", label, sample.used_percent),
+                }),
+            );
+        }
+    }
+}
+
+fn samples_from_snapshot(snapshot: &Value, recorded_at: i64) -> Vec<RateLimitSample> {
+    let limit_id = snapshot
+        .get("limitId")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let plan_type = snapshot
+        .get("planType")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    ["primary", "secondary"]
+        .into_iter()
+        .filter_map(|window| {
+            let data = snapshot.get(window)?;
+            Some(RateLimitSample {
+                recorded_at,
+                limit_id: limit_id.to_string(),
+                window: window.to_string(),
+                used_percent: data.get("usedPercent")?.as_f64()?,
+                window_duration_mins: data.get("windowDurationMins").and_then(Value::as_i64),
+                resets_at: data.get("resetsAt").and_then(Value::as_i64),
+                plan_type: plan_type.clone(),
+            })
+        })
+        .collect()
+}
+
+/// The highest threshold `sample` has reached that was not already reported for
+/// its window. Usage dropping below the reported level (the window reset) re-arms it.
+fn newly_crossed_threshold(sample: &RateLimitSample, thresholds: &[f64]) -> Option<f64> {
+    static WARNED: OnceLock<Mutex<HashMap<(String, String), f64>>> = OnceLock::new();
+    let mut warned = WARNED
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .ok()?;
+
+    let key = (sample.limit_id.clone(), sample.window.clone());
+    let reported = warned.entry(key).or_insert(0.0);
+    if sample.used_percent < *reported {
+        *reported = 0.0;
+    }
+    let crossed = thresholds
+        .iter()
+        .copied()
+        .filter(|threshold| sample.used_percent >= *threshold)
+        .fold(0.0, f64::max);
+    if crossed > *reported {
+        *reported = crossed;
+        Some(crossed)
+    } else {
+        None
+    }
+}
+
+/// Human name of a window, e.g. `weekly` or `5h`.
+fn window_label(sample: &RateLimitSample) -> String {
+    match sample.window_duration_mins {
+        Some(10080) => "weekly".to_string(),
+        Some(1440) => "daily".to_string(),
+        Some(mins) if mins > 0 && mins % 60 == 0 => format!("{}h", mins / 60),
+        Some(mins) if mins > 0 => format!("{}m", mins),
+        _ => sample.window.clone(),
+    }
+}
+
+/// Why scheduled codex automations should not start right now, if a rate-limit
+/// window is at or above `defer_automations_at` and has not reset yet.
+pub fn automation_defer_reason() -> Option<String> {
+    let defer_at = read_alert_settings().ok()?.defer_automations_at?;
+    defer_reason(rate_limits::latest_samples().ok()?, defer_at, Utc::now().timestamp())
+}
+
+/// [`automation_defer_reason`] for the latest `samples` at `now` (Unix seconds).
+fn defer_reason(samples: Vec<RateLimitSample>, defer_at: f64, now: i64) -> Option<String> {
+    samples
+        .into_iter()
+        .filter(|sample| sample.used_percent >= defer_at)
+        .find(|sample| match sample.resets_at {
+            Some(resets_at) => resets_at > now,
+            // Without a reset time only trust a recent sample.
+            None => now * 1000 - sample.recorded_at < 60 * 60 * 1000,
+        })
+        .map(|sample| {
+            let resets = sample
+                .resets_at
+                .and_then(|resets_at| Utc.timestamp_opt(resets_at, 0).single())
+                .map(|time| format!(", resets at {}", time.to_rfc3339()))
+                .unwrap_or_default();
+            format!(
+                "codex {} limit {:.0}% used{}",
+                window_label(&sample),
+                sample.used_percent,
+                resets
+            )
+        })
+}
+
+/// Recorded samples for charting, oldest first.
+pub fn rate_limit_history(
+    limit_id: Option<&str>,
+    since: Option<i64>,
+    limit: usize,
+) -> Result<Vec<RateLimitSample>, String> {
+    rate_limits::list_samples(limit_id, since, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(limit_id: &str, used_percent: f64) -> RateLimitSample {
+        RateLimitSample {
+            recorded_at: 0,
+            limit_id: limit_id.to_string(),
+            window: "primary".to_string(),
+            used_percent,
+            window_duration_mins: Some(300),
+            resets_at: None,
+            plan_type: None,
+        }
+    }
+
+    #[test]
+    fn thresholds_warn_once_until_usage_drops() {
+        // Each test uses its own limit id, since reported levels are process-wide.
+        let thresholds = [80.0, 95.0];
+        let crossed = |used| newly_crossed_threshold(&sample("threshold-test", used), &thresholds);
+        assert_eq!(crossed(50.0), None);
+        assert_eq!(crossed(81.0), Some(80.0));
+        assert_eq!(crossed(85.0), None);
+        assert_eq!(crossed(99.0), Some(95.0));
+        assert_eq!(crossed(99.5), None);
+        // The window reset, so the next crossing is reported again.
+        assert_eq!(crossed(5.0), None);
+        assert_eq!(crossed(96.0), Some(95.0));
+    }
+
+    #[test]
+    fn snapshots_become_one_sample_per_window() {
+        let snapshot = json!({
+            "limitId": "codex",
+            "planType": "plus",
+            "primary": { "usedPercent": 42.5, "windowDurationMins": 300, "resetsAt": 100 },
+            "secondary": { "usedPercent": 7.0, "windowDurationMins": 10080 },
+        });
+        let samples = samples_from_snapshot(&snapshot, 5);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].used_percent, 42.5);
+        assert_eq!(samples[0].resets_at, Some(100));
+        assert_eq!(samples[1].plan_type.as_deref(), Some("plus"));
+        assert_eq!(window_label(&samples[0]), "5h");
+        assert_eq!(window_label(&samples[1]), "weekly");
+        assert!(samples_from_snapshot(&json!({ "primary": {} }), 5).is_empty());
+    }
+
+    #[test]
+    fn automations_defer_only_before_the_window_resets() {
+        let now = 10_000;
+        let mut exhausted = sample("codex", 97.0);
+        exhausted.resets_at = Some(now + 60);
+        let reason = defer_reason(vec![sample("codex", 50.0), exhausted.clone()], 95.0, now);
+        assert!(reason.unwrap().starts_with("codex 5h limit 97% used, resets at "));
+
+        exhausted.resets_at = Some(now - 60);
+        assert_eq!(defer_reason(vec![exhausted.clone()], 95.0, now), None);
+
+        // Without a reset time only a sample from the last hour counts.
+        exhausted.resets_at = None;
+        exhausted.recorded_at = now * 1000 - 10 * 60 * 1000;
+        assert!(defer_reason(vec![exhausted.clone()], 95.0, now).is_some());
+        exhausted.recorded_at = now * 1000 - 2 * 60 * 60 * 1000;
+        assert_eq!(defer_reason(vec![exhausted], 95.0, now), None);
+    }
+}
@@ -0,0 +1,375 @@
+//! Normalized view over codex rollout files (`~/.codex/sessions/**/*.jsonl`).
+//!
+//! Rollouts mix several record types (`session_meta`, `response_item`,
+//! `event_msg`, `turn_context`). This module folds the ones Codexia cares about
+//! into a flat list of [`RolloutEvent`]s.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::utils::parse_json_line;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloutMeta {
+    pub id: String,
+    pub cwd: String,
+    pub source: String,
+    pub timestamp: Option<String>,
+    pub cli_version: Option<String>,
+    pub model_provider: Option<String>,
+    /// The first model the thread used.
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub cached_input_tokens: u64,
+    pub output_tokens: u64,
+    pub reasoning_output_tokens: u64,
+    pub total_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RolloutEvent {
+    #[serde(rename_all = "camelCase")]
+    Message {
+        role: String,
+        text: String,
+        timestamp: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ToolCall {
+        call_id: Option<String>,
+        name: String,
+        /// Shell command line when the tool is a command execution.
+        command: Option<String>,
+        input: String,
+        timestamp: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ToolOutput {
+        call_id: Option<String>,
+        output: String,
+        timestamp: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Patch {
+        call_id: Option<String>,
+        files: Vec<String>,
+        patch: String,
+        timestamp: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rollout {
+    pub meta: RolloutMeta,
+    pub events: Vec<RolloutEvent>,
+    /// Last cumulative token usage reported by the thread, if any.
+    pub token_usage: Option<TokenUsage>,
+}
+
+impl Rollout {
+    /// Every file path touched by a patch, in first-seen order.
+    pub fn touched_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = Vec::new();
+        for event in &self.events {
+            if let RolloutEvent::Patch { files: patch_files, .. } = event {
+                for file in patch_files {
+                    if !files.contains(file) {
+                        files.push(file.clone());
+                    }
+                }
+            }
+        }
+        files
+    }
+}
+
+pub fn read_rollout(path: &Path) -> io::Result<Rollout> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut rollout = Rollout::default();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(value) = parse_json_line(&line) {
+            apply_line(&mut rollout, &value);
+        }
+    }
+    Ok(rollout)
+}
+
+fn apply_line(rollout: &mut Rollout, value: &Value) {
+    let timestamp = value
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .map(ToOwned::to_owned);
+    let Some(payload) = value.get("payload") else {
+        return;
+    };
+
+    match value.get("type").and_then(Value::as_str) {
+        Some("session_meta") => {
+            rollout.meta = RolloutMeta {
+                id: str_field(payload, "id").unwrap_or_default(),
+                cwd: str_field(payload, "cwd").unwrap_or_default(),
+                source: str_field(payload, "source").unwrap_or_default(),
+                timestamp: str_field(payload, "timestamp").or(timestamp),
+                cli_version: str_field(payload, "cli_version"),
+                model_provider: str_field(payload, "model_provider"),
+                model: rollout.meta.model.take(),
+            };
+        }
+        Some("turn_context") if rollout.meta.model.is_none() => {
+            rollout.meta.model = str_field(payload, "model");
+        }
+        Some("response_item") => apply_response_item(rollout, payload, timestamp),
+        Some("event_msg") if payload.get("type").and_then(Value::as_str) == Some("token_count") => {
+            let usage = payload
+                .get("info")
+                .and_then(|info| info.get("total_token_usage"));
+            if let Some(usage) = usage {
+                rollout.token_usage = Some(TokenUsage {
+                    input_tokens: u64_field(usage, "input_tokens"),
+                    cached_input_tokens: u64_field(usage, "cached_input_tokens"),
+                    output_tokens: u64_field(usage, "output_tokens"),
+                    reasoning_output_tokens: u64_field(usage, "reasoning_output_tokens"),
+                    total_tokens: u64_field(usage, "total_tokens"),
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply_response_item(rollout: &mut Rollout, payload: &Value, timestamp: Option<String>) {
+    let call_id = str_field(payload, "call_id");
+    match payload.get("type").and_then(Value::as_str) {
+        Some("message") => {
+            let role = str_field(payload, "role").unwrap_or_default();
+            if role != "user" && role != "assistant" {
+                return;
+            }
+            let text = payload
+                .get("content")
+                .and_then(Value::as_array)
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.get("text").and_then(Value::as_str))
+                        .filter(|text| !is_injected_context(text))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            if !text.trim().is_empty() {
+                rollout.events.push(RolloutEvent::Message {
+                    role,
+                    text: text.trim().to_string(),
+                    timestamp,
+                });
+            }
+        }
+        Some("function_call") => {
+            let name = str_field(payload, "name").unwrap_or_default();
+            let arguments = str_field(payload, "arguments").unwrap_or_default();
+            let parsed_args: Option<Value> = serde_json::from_str(&arguments).ok();
+            if name == "apply_patch" {
+                let patch = parsed_args
+                    .as_ref()
+                    .and_then(|args| str_field(args, "input"))
+                    .unwrap_or(arguments);
+                push_patch(rollout, call_id, patch, timestamp);
+                return;
+            }
+            let command = parsed_args.as_ref().and_then(command_from_args);
+            if let Some(patch) = command.as_deref().and_then(embedded_patch) {
+                push_patch(rollout, call_id, patch, timestamp);
+                return;
+            }
+            rollout.events.push(RolloutEvent::ToolCall {
+                call_id,
+                name,
+                command,
+                input: arguments,
+                timestamp,
+            });
+        }
+        Some("custom_tool_call") => {
+            let name = str_field(payload, "name").unwrap_or_default();
+            let input = str_field(payload, "input").unwrap_or_default();
+            if name == "apply_patch" {
+                push_patch(rollout, call_id, input, timestamp);
+                return;
+            }
+            rollout.events.push(RolloutEvent::ToolCall {
+                call_id,
+                name,
+                command: None,
+                input,
+                timestamp,
+            });
+        }
+        Some("local_shell_call") => {
+            let command = payload.get("action").and_then(command_from_args);
+            rollout.events.push(RolloutEvent::ToolCall {
+                call_id,
+                name: "local_shell".to_string(),
+                command,
+                input: payload
+                    .get("action")
+                    .map(Value::to_string)
+                    .unwrap_or_default(),
+                timestamp,
+            });
+        }
+        Some("function_call_output") | Some("custom_tool_call_output") => {
+            let output = payload.get("output").map(tool_output_text).unwrap_or_default();
+            rollout.events.push(RolloutEvent::ToolOutput {
+                call_id,
+                output,
+                timestamp,
+            });
+        }
+        _ => {}
+    }
+}
+
+fn push_patch(rollout: &mut Rollout, call_id: Option<String>, patch: String, timestamp: Option<String>) {
+    let files = patch_files(&patch);
+    rollout.events.push(RolloutEvent::Patch {
+        call_id,
+        files,
+        patch,
+        timestamp,
+    });
+}
+
+/// Text codex injects into the conversation (environment context, AGENTS.md
+/// instructions) rather than something the user typed.
+fn is_injected_context(text: &str) -> bool {
+    let trimmed = text.trim();
+    trimmed.starts_with('<') || trimmed.contains("<INSTRUCTIONS>")
+}
+
+/// Extract a displayable command line from shell tool arguments.
+/// Handles `{"command": ["bash", "-lc", "..."]}`, `{"command": "..."}` and `{"cmd": "..."}`.
+fn command_from_args(args: &Value) -> Option<String> {
+    let command = args.get("command").or_else(|| args.get("cmd"))?;
+    if let Some(text) = command.as_str() {
+        return Some(text.to_string());
+    }
+    let parts: Vec<&str> = command.as_array()?.iter().filter_map(Value::as_str).collect();
+    match parts.as_slice() {
+        [shell, flag, script] if shell.ends_with("sh") && flag.starts_with('-') => {
+            Some((*script).to_string())
+        }
+        [] => None,
+        _ => Some(parts.join(" ")),
+    }
+}
+
+/// `apply_patch` invoked through the shell, e.g. `apply_patch <<'EOF' ... EOF`.
+fn embedded_patch(command: &str) -> Option<String> {
+    let trimmed = command.trim_start();
+    if !trimmed.starts_with("apply_patch") {
+        return None;
+    }
+    let start = command.find("*** Begin Patch")?;
+    let patch = &command[start..];
+    let end = patch
+        .find("*** End Patch")
+        .map(|idx| idx + "*** End Patch".len())
+        .unwrap_or(patch.len());
+    Some(patch[..end].to_string())
+}
+
+/// File paths named by an `apply_patch` envelope.
+pub fn patch_files(patch: &str) -> Vec<String> {
+    const MARKERS: &[&str] = &[
+        "*** Add File: ",
+        "*** Update File: ",
+        "*** Delete File: ",
+        "*** Move to: ",
+    ];
+    let mut files: Vec<String> = Vec::new();
+    for line in patch.lines() {
+        for marker in MARKERS {
+            if let Some(path) = line.strip_prefix(marker) {
+                let path = path.trim().to_string();
+                if !path.is_empty() && !files.contains(&path) {
+                    files.push(path);
+                }
+            }
+        }
+    }
+    files
+}
+
+/// Function call outputs are either plain strings, JSON strings wrapping
+/// `{"output": ..., "metadata": ...}`, or structured content arrays.
+fn tool_output_text(output: &Value) -> String {
+    match output {
+        Value::String(raw) => match serde_json::from_str::<Value>(raw) {
+            Ok(Value::Object(map)) => map
+                .get("output")
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned)
+                .unwrap_or_else(|| raw.clone()),
+            _ => raw.clone(),
+        },
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| item.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Object(map) => map
+            .get("output")
+            .or_else(|| map.get("content"))
+            .map(tool_output_text)
+            .unwrap_or_else(|| output.to_string()),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(ToOwned::to_owned)
+}
+
+fn u64_field(value: &Value, key: &str) -> u64 {
+    value.get(key).and_then(Value::as_u64).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_patch_extracts_the_envelope() {
+        let command = "apply_patch <<'EOF'\n*** Begin Patch\n*** Add File: a.txt\n+hi\n*** End Patch\nEOF";
+        let patch = embedded_patch(command).unwrap();
+        assert!(patch.starts_with("*** Begin Patch"));
+        assert!(patch.ends_with("*** End Patch"));
+        assert_eq!(patch_files(&patch), ["a.txt"]);
+    }
+
+    #[test]
+    fn embedded_patch_ignores_an_end_marker_before_the_begin_marker() {
+        let command = "apply_patch '*** End Patch' <<'EOF'\n*** Begin Patch\n*** Delete File: b.txt\nEOF";
+        let patch = embedded_patch(command).unwrap();
+        assert_eq!(patch, "*** Begin Patch\n*** Delete File: b.txt\nEOF");
+        assert!(embedded_patch("echo '*** End Patch *** Begin Patch'").is_none());
+    }
+}
@@ -1,13 +1,16 @@
 use std::fs::{self, File};
-use std::io::{self, BufRead, BufReader, Write};
-use std::path::Path;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, Once, OnceLock};
 use std::time::{Duration, Instant};
 
+use codexia_db::codex_history::{self, CodexHistoryQuery, CodexHistoryRow};
 use codexia_shared::event_sink::EventSink;
+use super::search::{index_rollout_paths, sync_search_index};
 use super::utils::{
-    codexia_history_path, codex_home, extract_preview, file_created_time, file_mtime,
-    parse_json_line, parse_ts,
+    archived_sessions_dir, codexia_history_path, codex_home, extract_preview, file_created_time,
+    file_mtime, file_size, parse_json_line, parse_ts, rollout_path_key,
 };
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
@@ -19,8 +22,13 @@ type EventSinks = Arc<Mutex<Vec<Arc<dyn EventSink>>>>;
 
 static HISTORY_SCANNER_START: Once = Once::new();
 static HISTORY_SCANNER_SINKS: OnceLock<EventSinks> = OnceLock::new();
-static HISTORY_CACHE: OnceLock<Mutex<Option<Vec<HistoryEntry>>>> = OnceLock::new();
 static HISTORY_WATCH_CWDS: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+/// Set once the watcher covers `sessions/` (resp. `archived_sessions/`) and the
+/// index has been reconciled; listings then read straight from SQLite.
+static SESSIONS_WATCHED: AtomicBool = AtomicBool::new(false);
+static ARCHIVED_WATCHED: AtomicBool = AtomicBool::new(false);
+/// How long rollouts the search index failed on wait before they are retried.
+const SEARCH_RETRY_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -39,6 +47,21 @@ pub struct HistoryEntry {
     pub archived: bool,
 }
 
+impl From<CodexHistoryRow> for HistoryEntry {
+    fn from(row: CodexHistoryRow) -> Self {
+        Self {
+            id: row.thread_id,
+            preview: row.preview,
+            cwd: row.cwd,
+            path: row.path,
+            source: row.source,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            archived: row.archived,
+        }
+    }
+}
+
 pub fn start_history_scanner(event_sink: Arc<dyn EventSink>) {
     let sinks = HISTORY_SCANNER_SINKS
         .get_or_init(|| Arc::new(Mutex::new(Vec::new())))
@@ -47,51 +70,53 @@ pub fn start_history_scanner(event_sink: Arc<dyn EventSink>) {
         guarded.push(Arc::clone(&event_sink));
     }
 
-    let mut started_now = false;
     HISTORY_SCANNER_START.call_once(|| {
-        started_now = true;
-
         let sessions_root = codex_home().join("sessions");
-        let cached_entries = load_cached_history_entries().unwrap_or_default();
-        emit_entries_to_sinks(&sinks, &cached_entries);
+        let archived_root = codex_home().join("archived_sessions");
 
         std::thread::spawn(move || {
-            let mut known_preview_paths = known_preview_paths_from_entries(&cached_entries);
-            let _ = scan_and_emit_to_sinks(&sinks, &mut known_preview_paths);
-
-            if !sessions_root.exists() {
-                return;
-            }
+            // The flat history.jsonl cache is superseded by the codex_history table.
+            let _ = fs::remove_file(codexia_history_path());
 
             let (tx, rx) = std::sync::mpsc::channel();
-            let mut watcher: RecommendedWatcher = match notify::recommended_watcher(tx) {
-                Ok(watcher) => watcher,
+            let mut watcher: Option<RecommendedWatcher> = match notify::recommended_watcher(tx) {
+                Ok(watcher) => Some(watcher),
                 Err(err) => {
                     eprintln!("history scanner: watcher init failed: {err}");
-                    return;
+                    None
                 }
             };
+            let sessions_watched = watch_root(watcher.as_mut(), &sessions_root);
+            let archived_watched = watch_root(watcher.as_mut(), &archived_root);
+
+            for archived in [false, true] {
+                match sync_history_root(archived) {
+                    Ok(changed) => emit_entries_to_sinks(&sinks, &changed),
+                    Err(err) => eprintln!("history scanner: initial scan failed: {err}"),
+                }
+            }
+            SESSIONS_WATCHED.store(sessions_watched, Ordering::Release);
+            ARCHIVED_WATCHED.store(archived_watched, Ordering::Release);
+            if let Err(err) = sync_search_index() {
+                eprintln!("history scanner: search index sync failed: {err}");
+            }
 
-            if let Err(err) = watcher.watch(&sessions_root, RecursiveMode::Recursive) {
-                eprintln!("history scanner: watch failed: {err}");
+            if !sessions_watched && !archived_watched {
                 return;
             }
+
             let mut last_scan = Instant::now() - Duration::from_secs(60);
-            let mut pending_rescan = false;
+            let mut pending_paths: HashSet<PathBuf> = HashSet::new();
+            // Rollouts the search index failed on, retried with the next batch.
+            let mut search_retry: HashSet<PathBuf> = HashSet::new();
+            let mut last_retry = Instant::now();
             loop {
                 match rx.recv_timeout(Duration::from_millis(200)) {
                     Ok(Ok(event)) => {
-                        if !should_rescan_for_event(&event, &known_preview_paths) {
+                        if !should_rescan_for_event(&event) {
                             continue;
                         }
-
-                        if last_scan.elapsed() < Duration::from_millis(500) {
-                            pending_rescan = true;
-                        } else {
-                            last_scan = Instant::now();
-                            let _ = scan_and_emit_to_sinks(&sinks, &mut known_preview_paths);
-                            pending_rescan = false;
-                        }
+                        pending_paths.extend(event.paths.into_iter().filter(|path| is_rollout_file(path)));
                     }
                     Ok(Err(err)) => {
                         eprintln!("history scanner: watch event error: {err}");
@@ -100,19 +125,24 @@ pub fn start_history_scanner(event_sink: Arc<dyn EventSink>) {
                     Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
                 }
 
-                if pending_rescan && last_scan.elapsed() >= Duration::from_millis(500) {
+                if !pending_paths.is_empty() && last_scan.elapsed() >= Duration::from_millis(500) {
                     last_scan = Instant::now();
-                    let _ = scan_and_emit_to_sinks(&sinks, &mut known_preview_paths);
-                    pending_rescan = false;
+                    match index_history_paths(&pending_paths) {
+                        Ok(changed) => emit_entries_to_sinks(&sinks, &changed),
+                        Err(err) => eprintln!("history scanner: index update failed: {err}"),
+                    }
+                    search_retry = index_rollout_paths(pending_paths.iter().chain(&search_retry))
+                        .into_iter()
+                        .collect();
+                    last_retry = Instant::now();
+                    pending_paths.clear();
+                } else if !search_retry.is_empty() && last_retry.elapsed() >= SEARCH_RETRY_INTERVAL {
+                    last_retry = Instant::now();
+                    search_retry = index_rollout_paths(search_retry.iter()).into_iter().collect();
                 }
             }
         });
     });
-
-    if !started_now {
-        let entries = load_cached_history_entries().unwrap_or_default();
-        emit_entries_to_sink(&*event_sink, &entries);
-    }
 }
 
 pub fn history_entries_to_thread_values(entries: &[HistoryEntry]) -> Vec<Value> {
@@ -122,19 +152,87 @@ pub fn history_entries_to_thread_values(entries: &[HistoryEntry]) -> Vec<Value>
         .collect()
 }
 
-pub fn scan_history_entries() -> io::Result<Vec<HistoryEntry>> {
-    let mut entries = load_cached_history_entries()?;
-    entries = reconcile_sessions_with_cache(entries)?;
-    entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-    write_history(&entries)?;
-    store_cached_history_entries(entries.clone());
-    Ok(entries)
+/// Reconcile the history index with `sessions/` or `archived_sessions/`: re-read
+/// files whose mtime or size changed and drop rows for files that are gone.
+/// Returns the entries that were added or updated.
+pub fn sync_history_root(archived: bool) -> io::Result<Vec<HistoryEntry>> {
+    let root = if archived {
+        codex_home().join("archived_sessions")
+    } else {
+        codex_home().join("sessions")
+    };
+    let known = codex_history::file_states(archived).map_err(io::Error::other)?;
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut changed: Vec<CodexHistoryRow> = Vec::new();
+    if root.exists() {
+        for entry in WalkDir::new(&root).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() || !is_rollout_file(entry.path()) {
+                continue;
+            }
+            let key = rollout_path_key(entry.path()).to_string_lossy().to_string();
+            let state = (
+                file_mtime(entry.path()).unwrap_or_default(),
+                file_size(entry.path()).unwrap_or_default(),
+            );
+            seen.insert(key.clone());
+            if known.get(&key) == Some(&state) {
+                continue;
+            }
+            changed.push(read_history_row(entry.path(), key, archived, state));
+        }
+    }
+
+    let removed: Vec<String> = known
+        .into_keys()
+        .filter(|path| !seen.contains(path))
+        .collect();
+    codex_history::apply_changes(&changed, &removed).map_err(io::Error::other)?;
+
+    Ok(changed.into_iter().map(HistoryEntry::from).collect())
 }
 
-pub fn scan_archived_entries() -> io::Result<Vec<HistoryEntry>> {
-    let mut entries = scan_archived_sessions()?;
-    entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-    Ok(entries)
+/// Update the index for paths reported by the watcher. A session file that
+/// disappears is usually an archive: codex moves it into `archived_sessions/`
+/// under the same name, so that location is indexed in its place.
+fn index_history_paths(paths: &HashSet<PathBuf>) -> io::Result<Vec<HistoryEntry>> {
+    let archived_root = archived_sessions_dir();
+    let mut changed: Vec<CodexHistoryRow> = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+
+    for path in paths {
+        let key = rollout_path_key(path);
+        let archived = key.starts_with(&archived_root);
+        let archived_twin = key
+            .file_name()
+            .filter(|_| !archived)
+            .map(|file_name| archived_root.join(file_name));
+
+        if path.is_file() {
+            let state = (
+                file_mtime(path).unwrap_or_default(),
+                file_size(path).unwrap_or_default(),
+            );
+            changed.push(read_history_row(path, key.to_string_lossy().to_string(), archived, state));
+            // Unarchiving moves the file back out of `archived_sessions/`.
+            if let Some(twin) = archived_twin.filter(|twin| !twin.exists()) {
+                removed.push(twin.to_string_lossy().to_string());
+            }
+            continue;
+        }
+
+        removed.push(key.to_string_lossy().to_string());
+        if let Some(twin) = archived_twin.filter(|twin| twin.is_file()) {
+            let state = (
+                file_mtime(&twin).unwrap_or_default(),
+                file_size(&twin).unwrap_or_default(),
+            );
+            changed.push(read_history_row(&twin, twin.to_string_lossy().to_string(), true, state));
+        }
+    }
+
+    codex_history::apply_changes(&changed, &removed).map_err(io::Error::other)?;
+    Ok(changed.into_iter().map(HistoryEntry::from).collect())
 }
 
 pub fn list_threads_payload(params: Value, cwd: Option<&str>) -> io::Result<Value> {
@@ -142,6 +240,18 @@ pub fn list_threads_payload(params: Value, cwd: Option<&str>) -> io::Result<Valu
         .get("archived")
         .and_then(Value::as_bool)
         .unwrap_or(false);
+    if !archived && let Some(filter_cwd) = cwd {
+        remember_watch_cwd(filter_cwd);
+    }
+    list_history_page(&params, archived, cwd)
+}
+
+pub fn list_archived_threads_payload(params: Value) -> io::Result<Value> {
+    // Archived threads always come from codex_home()/archived_sessions.
+    list_history_page(&params, true, None)
+}
+
+fn list_history_page(params: &Value, archived: bool, cwd: Option<&str>) -> io::Result<Value> {
     let sort_key = params
         .get("sortKey")
         .and_then(Value::as_str)
@@ -167,132 +277,88 @@ pub fn list_threads_payload(params: Value, cwd: Option<&str>) -> io::Result<Valu
         })
         .filter(|kinds| !kinds.is_empty());
 
-    let mut entries = if archived {
-        scan_archived_entries()?
-    } else {
-        if let Some(filter_cwd) = cwd {
-            remember_watch_cwd(filter_cwd);
-        }
-        scan_history_entries()?
-    };
+    ensure_history_index(archived)?;
 
-    if let Some(cwd) = cwd {
-        let filter_cwd = cwd.trim();
-        if !filter_cwd.is_empty() {
-            let filter_repo_root = repo_root_for_path(filter_cwd);
-            let mut entry_repo_roots: HashMap<String, Option<String>> = HashMap::new();
-            entries.retain(|entry| {
-                if entry.cwd == filter_cwd {
-                    return true;
-                }
-                let entry_root = entry_repo_roots
-                    .entry(entry.cwd.clone())
-                    .or_insert_with(|| repo_root_for_path(&entry.cwd));
-                match (&filter_repo_root, entry_root) {
-                    (Some(filter_root), Some(entry_root)) => filter_root == entry_root,
-                    _ => false,
-                }
-            });
-        }
-    }
-
-    if let Some(source_kinds) = source_kinds {
-        entries.retain(|entry| source_kinds.iter().any(|kind| kind == &entry.source));
-    }
+    let cwds = match cwd.map(str::trim).filter(|cwd| !cwd.is_empty()) {
+        Some(filter_cwd) => Some(matching_cwds(filter_cwd, archived)?),
+        None => None,
+    };
 
-    entries.retain(|entry| !entry.preview.trim().is_empty());
-    if sort_key == "updated_at" {
-        entries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-    } else {
-        entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-    }
+    let page = codex_history::list(&CodexHistoryQuery {
+        archived,
+        cwds,
+        source_kinds,
+        sort_by_updated: sort_key == "updated_at",
+        offset,
+        limit,
+    })
+    .map_err(io::Error::other)?;
 
-    let total = entries.len();
-    let start = offset.min(total);
-    let end = start.saturating_add(limit).min(total);
-    let next_cursor = if end < total {
-        Some(end.to_string())
+    let next_cursor = if page.has_more {
+        Some((offset + page.rows.len()).to_string())
     } else {
         None
     };
-
-    let page = &entries[start..end];
+    let entries: Vec<HistoryEntry> = page.rows.into_iter().map(HistoryEntry::from).collect();
     Ok(json!({
-        "data": history_entries_to_thread_values(page),
+        "data": history_entries_to_thread_values(&entries),
         "nextCursor": next_cursor,
     }))
 }
 
-pub fn list_archived_threads_payload(params: Value) -> io::Result<Value> {
-    let sort_key = params
-        .get("sortKey")
-        .and_then(Value::as_str)
-        .unwrap_or("created_at");
-    let limit = params
-        .get("limit")
-        .and_then(Value::as_u64)
-        .map(|n| n as usize)
-        .unwrap_or(20);
-    let offset = params
-        .get("cursor")
-        .and_then(Value::as_str)
-        .and_then(|v| v.parse::<usize>().ok())
-        .unwrap_or(0);
-    let source_kinds: Option<Vec<String>> = params
-        .get("sourceKinds")
-        .and_then(Value::as_array)
-        .map(|values| {
-            values
-                .iter()
-                .filter_map(|v| v.as_str().map(ToOwned::to_owned))
-                .collect::<Vec<_>>()
-        })
-        .filter(|kinds| !kinds.is_empty());
-
-    // Archived threads are always scanned from codex_home()/archived_sessions.
-    let mut entries = scan_archived_entries()?;
-
-    if let Some(source_kinds) = source_kinds {
-        entries.retain(|entry| source_kinds.iter().any(|kind| kind == &entry.source));
-    }
-
-    entries.retain(|entry| !entry.preview.trim().is_empty());
-    if sort_key == "updated_at" {
-        entries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-    } else {
-        entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-    }
-
-    let total = entries.len();
-    let start = offset.min(total);
-    let end = start.saturating_add(limit).min(total);
-    let next_cursor = if end < total {
-        Some(end.to_string())
+/// Without a live watcher on the root, reconcile before reading so listings
+/// still reflect the filesystem; only changed files are re-read.
+fn ensure_history_index(archived: bool) -> io::Result<()> {
+    let watched = if archived {
+        &ARCHIVED_WATCHED
     } else {
-        None
+        &SESSIONS_WATCHED
     };
+    if watched.load(Ordering::Acquire) {
+        return Ok(());
+    }
+    sync_history_root(archived).map(|_| ())
+}
 
-    let page = &entries[start..end];
-    Ok(json!({
-        "data": history_entries_to_thread_values(page),
-        "nextCursor": next_cursor,
-    }))
+/// Indexed cwds that match `filter_cwd` exactly or live in the same git repository.
+fn matching_cwds(filter_cwd: &str, archived: bool) -> io::Result<Vec<String>> {
+    let filter_repo_root = repo_root_for_path(filter_cwd);
+    let mut cwds = codex_history::distinct_cwds(archived).map_err(io::Error::other)?;
+    cwds.retain(|cwd| {
+        if cwd == filter_cwd {
+            return true;
+        }
+        match (&filter_repo_root, repo_root_for_path(cwd)) {
+            (Some(filter_root), Some(entry_root)) => *filter_root == entry_root,
+            _ => false,
+        }
+    });
+    Ok(cwds)
 }
 
-fn scan_and_emit_to_sinks(
-    sinks: &EventSinks,
-    known_preview_paths: &mut HashSet<String>,
-) -> io::Result<()> {
-    let entries = scan_history_entries()?;
-    *known_preview_paths = known_preview_paths_from_entries(&entries);
-    emit_entries_to_sinks(sinks, &entries);
-    Ok(())
+fn watch_root(watcher: Option<&mut RecommendedWatcher>, root: &Path) -> bool {
+    let Some(watcher) = watcher else {
+        return false;
+    };
+    if !root.exists() {
+        return false;
+    }
+    match watcher.watch(root, RecursiveMode::Recursive) {
+        Ok(()) => true,
+        Err(err) => {
+            eprintln!("history scanner: watch failed for {}: {err}", root.display());
+            false
+        }
+    }
 }
 
 fn emit_entries_to_sink(_event_sink: &dyn EventSink, _entries: &[HistoryEntry]) {
 }
 
 fn emit_entries_to_sinks(sinks: &EventSinks, entries: &[HistoryEntry]) {
+    if entries.is_empty() {
+        return;
+    }
     let sink_list = match sinks.lock() {
         Ok(guarded) => guarded.clone(),
         Err(_) => Vec::new(),
@@ -302,7 +368,7 @@ fn emit_entries_to_sinks(sinks: &EventSinks, entries: &[HistoryEntry]) {
     }
 }
 
-fn should_rescan_for_event(event: &Event, _known_preview_paths: &HashSet<String>) -> bool {
+fn should_rescan_for_event(event: &Event) -> bool {
     if matches!(event.kind, EventKind::Access(_)) {
         return false;
     }
@@ -315,21 +381,11 @@ fn should_rescan_for_event(event: &Event, _known_preview_paths: &HashSet<String>
         return false;
     }
 
-    for path in &event.paths {
-        if path.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
-            continue;
-        }
-        return true;
-    }
-    false
+    event.paths.iter().any(|path| is_rollout_file(path))
 }
 
-fn known_preview_paths_from_entries(entries: &[HistoryEntry]) -> HashSet<String> {
-    entries
-        .iter()
-        .filter(|entry| !entry.preview.trim().is_empty())
-        .map(|entry| history_path_key(Path::new(&entry.path)))
-        .collect()
+fn is_rollout_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("jsonl")
 }
 
 fn watch_cwds() -> &'static Mutex<HashSet<String>> {
@@ -347,142 +403,58 @@ fn remember_watch_cwd(cwd: &str) {
 }
 
 fn repo_root_for_path(path: &str) -> Option<String> {
-    let repo = gix::discover(path).ok()?;
-    let root = repo.workdir()?;
-    Some(root.to_string_lossy().to_string())
-}
-
-fn reconcile_sessions_with_cache(cached_entries: Vec<HistoryEntry>) -> io::Result<Vec<HistoryEntry>> {
-    let sessions_root = codex_home().join("sessions");
-    if !sessions_root.exists() {
-        return Ok(Vec::new());
-    }
-
-    let cached_by_path: HashMap<String, HistoryEntry> = cached_entries
-        .into_iter()
-        .filter(|entry| !entry.archived)
-        .map(|entry| (history_path_key(Path::new(&entry.path)), entry))
-        .collect();
-
-    let mut paths_in_fs: HashSet<String> = HashSet::new();
-    let mut next_entries: Vec<HistoryEntry> = Vec::new();
-
-    for entry in WalkDir::new(&sessions_root).into_iter().filter_map(|e| e.ok()) {
-        if !entry.file_type().is_file() {
-            continue;
-        }
-        if entry.path().extension().and_then(|s| s.to_str()) != Some("jsonl") {
-            continue;
-        }
-
-        let key = history_path_key(entry.path());
-        paths_in_fs.insert(key.clone());
-        let mtime = file_mtime(entry.path()).unwrap_or_default();
-
-        if let Some(existing) = cached_by_path.get(&key) {
-            if !existing.preview.trim().is_empty() {
-                next_entries.push(existing.clone());
-                continue;
-            }
-            if existing.updated_at == mtime {
-                next_entries.push(existing.clone());
-                continue;
-            }
-        }
-
-        if let Some(history_entry) = extract_entry_from_file(entry.path(), false) {
-            next_entries.push(history_entry);
-        }
+    static REPO_ROOTS: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+    let cache = REPO_ROOTS.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(root) = cache.lock().ok().and_then(|guarded| guarded.get(path).cloned()) {
+        return root;
     }
 
-    next_entries.retain(|entry| {
-        let key = history_path_key(Path::new(&entry.path));
-        paths_in_fs.contains(&key)
-    });
-
-    Ok(next_entries)
-}
-
-fn history_path_key(path: &Path) -> String {
-    let normalized = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
-    normalized.to_string_lossy().to_string()
-}
-
-fn history_cache() -> &'static Mutex<Option<Vec<HistoryEntry>>> {
-    HISTORY_CACHE.get_or_init(|| Mutex::new(None))
-}
-
-fn load_cached_history_entries() -> io::Result<Vec<HistoryEntry>> {
-    if let Ok(guarded) = history_cache().lock() {
-        if let Some(entries) = guarded.as_ref() {
-            return Ok(entries.clone());
-        }
+    let root = gix::discover(path)
+        .ok()
+        .and_then(|repo| repo.workdir().map(|root| root.to_string_lossy().to_string()));
+    if let Ok(mut guarded) = cache.lock() {
+        guarded.insert(path.to_string(), root.clone());
     }
-
-    let loaded = read_history_file()?;
-    store_cached_history_entries(loaded.clone());
-    Ok(loaded)
+    root
 }
 
-fn store_cached_history_entries(entries: Vec<HistoryEntry>) {
-    if let Ok(mut guarded) = history_cache().lock() {
-        *guarded = Some(entries);
-    }
-}
-
-fn read_history_file() -> io::Result<Vec<HistoryEntry>> {
-    let path = codexia_history_path();
-    if !path.exists() {
-        return Ok(Vec::new());
-    }
-
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut entries = Vec::new();
-    for line in reader.lines() {
-        let line = line?;
-        if line.trim().is_empty() {
-            continue;
-        }
-    if let Ok(entry) = serde_json::from_str::<HistoryEntry>(&line) {
-            entries.push(entry);
-        }
-    }
-    Ok(entries)
-}
-
-fn scan_archived_sessions() -> io::Result<Vec<HistoryEntry>> {
-    let sessions_root = codex_home().join("archived_sessions");
-    let mut entries = Vec::new();
-    if sessions_root.exists() {
-        collect_entries(&sessions_root, &mut entries, true);
-    }
-
-    Ok(entries)
-}
-
-fn collect_entries(root: &Path, entries: &mut Vec<HistoryEntry>, archived: bool) {
-    for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
-        if !entry.file_type().is_file() {
-            continue;
-        }
-        if entry.path().extension().and_then(|s| s.to_str()) != Some("jsonl") {
-            continue;
-        }
-
-        if let Some(history_entry) = extract_entry_from_file(entry.path(), archived) {
-            entries.push(history_entry);
-        }
+/// Build the index row for one rollout. Files without a usable preview yet still get
+/// a row (with an empty preview) so they are skipped until they change on disk.
+fn read_history_row(path: &Path, key: String, archived: bool, state: (i64, i64)) -> CodexHistoryRow {
+    let (file_mtime, file_size) = state;
+    match extract_entry_from_file(path, archived) {
+        Some(entry) => CodexHistoryRow {
+            path: key,
+            thread_id: entry.id,
+            preview: entry.preview,
+            cwd: entry.cwd,
+            source: entry.source,
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+            archived,
+            file_mtime,
+            file_size,
+        },
+        None => CodexHistoryRow {
+            path: key,
+            thread_id: String::new(),
+            preview: String::new(),
+            cwd: String::new(),
+            source: String::new(),
+            created_at: file_mtime,
+            updated_at: file_mtime,
+            archived,
+            file_mtime,
+            file_size,
+        },
     }
 }
 
 fn extract_entry_from_file(path: &Path, archived: bool) -> Option<HistoryEntry> {
     let file = File::open(path).ok()?;
     let reader = BufReader::new(file);
-    let mut lines: Vec<String> = Vec::new();
-    for line in reader.lines().flatten() {
-        lines.push(line);
-    }
+    // Only the session_meta line and the first dozen items are needed for the preview.
+    let lines: Vec<String> = reader.lines().map_while(Result::ok).take(13).collect();
     if lines.len() < 5 {
         return None;
     }
@@ -536,18 +508,3 @@ fn extract_entry_from_file(path: &Path, archived: bool) -> Option<HistoryEntry>
         archived,
     })
 }
-
-fn write_history(entries: &[HistoryEntry]) -> io::Result<()> {
-    let history_path = codexia_history_path();
-    if let Some(parent) = history_path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-
-    let mut file = File::create(history_path)?;
-    for entry in entries {
-        serde_json::to_writer(&mut file, entry)?;
-        file.write_all(b"\n")?;
-    }
-
-    Ok(())
-}
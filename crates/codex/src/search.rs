@@ -0,0 +1,169 @@
+//! Full-text search over codex rollouts, backed by the FTS5 index in `codexia-db`.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use codexia_db::codex_search::{
+    self, CodexSearchDocument, CodexSearchHit, CodexSearchQuery, CodexSearchSegment,
+};
+use walkdir::WalkDir;
+
+use super::rollout::{RolloutEvent, read_rollout};
+use super::utils::{
+    archived_sessions_dir, codex_home, file_created_time, file_mtime, file_size, parse_ts,
+    rollout_path_key,
+};
+
+/// Index every rollout under `sessions/` and `archived_sessions/`, skipping files
+/// whose mtime and size are unchanged, and drop rows for files that no longer exist.
+pub fn sync_search_index() -> Result<(), String> {
+    let mut seen: HashSet<String> = HashSet::new();
+    for (root, archived) in rollout_roots() {
+        if !root.exists() {
+            continue;
+        }
+        for entry in WalkDir::new(&root).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() || !is_rollout_file(entry.path()) {
+                continue;
+            }
+            seen.insert(rollout_path_key(entry.path()).to_string_lossy().to_string());
+            if let Err(err) = index_rollout_file(entry.path(), archived) {
+                log::warn!("codex search: failed to index {}: {}", entry.path().display(), err);
+            }
+        }
+    }
+
+    for path in codex_search::indexed_paths()? {
+        if !seen.contains(&path) {
+            codex_search::remove_document(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Re-index rollouts reported by the history watcher. A removed session file is
+/// usually an archive (codex moves it into `archived_sessions/` under the same name),
+/// so that location is checked before the path is dropped from the index.
+/// Each file is indexed on its own; the paths that failed are returned so the
+/// caller can retry them.
+pub fn index_rollout_paths<'a, I>(paths: I) -> Vec<PathBuf>
+where
+    I: IntoIterator<Item = &'a PathBuf>,
+{
+    let archived_root = archived_sessions_dir();
+    let mut failed = Vec::new();
+    for path in paths {
+        if !is_rollout_file(path) {
+            continue;
+        }
+        if let Err(err) = index_rollout_path(path, &archived_root) {
+            log::warn!("codex search: failed to index {}: {}", path.display(), err);
+            failed.push(path.clone());
+        }
+    }
+    failed
+}
+
+fn index_rollout_path(path: &Path, archived_root: &Path) -> Result<(), String> {
+    let key = rollout_path_key(path);
+    let archived = key.starts_with(archived_root);
+    if path.is_file() {
+        index_rollout_file(path, archived)?;
+        // An unarchived thread moves back out of `archived_sessions/`.
+        if let Some(file_name) = key.file_name().filter(|_| !archived) {
+            let archived_path = archived_root.join(file_name);
+            if !archived_path.exists() {
+                codex_search::remove_document(&archived_path.to_string_lossy())?;
+            }
+        }
+        return Ok(());
+    }
+
+    codex_search::remove_document(&key.to_string_lossy())?;
+    if let Some(file_name) = key.file_name().filter(|_| !archived) {
+        let archived_path = archived_root.join(file_name);
+        if archived_path.is_file() {
+            index_rollout_file(&archived_path, true)?;
+        }
+    }
+    Ok(())
+}
+
+/// Index a single rollout file. Returns `false` when the stored copy is already current.
+pub fn index_rollout_file(path: &Path, archived: bool) -> Result<bool, String> {
+    let path_key = rollout_path_key(path).to_string_lossy().to_string();
+    let mtime = file_mtime(path).unwrap_or_default();
+    let size = file_size(path).unwrap_or_default();
+    if codex_search::indexed_file_state(&path_key)? == Some((mtime, size)) {
+        return Ok(false);
+    }
+
+    let rollout = read_rollout(path)
+        .map_err(|e| format!("Failed to read rollout {}: {}", path.display(), e))?;
+
+    let mut segments: Vec<CodexSearchSegment> = Vec::new();
+    let mut preview = String::new();
+    for event in &rollout.events {
+        match event {
+            RolloutEvent::Message { role, text, .. } => {
+                if preview.is_empty() && role == "user" {
+                    preview = text.clone();
+                }
+                segments.push(segment(role, text));
+            }
+            RolloutEvent::ToolCall {
+                command: Some(command),
+                ..
+            } => segments.push(segment("command", command)),
+            _ => {}
+        }
+    }
+    for file in rollout.touched_files() {
+        segments.push(segment("file", &file));
+    }
+
+    let created_at = rollout
+        .meta
+        .timestamp
+        .as_deref()
+        .and_then(parse_ts)
+        .or_else(|| file_created_time(path))
+        .unwrap_or(mtime);
+
+    codex_search::upsert_document(&CodexSearchDocument {
+        path: path_key,
+        thread_id: rollout.meta.id,
+        cwd: rollout.meta.cwd,
+        preview,
+        created_at,
+        updated_at: mtime,
+        archived,
+        file_mtime: mtime,
+        file_size: size,
+        segments,
+    })?;
+    Ok(true)
+}
+
+pub fn search_threads(query: &CodexSearchQuery) -> Result<Vec<CodexSearchHit>, String> {
+    codex_search::search(query)
+}
+
+fn rollout_roots() -> [(PathBuf, bool); 2] {
+    let home = codex_home();
+    [
+        (home.join("sessions"), false),
+        (home.join("archived_sessions"), true),
+    ]
+}
+
+fn is_rollout_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("jsonl")
+}
+
+fn segment(kind: &str, text: &str) -> CodexSearchSegment {
+    CodexSearchSegment {
+        kind: kind.to_string(),
+        text: text.to_string(),
+    }
+}
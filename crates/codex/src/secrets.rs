@@ -0,0 +1,345 @@
+//! Storage for provider API keys, so they do not have to live in shell dotfiles.
+//!
+//! On Linux keys go to the Secret Service (GNOME Keyring, KWallet, ...) when one
+//! is running. Otherwise they are kept in `~/.codexia/secrets.enc`, encrypted
+//! with ChaCha20-Poly1305 under a random key in `~/.codexia/secrets.key`. Both
+//! files are private to the user; the encryption keeps the secrets out of
+//! backups and synced copies of the secrets file, it does not protect them from
+//! other processes running as the same user.
+//!
+//! The names of stored keys (never the values) are tracked in
+//! `~/.codexia/secrets.json` so [`stored_env`] can inject them into the
+//! `codex app-server` and Claude processes Codexia spawns.
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[cfg(target_os = "linux")]
+const KEYRING_SERVICE: &str = "codexia";
+const INDEX_FILE: &str = "secrets.json";
+const SECRETS_FILE: &str = "secrets.enc";
+const KEY_FILE: &str = "secrets.key";
+const NONCE_LEN: usize = 12;
+
+/// Serializes read-modify-write cycles on the index and the encrypted file.
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretBackend {
+    SecretService,
+    EncryptedFile,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredSecret {
+    pub name: String,
+    pub backend: SecretBackend,
+}
+
+fn secrets_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join(".codexia"))
+}
+
+/// Store `value` under the environment variable `name`, replacing any previous value.
+pub fn set_secret(name: &str, value: &str) -> Result<SecretBackend, String> {
+    validate_name(name)?;
+    let dir = secrets_dir()?;
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut index = read_index(&dir)?;
+    let backend = if secret_service::set(name, value).is_ok() {
+        SecretBackend::SecretService
+    } else {
+        let mut secrets = read_encrypted(&dir)?;
+        secrets.insert(name.to_string(), value.to_string());
+        write_encrypted(&dir, &secrets)?;
+        SecretBackend::EncryptedFile
+    };
+
+    // Drop a stale copy left in the other backend.
+    match index.insert(name.to_string(), backend) {
+        Some(SecretBackend::EncryptedFile) if backend == SecretBackend::SecretService => {
+            let mut secrets = read_encrypted(&dir)?;
+            secrets.remove(name);
+            write_encrypted(&dir, &secrets)?;
+        }
+        Some(SecretBackend::SecretService) if backend == SecretBackend::EncryptedFile => {
+            let _ = secret_service::delete(name);
+        }
+        _ => {}
+    }
+    write_index(&dir, &index)?;
+    Ok(backend)
+}
+
+/// The stored value for `name`, if any.
+pub fn get_secret(name: &str) -> Option<String> {
+    let dir = secrets_dir().ok()?;
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let backend = *read_index(&dir).ok()?.get(name)?;
+    match backend {
+        SecretBackend::SecretService => secret_service::get(name),
+        SecretBackend::EncryptedFile => read_encrypted(&dir).ok()?.remove(name),
+    }
+}
+
+pub fn delete_secret(name: &str) -> Result<(), String> {
+    let dir = secrets_dir()?;
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = read_index(&dir)?;
+    match index.remove(name) {
+        Some(SecretBackend::SecretService) => secret_service::delete(name)?,
+        Some(SecretBackend::EncryptedFile) => {
+            let mut secrets = read_encrypted(&dir)?;
+            secrets.remove(name);
+            write_encrypted(&dir, &secrets)?;
+        }
+        None => return Err(format!("Secret '{}' not found", name)),
+    }
+    write_index(&dir, &index)
+}
+
+/// Names of the stored secrets and where each one lives. Values are not read.
+pub fn list_secrets() -> Result<Vec<StoredSecret>, String> {
+    let dir = secrets_dir()?;
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(read_index(&dir)?
+        .into_iter()
+        .map(|(name, backend)| StoredSecret { name, backend })
+        .collect())
+}
+
+/// Every stored secret as environment variables for a child process.
+/// Secrets that cannot be read (e.g. a locked keyring) are skipped.
+pub fn stored_env() -> HashMap<String, String> {
+    let Ok(dir) = secrets_dir() else {
+        return HashMap::new();
+    };
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let index = match read_index(&dir) {
+        Ok(index) => index,
+        Err(e) => {
+            log::warn!("Failed to read stored secrets: {}", e);
+            return HashMap::new();
+        }
+    };
+
+    let mut file_secrets: Option<BTreeMap<String, String>> = None;
+    let mut env = HashMap::new();
+    for (name, backend) in index {
+        let value = match backend {
+            SecretBackend::SecretService => secret_service::get(&name),
+            SecretBackend::EncryptedFile => file_secrets
+                .get_or_insert_with(|| read_encrypted(&dir).unwrap_or_default())
+                .get(&name)
+                .cloned(),
+        };
+        match value {
+            Some(value) => {
+                env.insert(name, value);
+            }
+            None => log::warn!("Stored secret '{}' could not be read", name),
+        }
+    }
+    env
+}
+
+/// Secrets are exposed as environment variables, so names must be valid ones.
+fn validate_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid environment variable name: '{}'", name))
+    }
+}
+
+fn read_index(dir: &Path) -> Result<BTreeMap<String, SecretBackend>, String> {
+    let path = dir.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn write_index(dir: &Path, index: &BTreeMap<String, SecretBackend>) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize secret index: {}", e))?;
+    write_private(&dir.join(INDEX_FILE), content.as_bytes())
+}
+
+fn read_encrypted(dir: &Path) -> Result<BTreeMap<String, String>, String> {
+    let path = dir.join(SECRETS_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if data.len() < NONCE_LEN {
+        return Err(format!("{} is corrupted", path.display()));
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let plaintext = cipher(dir, false)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| format!("Failed to decrypt {}", path.display()))?;
+    serde_json::from_slice(&plaintext)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn write_encrypted(dir: &Path, secrets: &BTreeMap<String, String>) -> Result<(), String> {
+    let plaintext =
+        serde_json::to_vec(secrets).map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(dir, true)?
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| "Failed to encrypt secrets".to_string())?;
+    let mut data = nonce.to_vec();
+    data.extend_from_slice(&ciphertext);
+    write_private(&dir.join(SECRETS_FILE), &data)
+}
+
+/// The file encryption key, generated on first write.
+fn cipher(dir: &Path, create: bool) -> Result<ChaCha20Poly1305, String> {
+    let path = dir.join(KEY_FILE);
+    match fs::read(&path) {
+        Ok(key) if key.len() == 32 => Ok(ChaCha20Poly1305::new(Key::from_slice(&key))),
+        Ok(_) => Err(format!("{} is corrupted", path.display())),
+        Err(_) if create => {
+            let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+            write_private(&path, &key)?;
+            Ok(ChaCha20Poly1305::new(&key))
+        }
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+/// Write through a temp file readable only by the current user, then rename
+/// over `path` so a crash never leaves a half-written file behind.
+fn write_private(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    let tmp = path.with_extension("tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&tmp)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    file.write_all(data)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(target_os = "linux")]
+mod secret_service {
+    use super::KEYRING_SERVICE;
+    use keyring::Entry;
+
+    pub fn set(name: &str, value: &str) -> Result<(), String> {
+        Entry::new(KEYRING_SERVICE, name)
+            .and_then(|entry| entry.set_password(value))
+            .map_err(|e| format!("Secret Service unavailable: {}", e))
+    }
+
+    pub fn get(name: &str) -> Option<String> {
+        Entry::new(KEYRING_SERVICE, name)
+            .and_then(|entry| entry.get_password())
+            .ok()
+    }
+
+    pub fn delete(name: &str) -> Result<(), String> {
+        match Entry::new(KEYRING_SERVICE, name).and_then(|entry| entry.delete_credential()) {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to delete secret '{}': {}", name, e)),
+        }
+    }
+}
+
+/// Only Linux has a Secret Service; elsewhere everything goes to the encrypted file.
+#[cfg(not(target_os = "linux"))]
+mod secret_service {
+    pub fn set(_name: &str, _value: &str) -> Result<(), String> {
+        Err("Secret Service is only available on Linux".to_string())
+    }
+
+    pub fn get(_name: &str) -> Option<String> {
+        None
+    }
+
+    pub fn delete(_name: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default();
+        std::env::temp_dir().join(format!("codexia-{}-{}-{}", name, std::process::id(), nanos))
+    }
+
+    #[test]
+    fn encrypted_file_round_trips() {
+        let dir = temp_dir("secrets");
+        let secrets = BTreeMap::from([
+            ("OPENAI_API_KEY".to_string(), "sk-test-value".to_string()),
+            ("GROQ_API_KEY".to_string(), "gsk-other".to_string()),
+        ]);
+        write_encrypted(&dir, &secrets).unwrap();
+
+        assert_eq!(read_encrypted(&dir).unwrap(), secrets);
+        let raw = fs::read(dir.join(SECRETS_FILE)).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("sk-test-value"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            for file in [SECRETS_FILE, KEY_FILE] {
+                let mode = fs::metadata(dir.join(file)).unwrap().permissions().mode();
+                assert_eq!(mode & 0o777, 0o600, "{}", file);
+            }
+        }
+
+        // A different key cannot decrypt the file.
+        write_private(&dir.join(KEY_FILE), &[7u8; 32]).unwrap();
+        assert!(read_encrypted(&dir).unwrap_err().starts_with("Failed to decrypt"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn index_round_trips_and_names_are_validated() {
+        let dir = temp_dir("secret-index");
+        let index = BTreeMap::from([("MY_KEY".to_string(), SecretBackend::EncryptedFile)]);
+        write_index(&dir, &index).unwrap();
+        assert_eq!(read_index(&dir).unwrap(), index);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(validate_name("_OK_1").is_ok());
+        assert!(validate_name("1BAD").is_err());
+        assert!(validate_name("BAD-NAME").is_err());
+    }
+}
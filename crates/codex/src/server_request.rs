@@ -1,3 +1,4 @@
+use super::approval_audit;
 use codexia_shared::event_sink::EventSink;
 use codex_app_server_protocol::ServerRequest;
 use std::sync::Arc;
@@ -7,6 +8,7 @@ pub async fn handle_server_request(event_sink: &Arc<dyn EventSink>, server_reque
     match server_request {
         ServerRequest::CommandExecutionRequestApproval { request_id, params } => {
             if let Ok(mut payload) = serde_json::to_value(params) {
+                approval_audit::track(&request_id, "commandExecution", &payload);
                 if let serde_json::Value::Object(ref mut map) = payload {
                     map.insert(
                         "requestId".to_string(),
@@ -22,6 +24,7 @@ pub async fn handle_server_request(event_sink: &Arc<dyn EventSink>, server_reque
         }
         ServerRequest::FileChangeRequestApproval { request_id, params } => {
             if let Ok(mut payload) = serde_json::to_value(params) {
+                approval_audit::track(&request_id, "fileChange", &payload);
                 if let serde_json::Value::Object(ref mut map) = payload {
                     map.insert(
                         "requestId".to_string(),
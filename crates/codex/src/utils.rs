@@ -48,6 +48,10 @@ pub fn file_mtime(path: &Path) -> Option<i64> {
     Some(duration.as_secs() as i64)
 }
 
+pub fn file_size(path: &Path) -> Option<i64> {
+    fs::metadata(path).ok().map(|metadata| metadata.len() as i64)
+}
+
 pub fn file_created_time(path: &Path) -> Option<i64> {
     let metadata = fs::metadata(path).ok()?;
     let created = metadata.created().ok()?;
@@ -65,6 +69,24 @@ pub fn codex_home() -> PathBuf {
         .unwrap_or_else(|| PathBuf::from(".codex"))
 }
 
+/// Stable key for a rollout file: the canonical parent directory joined with the
+/// file name, so removed files (archived, deleted) map to the same key they were
+/// indexed under.
+pub fn rollout_path_key(path: &Path) -> PathBuf {
+    let Some(file_name) = path.file_name() else {
+        return path.to_path_buf();
+    };
+    path.parent()
+        .and_then(|parent| fs::canonicalize(parent).ok())
+        .map(|parent| parent.join(file_name))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+pub fn archived_sessions_dir() -> PathBuf {
+    let dir = codex_home().join("archived_sessions");
+    fs::canonicalize(&dir).unwrap_or(dir)
+}
+
 pub fn codexia_history_path() -> PathBuf {
     dirs::home_dir()
         .map(|home| home.join(".codexia").join("history.jsonl"))
@@ -0,0 +1,364 @@
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+use super::get_connection;
+
+/// Longest input summary stored; longer ones are cut on a char boundary.
+const MAX_SUMMARY_CHARS: usize = 500;
+/// Attempts per queued record before it is given up on.
+const MAX_INSERT_ATTEMPTS: u32 = 10;
+/// Wait before the first retry; each further retry waits one step longer.
+const RETRY_STEP: Duration = Duration::from_millis(500);
+
+/// Who made an approval decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DecidedBy {
+    /// Answered in a prompt.
+    User,
+    /// A settings or saved allow/deny rule.
+    Rule,
+    /// Decided without a person: permission mode, read-only auto-approval or a
+    /// prompt timeout fallback.
+    Automation,
+}
+
+impl DecidedBy {
+    fn as_str(self) -> &'static str {
+        match self {
+            DecidedBy::User => "user",
+            DecidedBy::Rule => "rule",
+            DecidedBy::Automation => "automation",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "user" => DecidedBy::User,
+            "rule" => DecidedBy::Rule,
+            _ => DecidedBy::Automation,
+        }
+    }
+}
+
+/// One tool approval or denial.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRecord {
+    /// Assigned on insert; ignored when recording.
+    #[serde(default)]
+    pub id: i64,
+    /// Unix milliseconds.
+    pub recorded_at: i64,
+    /// `cc` or `codex`.
+    pub agent: String,
+    /// Claude session id or codex thread id.
+    pub session_id: String,
+    pub tool_name: String,
+    pub input_summary: String,
+    /// The agent's own decision string, e.g. `allow_project` or `acceptForSession`.
+    pub decision: String,
+    pub decided_by: DecidedBy,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalQuery {
+    pub agent: Option<String>,
+    pub session_id: Option<String>,
+    pub tool_name: Option<String>,
+    pub decision: Option<String>,
+    pub decided_by: Option<DecidedBy>,
+    /// Unix milliseconds, inclusive.
+    pub since: Option<i64>,
+    /// Unix milliseconds, exclusive.
+    pub until: Option<i64>,
+    #[serde(default)]
+    pub limit: usize,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// Append a decision to the audit log.
+pub fn record(record: &ApprovalRecord) -> Result<(), String> {
+    let conn = get_connection()?;
+    record_with(&conn, record)
+}
+
+/// Append a decision to the audit log from a background writer. Records are
+/// written in order, and an insert that fails, e.g. while another writer holds
+/// the database past the busy timeout, is retried before it is given up on.
+/// Safe to call from async code: nothing here blocks.
+pub fn enqueue(record: ApprovalRecord) {
+    static WRITER: OnceLock<Sender<ApprovalRecord>> = OnceLock::new();
+    let writer = WRITER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<ApprovalRecord>();
+        let spawned = std::thread::Builder::new()
+            .name("approval-audit".to_string())
+            .spawn(move || {
+                for record in receiver {
+                    if let Err(e) = insert_with_retry(&record, self::record, RETRY_STEP) {
+                        log::error!(
+                            "Dropped {} approval of {} in {}: {}",
+                            record.agent,
+                            record.tool_name,
+                            record.session_id,
+                            e
+                        );
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            log::error!("Failed to start the approval audit writer: {}", e);
+        }
+        sender
+    });
+    if let Err(mpsc::SendError(record)) = writer.send(record) {
+        // The writer never started; record inline rather than lose it.
+        if let Err(e) = self::record(&record) {
+            log::error!("Failed to record approval: {}", e);
+        }
+    }
+}
+
+fn insert_with_retry(
+    record: &ApprovalRecord,
+    mut insert: impl FnMut(&ApprovalRecord) -> Result<(), String>,
+    step: Duration,
+) -> Result<(), String> {
+    let mut attempt = 1;
+    loop {
+        match insert(record) {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt >= MAX_INSERT_ATTEMPTS => {
+                return Err(format!("{} (after {} attempts)", e, attempt));
+            }
+            Err(e) => {
+                log::warn!("Retrying approval audit insert: {}", e);
+                std::thread::sleep(step * attempt);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+fn record_with(conn: &Connection, record: &ApprovalRecord) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO approval_audit (
+            recorded_at, agent, session_id, tool_name, input_summary, decision, decided_by, reason
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            record.recorded_at,
+            record.agent,
+            record.session_id,
+            record.tool_name,
+            truncate(&record.input_summary),
+            record.decision,
+            record.decided_by.as_str(),
+            record.reason,
+        ],
+    )
+    .map_err(|e| format!("Failed to insert approval audit record: {}", e))?;
+    Ok(())
+}
+
+/// Matching decisions, newest first.
+pub fn query(query: &ApprovalQuery) -> Result<Vec<ApprovalRecord>, String> {
+    let conn = get_connection()?;
+    query_with(&conn, query)
+}
+
+fn query_with(conn: &Connection, query: &ApprovalQuery) -> Result<Vec<ApprovalRecord>, String> {
+    let limit = if query.limit == 0 { 200 } else { query.limit.min(10_000) };
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, recorded_at, agent, session_id, tool_name, input_summary, decision,
+                    decided_by, reason
+             FROM approval_audit
+             WHERE (?1 IS NULL OR agent = ?1)
+               AND (?2 IS NULL OR session_id = ?2)
+               AND (?3 IS NULL OR tool_name = ?3)
+               AND (?4 IS NULL OR decision = ?4)
+               AND (?5 IS NULL OR decided_by = ?5)
+               AND recorded_at >= ?6
+               AND (?7 IS NULL OR recorded_at < ?7)
+             ORDER BY recorded_at DESC, id DESC
+             LIMIT ?8 OFFSET ?9",
+        )
+        .map_err(|e| format!("Failed to prepare approval audit query: {}", e))?;
+    stmt.query_map(
+        params![
+            query.agent,
+            query.session_id,
+            query.tool_name,
+            query.decision,
+            query.decided_by.map(DecidedBy::as_str),
+            query.since.unwrap_or(0),
+            query.until,
+            limit as i64,
+            query.offset as i64,
+        ],
+        |row| {
+            let decided_by: String = row.get(7)?;
+            Ok(ApprovalRecord {
+                id: row.get(0)?,
+                recorded_at: row.get(1)?,
+                agent: row.get(2)?,
+                session_id: row.get(3)?,
+                tool_name: row.get(4)?,
+                input_summary: row.get(5)?,
+                decision: row.get(6)?,
+                decided_by: DecidedBy::parse(&decided_by),
+                reason: row.get(8)?,
+            })
+        },
+    )
+    .map_err(|e| format!("Failed to query approval audit: {}", e))?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| format!("Failed to collect approval audit: {}", e))
+}
+
+/// Matching decisions as CSV with a header row, newest first.
+pub fn export_csv(query: &ApprovalQuery) -> Result<String, String> {
+    Ok(to_csv(&self::query(query)?))
+}
+
+fn to_csv(records: &[ApprovalRecord]) -> String {
+    let mut csv = String::from(
+        "id,recorded_at,agent,session_id,tool_name,input_summary,decision,decided_by,reason\n",
+    );
+    for record in records {
+        let recorded_at = chrono::DateTime::from_timestamp_millis(record.recorded_at)
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_default();
+        let fields = [
+            record.id.to_string(),
+            recorded_at,
+            record.agent.clone(),
+            record.session_id.clone(),
+            record.tool_name.clone(),
+            record.input_summary.clone(),
+            record.decision.clone(),
+            record.decided_by.as_str().to_string(),
+            record.reason.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn truncate(summary: &str) -> String {
+    match summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((index, _)) => format!("{}…", &summary[..index]),
+        None => summary.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(recorded_at: i64, agent: &str, decision: &str, decided_by: DecidedBy) -> ApprovalRecord {
+        ApprovalRecord {
+            id: 0,
+            recorded_at,
+            agent: agent.to_string(),
+            session_id: format!("{}-session", agent),
+            tool_name: "Bash".to_string(),
+            input_summary: "echo \"a, b\"".to_string(),
+            decision: decision.to_string(),
+            decided_by,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn failed_inserts_are_retried_until_they_land_or_give_up() {
+        let conn = Connection::open_in_memory().expect("open in-memory database");
+        crate::conn::init_approval_audit_table(&conn).expect("create approval audit table");
+
+        let mut failures = 2;
+        let flaky = |record: &ApprovalRecord| {
+            if failures > 0 {
+                failures -= 1;
+                return Err("database is locked".to_string());
+            }
+            record_with(&conn, record)
+        };
+        insert_with_retry(&record(1, "cc", "allow", DecidedBy::User), flaky, Duration::ZERO).unwrap();
+        assert_eq!(query_with(&conn, &ApprovalQuery::default()).unwrap().len(), 1);
+
+        let mut attempts = 0;
+        let broken = |_: &ApprovalRecord| {
+            attempts += 1;
+            Err("disk I/O error".to_string())
+        };
+        let error = insert_with_retry(&record(2, "cc", "deny", DecidedBy::Rule), broken, Duration::ZERO)
+            .unwrap_err();
+        assert!(error.contains("disk I/O error"));
+        assert_eq!(attempts, MAX_INSERT_ATTEMPTS);
+    }
+
+    #[test]
+    fn query_filters_newest_first_and_rejects_updates() {
+        let conn = Connection::open_in_memory().expect("open in-memory database");
+        crate::conn::init_approval_audit_table(&conn).expect("create approval audit table");
+
+        record_with(&conn, &record(1, "cc", "allow", DecidedBy::User)).unwrap();
+        record_with(&conn, &record(2, "codex", "decline", DecidedBy::User)).unwrap();
+        record_with(&conn, &record(3, "cc", "deny", DecidedBy::Rule)).unwrap();
+
+        let all = query_with(&conn, &ApprovalQuery::default()).unwrap();
+        assert_eq!(all.iter().map(|r| r.recorded_at).collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        let cc_rules = query_with(
+            &conn,
+            &ApprovalQuery {
+                agent: Some("cc".to_string()),
+                decided_by: Some(DecidedBy::Rule),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(cc_rules.len(), 1);
+        assert_eq!(cc_rules[0].decision, "deny");
+
+        let windowed = query_with(
+            &conn,
+            &ApprovalQuery { since: Some(2), until: Some(3), ..Default::default() },
+        )
+        .unwrap();
+        assert_eq!(windowed.len(), 1);
+        assert_eq!(windowed[0].agent, "codex");
+
+        assert!(conn.execute("UPDATE approval_audit SET decision = 'allow'", []).is_err());
+        assert!(conn.execute("DELETE FROM approval_audit", []).is_err());
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_separators() {
+        let csv = to_csv(&[record(0, "cc", "allow", DecidedBy::User)]);
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.contains(",\"echo \"\"a, b\"\"\",allow,user,"));
+    }
+
+    #[test]
+    fn long_summaries_are_truncated() {
+        let summary = "é".repeat(MAX_SUMMARY_CHARS + 10);
+        assert_eq!(truncate(&summary).chars().count(), MAX_SUMMARY_CHARS + 1);
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+
+use super::get_connection;
+
+/// One indexed codex rollout file. Files that do not (yet) have a preview are stored
+/// with an empty one so they are not re-read until their mtime or size changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodexHistoryRow {
+    pub path: String,
+    pub thread_id: String,
+    pub preview: String,
+    pub cwd: String,
+    pub source: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub archived: bool,
+    pub file_mtime: i64,
+    pub file_size: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CodexHistoryQuery {
+    pub archived: bool,
+    /// Restrict to these working directories. `Some(vec![])` matches nothing.
+    pub cwds: Option<Vec<String>>,
+    pub source_kinds: Option<Vec<String>>,
+    /// Sort by `updated_at` instead of `created_at`.
+    pub sort_by_updated: bool,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CodexHistoryPage {
+    pub rows: Vec<CodexHistoryRow>,
+    pub has_more: bool,
+}
+
+/// `(file_mtime, file_size)` of every indexed file in the archived or live set, keyed by path.
+pub fn file_states(archived: bool) -> Result<HashMap<String, (i64, i64)>, String> {
+    let conn = get_connection()?;
+    let mut stmt = conn
+        .prepare("SELECT path, file_mtime, file_size FROM codex_history WHERE archived = ?1")
+        .map_err(|e| format!("Failed to prepare codex history state query: {}", e))?;
+    stmt
+        .query_map(params![archived], |row| {
+            Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+        })
+        .map_err(|e| format!("Failed to query codex history state: {}", e))?
+        .collect::<Result<HashMap<_, _>, _>>()
+        .map_err(|e| format!("Failed to collect codex history state: {}", e))
+}
+
+/// Insert or replace rows and delete `removed` paths in a single transaction.
+pub fn apply_changes(rows: &[CodexHistoryRow], removed: &[String]) -> Result<(), String> {
+    if rows.is_empty() && removed.is_empty() {
+        return Ok(());
+    }
+    let mut conn = get_connection()?;
+    apply_changes_with(&mut conn, rows, removed)
+}
+
+pub fn distinct_cwds(archived: bool) -> Result<Vec<String>, String> {
+    let conn = get_connection()?;
+    let mut stmt = conn
+        .prepare("SELECT DISTINCT cwd FROM codex_history WHERE archived = ?1 AND preview != ''")
+        .map_err(|e| format!("Failed to prepare codex history cwd query: {}", e))?;
+    stmt
+        .query_map(params![archived], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Failed to query codex history cwds: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect codex history cwds: {}", e))
+}
+
+/// Page through rows with a non-empty preview, newest first.
+pub fn list(query: &CodexHistoryQuery) -> Result<CodexHistoryPage, String> {
+    let conn = get_connection()?;
+    list_with(&conn, query)
+}
+
+fn apply_changes_with(
+    conn: &mut Connection,
+    rows: &[CodexHistoryRow],
+    removed: &[String],
+) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start codex history transaction: {}", e))?;
+    {
+        let mut delete = tx
+            .prepare("DELETE FROM codex_history WHERE path = ?1")
+            .map_err(|e| format!("Failed to prepare codex history delete: {}", e))?;
+        for path in removed {
+            delete
+                .execute(params![path])
+                .map_err(|e| format!("Failed to delete codex history row: {}", e))?;
+        }
+
+        let mut upsert = tx
+            .prepare(
+                "INSERT INTO codex_history (
+                    path, thread_id, preview, cwd, source, created_at, updated_at, archived,
+                    file_mtime, file_size
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                ON CONFLICT(path) DO UPDATE SET
+                    thread_id = excluded.thread_id,
+                    preview = excluded.preview,
+                    cwd = excluded.cwd,
+                    source = excluded.source,
+                    created_at = excluded.created_at,
+                    updated_at = excluded.updated_at,
+                    archived = excluded.archived,
+                    file_mtime = excluded.file_mtime,
+                    file_size = excluded.file_size",
+            )
+            .map_err(|e| format!("Failed to prepare codex history upsert: {}", e))?;
+        for row in rows {
+            upsert
+                .execute(params![
+                    row.path,
+                    row.thread_id,
+                    row.preview,
+                    row.cwd,
+                    row.source,
+                    row.created_at,
+                    row.updated_at,
+                    row.archived,
+                    row.file_mtime,
+                    row.file_size,
+                ])
+                .map_err(|e| format!("Failed to upsert codex history row: {}", e))?;
+        }
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit codex history transaction: {}", e))
+}
+
+fn list_with(conn: &Connection, query: &CodexHistoryQuery) -> Result<CodexHistoryPage, String> {
+    let mut filters = vec!["archived = ?".to_string(), "preview != ''".to_string()];
+    let mut params_list: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(query.archived)];
+
+    if let Some(cwds) = &query.cwds {
+        if cwds.is_empty() {
+            return Ok(CodexHistoryPage {
+                rows: Vec::new(),
+                has_more: false,
+            });
+        }
+        filters.push(format!("cwd IN ({})", vec!["?"; cwds.len()].join(", ")));
+        for cwd in cwds {
+            params_list.push(Box::new(cwd.clone()));
+        }
+    }
+    if let Some(kinds) = query.source_kinds.as_ref().filter(|kinds| !kinds.is_empty()) {
+        filters.push(format!("source IN ({})", vec!["?"; kinds.len()].join(", ")));
+        for kind in kinds {
+            params_list.push(Box::new(kind.clone()));
+        }
+    }
+
+    // Fetch one extra row to know whether another page exists.
+    params_list.push(Box::new(query.limit as i64 + 1));
+    params_list.push(Box::new(query.offset as i64));
+
+    let order_column = if query.sort_by_updated {
+        "updated_at"
+    } else {
+        "created_at"
+    };
+    let sql = format!(
+        "SELECT path, thread_id, preview, cwd, source, created_at, updated_at, archived,
+                file_mtime, file_size
+         FROM codex_history
+         WHERE {}
+         ORDER BY {} DESC, path ASC
+         LIMIT ? OFFSET ?",
+        filters.join(" AND "),
+        order_column
+    );
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Failed to prepare codex history query: {}", e))?;
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params_list.iter().map(|b| b.as_ref()).collect();
+    let mut rows = stmt
+        .query_map(params_refs.as_slice(), |row| {
+            Ok(CodexHistoryRow {
+                path: row.get(0)?,
+                thread_id: row.get(1)?,
+                preview: row.get(2)?,
+                cwd: row.get(3)?,
+                source: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+                archived: row.get(7)?,
+                file_mtime: row.get(8)?,
+                file_size: row.get(9)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query codex history: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read codex history rows: {}", e))?;
+
+    let has_more = rows.len() > query.limit;
+    rows.truncate(query.limit);
+    Ok(CodexHistoryPage { rows, has_more })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(path: &str, cwd: &str, created_at: i64, preview: &str) -> CodexHistoryRow {
+        CodexHistoryRow {
+            path: path.to_string(),
+            thread_id: format!("thread-{}", path),
+            preview: preview.to_string(),
+            cwd: cwd.to_string(),
+            source: "cli".to_string(),
+            created_at,
+            updated_at: created_at,
+            archived: false,
+            file_mtime: created_at,
+            file_size: 1,
+        }
+    }
+
+    #[test]
+    fn list_paginates_and_filters_in_sql() {
+        let mut conn = Connection::open_in_memory().expect("open in-memory database");
+        crate::conn::init_codex_history_table(&conn).expect("create codex history table");
+
+        let rows: Vec<CodexHistoryRow> = (0..5)
+            .map(|i| row(&format!("/s/{}.jsonl", i), "/repo", i, "hello"))
+            .chain([row("/s/empty.jsonl", "/repo", 99, ""), row("/s/other.jsonl", "/other", 50, "hi")])
+            .collect();
+        apply_changes_with(&mut conn, &rows, &[]).unwrap();
+
+        let query = CodexHistoryQuery {
+            cwds: Some(vec!["/repo".to_string()]),
+            limit: 2,
+            ..Default::default()
+        };
+        let first = list_with(&conn, &query).unwrap();
+        assert!(first.has_more);
+        assert_eq!(
+            first.rows.iter().map(|r| r.created_at).collect::<Vec<_>>(),
+            vec![4, 3]
+        );
+
+        let last = list_with(&conn, &CodexHistoryQuery { offset: 4, ..query.clone() }).unwrap();
+        assert!(!last.has_more);
+        assert_eq!(last.rows.len(), 1);
+
+        apply_changes_with(&mut conn, &[], &["/s/4.jsonl".to_string()]).unwrap();
+        let all = list_with(&conn, &CodexHistoryQuery { limit: 10, ..Default::default() }).unwrap();
+        assert_eq!(all.rows.len(), 5);
+        assert_eq!(all.rows[0].path, "/s/other.jsonl");
+    }
+}
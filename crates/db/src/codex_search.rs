@@ -0,0 +1,343 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+
+use super::get_connection;
+
+/// A single searchable piece of a rollout: a message, a shell command or a file path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodexSearchSegment {
+    pub kind: String,
+    pub text: String,
+}
+
+/// Everything the index stores for one rollout file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodexSearchDocument {
+    pub path: String,
+    pub thread_id: String,
+    pub cwd: String,
+    pub preview: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub archived: bool,
+    pub file_mtime: i64,
+    pub file_size: i64,
+    pub segments: Vec<CodexSearchSegment>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CodexSearchQuery {
+    pub query: String,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Lower bound (unix seconds) on the rollout creation time.
+    #[serde(default)]
+    pub from: Option<i64>,
+    /// Upper bound (unix seconds) on the rollout creation time.
+    #[serde(default)]
+    pub to: Option<i64>,
+    #[serde(default)]
+    pub archived: Option<bool>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodexSearchHit {
+    pub path: String,
+    pub thread_id: String,
+    pub cwd: String,
+    pub preview: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub archived: bool,
+    pub kind: String,
+    pub snippet: String,
+}
+
+/// Returns the `(file_mtime, file_size)` recorded when `path` was last indexed.
+pub fn indexed_file_state(path: &str) -> Result<Option<(i64, i64)>, String> {
+    let conn = get_connection()?;
+    conn.query_row(
+        "SELECT file_mtime, file_size FROM codex_search_files WHERE path = ?1",
+        params![path],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| format!("Failed to read codex search file state: {}", e))
+}
+
+pub fn indexed_paths() -> Result<Vec<String>, String> {
+    let conn = get_connection()?;
+    let mut stmt = conn
+        .prepare("SELECT path FROM codex_search_files")
+        .map_err(|e| format!("Failed to prepare codex search path query: {}", e))?;
+    stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Failed to query codex search paths: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect codex search paths: {}", e))
+}
+
+/// Replace the indexed contents of one rollout file.
+pub fn upsert_document(doc: &CodexSearchDocument) -> Result<(), String> {
+    let mut conn = get_connection()?;
+    upsert_document_with(&mut conn, doc)
+}
+
+pub fn remove_document(path: &str) -> Result<(), String> {
+    let mut conn = get_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start codex search transaction: {}", e))?;
+    tx.execute("DELETE FROM codex_search_fts WHERE path = ?1", params![path])
+        .map_err(|e| format!("Failed to delete codex search rows: {}", e))?;
+    tx.execute("DELETE FROM codex_search_files WHERE path = ?1", params![path])
+        .map_err(|e| format!("Failed to delete codex search file: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit codex search transaction: {}", e))
+}
+
+/// Full-text search over indexed rollouts. Returns the best matching segment per rollout.
+pub fn search(query: &CodexSearchQuery) -> Result<Vec<CodexSearchHit>, String> {
+    let conn = get_connection()?;
+    search_with(&conn, query)
+}
+
+fn upsert_document_with(conn: &mut Connection, doc: &CodexSearchDocument) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start codex search transaction: {}", e))?;
+
+    tx.execute("DELETE FROM codex_search_fts WHERE path = ?1", params![doc.path])
+        .map_err(|e| format!("Failed to clear codex search rows: {}", e))?;
+
+    tx.execute(
+        "INSERT INTO codex_search_files (
+            path, thread_id, cwd, preview, created_at, updated_at, archived, file_mtime, file_size
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        ON CONFLICT(path) DO UPDATE SET
+            thread_id = excluded.thread_id,
+            cwd = excluded.cwd,
+            preview = excluded.preview,
+            created_at = excluded.created_at,
+            updated_at = excluded.updated_at,
+            archived = excluded.archived,
+            file_mtime = excluded.file_mtime,
+            file_size = excluded.file_size",
+        params![
+            doc.path,
+            doc.thread_id,
+            doc.cwd,
+            doc.preview,
+            doc.created_at,
+            doc.updated_at,
+            doc.archived,
+            doc.file_mtime,
+            doc.file_size,
+        ],
+    )
+    .map_err(|e| format!("Failed to upsert codex search file: {}", e))?;
+
+    {
+        let mut stmt = tx
+            .prepare("INSERT INTO codex_search_fts (path, kind, body) VALUES (?1, ?2, ?3)")
+            .map_err(|e| format!("Failed to prepare codex search insert: {}", e))?;
+        for segment in &doc.segments {
+            if segment.text.trim().is_empty() {
+                continue;
+            }
+            stmt.execute(params![doc.path, segment.kind, segment.text])
+                .map_err(|e| format!("Failed to insert codex search row: {}", e))?;
+        }
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit codex search transaction: {}", e))
+}
+
+fn search_with(conn: &Connection, query: &CodexSearchQuery) -> Result<Vec<CodexSearchHit>, String> {
+    let Some(match_expr) = fts_match_expression(&query.query) else {
+        return Ok(Vec::new());
+    };
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+
+    let mut filters = vec!["codex_search_fts MATCH ?".to_string()];
+    let mut params_list: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(match_expr)];
+
+    if let Some(cwd) = query.cwd.as_deref().map(str::trim).filter(|cwd| !cwd.is_empty()) {
+        filters.push("d.cwd = ?".to_string());
+        params_list.push(Box::new(cwd.to_string()));
+    }
+    if let Some(from) = query.from {
+        filters.push("d.created_at >= ?".to_string());
+        params_list.push(Box::new(from));
+    }
+    if let Some(to) = query.to {
+        filters.push("d.created_at <= ?".to_string());
+        params_list.push(Box::new(to));
+    }
+    if let Some(archived) = query.archived {
+        filters.push("d.archived = ?".to_string());
+        params_list.push(Box::new(archived));
+    }
+    params_list.push(Box::new(limit as i64));
+
+    // The CTE is materialized so the FTS5 auxiliary functions run against the MATCH
+    // query itself; SQLite then returns the bare columns of the row holding MIN(rank)
+    // within each group, which gives us the best matching segment per rollout.
+    let sql = format!(
+        "WITH matches AS MATERIALIZED (
+            SELECT f.path AS path, d.thread_id AS thread_id, d.cwd AS cwd, d.preview AS preview,
+                   d.created_at AS created_at, d.updated_at AS updated_at, d.archived AS archived,
+                   f.kind AS kind,
+                   snippet(codex_search_fts, 2, '[', ']', '…', 16) AS snippet,
+                   bm25(codex_search_fts) AS rank
+            FROM codex_search_fts f
+            JOIN codex_search_files d ON d.path = f.path
+            WHERE {}
+         )
+         SELECT path, thread_id, cwd, preview, created_at, updated_at, archived, kind, snippet, MIN(rank)
+         FROM matches
+         GROUP BY path
+         ORDER BY MIN(rank) ASC, created_at DESC
+         LIMIT ?",
+        filters.join(" AND ")
+    );
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Failed to prepare codex search query: {}", e))?;
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params_list.iter().map(|b| b.as_ref()).collect();
+    stmt
+        .query_map(params_refs.as_slice(), |row| {
+            Ok(CodexSearchHit {
+                path: row.get(0)?,
+                thread_id: row.get(1)?,
+                cwd: row.get(2)?,
+                preview: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                created_at: row.get(4)?,
+                updated_at: row.get(5)?,
+                archived: row.get(6)?,
+                kind: row.get(7)?,
+                snippet: row.get(8)?,
+            })
+        })
+        .map_err(|e| format!("Failed to run codex search query: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read codex search rows: {}", e))
+}
+
+/// Turn free-form user input into an FTS5 expression: every whitespace separated
+/// term is quoted (so punctuation like `-` or `:` is not parsed as syntax) and
+/// prefix-matched, and all terms must match.
+pub fn fts_match_expression(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(path: &str, cwd: &str, created_at: i64, segments: &[(&str, &str)]) -> CodexSearchDocument {
+        CodexSearchDocument {
+            path: path.to_string(),
+            thread_id: format!("thread-{}", path),
+            cwd: cwd.to_string(),
+            preview: "preview".to_string(),
+            created_at,
+            updated_at: created_at,
+            archived: false,
+            file_mtime: created_at,
+            file_size: 1,
+            segments: segments
+                .iter()
+                .map(|(kind, text)| CodexSearchSegment {
+                    kind: kind.to_string(),
+                    text: text.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn memory_connection() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory database");
+        crate::conn::init_codex_search_tables(&conn).expect("create codex search tables");
+        conn
+    }
+
+    #[test]
+    fn fts_match_expression_quotes_terms() {
+        assert_eq!(
+            fts_match_expression("auth bug"),
+            Some("\"auth\"* \"bug\"*".to_string())
+        );
+        assert_eq!(
+            fts_match_expression("say \"hi\""),
+            Some("\"say\"* \"\"\"hi\"\"\"*".to_string())
+        );
+        assert_eq!(fts_match_expression("   "), None);
+    }
+
+    #[test]
+    fn search_filters_by_cwd_and_replaces_on_reindex() {
+        let mut conn = memory_connection();
+        upsert_document_with(
+            &mut conn,
+            &document("/a.jsonl", "/repo/a", 10, &[("user", "fix the auth bug in login.rs")]),
+        )
+        .unwrap();
+        upsert_document_with(
+            &mut conn,
+            &document("/b.jsonl", "/repo/b", 20, &[("command", "cargo test auth")]),
+        )
+        .unwrap();
+
+        let all = search_with(
+            &conn,
+            &CodexSearchQuery {
+                query: "auth".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(all.len(), 2);
+
+        let only_a = search_with(
+            &conn,
+            &CodexSearchQuery {
+                query: "auth".to_string(),
+                cwd: Some("/repo/a".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(only_a.len(), 1);
+        assert_eq!(only_a[0].kind, "user");
+        assert!(only_a[0].snippet.contains("[auth]"));
+
+        upsert_document_with(
+            &mut conn,
+            &document("/a.jsonl", "/repo/a", 10, &[("user", "rename the config loader")]),
+        )
+        .unwrap();
+        let after = search_with(
+            &conn,
+            &CodexSearchQuery {
+                query: "auth".to_string(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].path, "/b.jsonl");
+    }
+}
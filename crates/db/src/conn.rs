@@ -1,5 +1,9 @@
 use rusqlite::Connection;
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long a statement waits for another connection's write lock.
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Get the path to the SQLite database
 fn get_db_path() -> Result<PathBuf, String> {
@@ -14,6 +18,10 @@ fn get_db_path() -> Result<PathBuf, String> {
 pub(crate) fn get_connection() -> Result<Connection, String> {
     let db_path = get_db_path()?;
     let conn = Connection::open(db_path).map_err(|e| format!("Failed to open database: {}", e))?;
+    // cache.db has several writers (history and search indexers, the Claude
+    // session cache, audit inserts); wait for a lock instead of failing.
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| format!("Failed to set database busy timeout: {}", e))?;
 
     init_tables(&conn)?;
     Ok(conn)
@@ -23,6 +31,10 @@ pub(crate) fn get_connection() -> Result<Connection, String> {
 fn init_tables(conn: &Connection) -> Result<(), String> {
     init_notes_table(conn)?;
     init_automation_runs_tables(conn)?;
+    init_codex_search_tables(conn)?;
+    init_codex_history_table(conn)?;
+    init_codex_rate_limits_table(conn)?;
+    init_approval_audit_table(conn)?;
     Ok(())
 }
 
@@ -116,3 +128,169 @@ fn init_automation_runs_tables(conn: &Connection) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Create the codex rollout search tables: one row per indexed rollout file and
+/// an FTS5 table holding its searchable segments.
+pub(crate) fn init_codex_search_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS codex_search_files (
+            path TEXT PRIMARY KEY,
+            thread_id TEXT NOT NULL,
+            cwd TEXT NOT NULL,
+            preview TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            archived BOOLEAN NOT NULL DEFAULT 0,
+            file_mtime INTEGER NOT NULL,
+            file_size INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_search_files table: {}", e))?;
+
+    conn.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS codex_search_fts USING fts5(
+            path UNINDEXED,
+            kind UNINDEXED,
+            body,
+            tokenize = 'unicode61'
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_search_fts table: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_codex_search_files_cwd_created
+         ON codex_search_files(cwd, created_at DESC)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_search_files cwd index: {}", e))?;
+
+    Ok(())
+}
+
+/// Create the codex history index: one row per rollout file, keyed by path and
+/// stamped with the file's mtime and size so unchanged files are never re-read.
+pub(crate) fn init_codex_history_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS codex_history (
+            path TEXT PRIMARY KEY,
+            thread_id TEXT NOT NULL,
+            preview TEXT NOT NULL,
+            cwd TEXT NOT NULL,
+            source TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            archived BOOLEAN NOT NULL DEFAULT 0,
+            file_mtime INTEGER NOT NULL,
+            file_size INTEGER NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_history table: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_codex_history_archived_created
+         ON codex_history(archived, created_at DESC)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_history created index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_codex_history_archived_updated
+         ON codex_history(archived, updated_at DESC)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_history updated index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_codex_history_cwd
+         ON codex_history(cwd)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_history cwd index: {}", e))?;
+
+    Ok(())
+}
+
+/// Create the codex rate-limit history: one row per window per recorded snapshot.
+pub(crate) fn init_codex_rate_limits_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS codex_rate_limits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            recorded_at INTEGER NOT NULL,
+            limit_id TEXT NOT NULL,
+            window_kind TEXT NOT NULL,
+            used_percent REAL NOT NULL,
+            window_duration_mins INTEGER,
+            resets_at INTEGER,
+            plan_type TEXT
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_rate_limits table: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_codex_rate_limits_window_recorded
+         ON codex_rate_limits(limit_id, window_kind, recorded_at DESC)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_rate_limits window index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_codex_rate_limits_recorded
+         ON codex_rate_limits(recorded_at)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create codex_rate_limits recorded index: {}", e))?;
+
+    Ok(())
+}
+
+/// Create the append-only approval audit log. Triggers reject any UPDATE or
+/// DELETE so recorded decisions cannot be rewritten.
+pub(crate) fn init_approval_audit_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS approval_audit (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            recorded_at INTEGER NOT NULL,
+            agent TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            tool_name TEXT NOT NULL,
+            input_summary TEXT NOT NULL,
+            decision TEXT NOT NULL,
+            decided_by TEXT NOT NULL,
+            reason TEXT
+        )",
+        [],
+    )
+    .map_err(|e| format!("Failed to create approval_audit table: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_approval_audit_recorded
+         ON approval_audit(recorded_at DESC)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create approval_audit recorded index: {}", e))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_approval_audit_session
+         ON approval_audit(session_id, recorded_at DESC)",
+        [],
+    )
+    .map_err(|e| format!("Failed to create approval_audit session index: {}", e))?;
+
+    for (name, action) in [("no_update", "UPDATE"), ("no_delete", "DELETE")] {
+        conn.execute(
+            &format!(
+                "CREATE TRIGGER IF NOT EXISTS approval_audit_{name}
+                 BEFORE {action} ON approval_audit
+                 BEGIN SELECT RAISE(ABORT, 'approval_audit is append-only'); END"
+            ),
+            [],
+        )
+        .map_err(|e| format!("Failed to create approval_audit {} trigger: {}", action, e))?;
+    }
+
+    Ok(())
+}
@@ -1,6 +1,11 @@
 mod conn;
+pub mod approval_audit;
 pub mod automation_runs;
+pub mod codex_history;
+pub mod codex_search;
 pub mod notes;
+pub mod rate_limits;
 
 pub(crate) use conn::get_connection;
+pub use conn::BUSY_TIMEOUT;
 pub use notes::*;
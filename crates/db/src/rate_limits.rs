@@ -0,0 +1,237 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+
+use super::get_connection;
+
+/// Samples older than this are pruned when new ones are recorded.
+const RETENTION_MS: i64 = 90 * 24 * 60 * 60 * 1000;
+
+/// Usage of one codex rate-limit window at a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitSample {
+    /// Unix milliseconds.
+    pub recorded_at: i64,
+    /// Metered limit id such as `codex`; empty for the single-bucket view.
+    pub limit_id: String,
+    /// `primary` or `secondary`.
+    pub window: String,
+    pub used_percent: f64,
+    pub window_duration_mins: Option<i64>,
+    /// Unix seconds.
+    pub resets_at: Option<i64>,
+    pub plan_type: Option<String>,
+}
+
+/// Store samples, skipping any whose usage and reset time match the latest
+/// sample of the same window. Returns the samples actually inserted.
+pub fn record_samples(samples: &[RateLimitSample]) -> Result<Vec<RateLimitSample>, String> {
+    if samples.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut conn = get_connection()?;
+    record_samples_with(&mut conn, samples)
+}
+
+fn record_samples_with(
+    conn: &mut Connection,
+    samples: &[RateLimitSample],
+) -> Result<Vec<RateLimitSample>, String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to begin rate limit transaction: {}", e))?;
+    let mut inserted = Vec::new();
+    for sample in samples {
+        let latest: Option<(f64, Option<i64>)> = tx
+            .query_row(
+                "SELECT used_percent, resets_at FROM codex_rate_limits
+                 WHERE limit_id = ?1 AND window_kind = ?2
+                 ORDER BY recorded_at DESC LIMIT 1",
+                params![sample.limit_id, sample.window],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to query latest rate limit: {}", e))?;
+        if latest == Some((sample.used_percent, sample.resets_at)) {
+            continue;
+        }
+        tx.execute(
+            "INSERT INTO codex_rate_limits (
+                recorded_at, limit_id, window_kind, used_percent, window_duration_mins, resets_at, plan_type
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                sample.recorded_at,
+                sample.limit_id,
+                sample.window,
+                sample.used_percent,
+                sample.window_duration_mins,
+                sample.resets_at,
+                sample.plan_type,
+            ],
+        )
+        .map_err(|e| format!("Failed to insert rate limit sample: {}", e))?;
+        inserted.push(sample.clone());
+    }
+    if let Some(newest) = samples.iter().map(|sample| sample.recorded_at).max() {
+        tx.execute(
+            "DELETE FROM codex_rate_limits WHERE recorded_at < ?1",
+            params![newest - RETENTION_MS],
+        )
+        .map_err(|e| format!("Failed to prune rate limit samples: {}", e))?;
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit rate limit samples: {}", e))?;
+    Ok(inserted)
+}
+
+/// Samples recorded at or after `since` (Unix milliseconds), oldest first, for charting.
+pub fn list_samples(
+    limit_id: Option<&str>,
+    since: Option<i64>,
+    limit: usize,
+) -> Result<Vec<RateLimitSample>, String> {
+    let conn = get_connection()?;
+    list_samples_with(&conn, limit_id, since, limit)
+}
+
+fn list_samples_with(
+    conn: &Connection,
+    limit_id: Option<&str>,
+    since: Option<i64>,
+    limit: usize,
+) -> Result<Vec<RateLimitSample>, String> {
+    let limit = if limit == 0 { 1000 } else { limit.min(10_000) };
+    // Take the newest `limit` samples, then return them in chronological order.
+    let mut stmt = conn
+        .prepare(
+            "SELECT * FROM (
+                SELECT recorded_at, limit_id, window_kind, used_percent, window_duration_mins,
+                       resets_at, plan_type
+                FROM codex_rate_limits
+                WHERE (?1 IS NULL OR limit_id = ?1) AND recorded_at >= ?2
+                ORDER BY recorded_at DESC
+                LIMIT ?3
+             ) ORDER BY recorded_at ASC",
+        )
+        .map_err(|e| format!("Failed to prepare rate limit query: {}", e))?;
+    stmt.query_map(
+        params![limit_id, since.unwrap_or(0), limit as i64],
+        map_sample,
+    )
+    .map_err(|e| format!("Failed to query rate limits: {}", e))?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| format!("Failed to collect rate limits: {}", e))
+}
+
+/// The most recent sample of every window.
+pub fn latest_samples() -> Result<Vec<RateLimitSample>, String> {
+    let conn = get_connection()?;
+    latest_samples_with(&conn)
+}
+
+fn latest_samples_with(conn: &Connection) -> Result<Vec<RateLimitSample>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT recorded_at, limit_id, window_kind, used_percent, window_duration_mins,
+                    resets_at, plan_type
+             FROM codex_rate_limits AS sample
+             WHERE recorded_at = (
+                SELECT MAX(recorded_at) FROM codex_rate_limits
+                WHERE limit_id = sample.limit_id AND window_kind = sample.window_kind
+             )
+             ORDER BY limit_id, window_kind",
+        )
+        .map_err(|e| format!("Failed to prepare latest rate limit query: {}", e))?;
+    stmt.query_map([], map_sample)
+        .map_err(|e| format!("Failed to query latest rate limits: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect latest rate limits: {}", e))
+}
+
+fn map_sample(row: &rusqlite::Row<'_>) -> rusqlite::Result<RateLimitSample> {
+    Ok(RateLimitSample {
+        recorded_at: row.get(0)?,
+        limit_id: row.get(1)?,
+        window: row.get(2)?,
+        used_percent: row.get(3)?,
+        window_duration_mins: row.get(4)?,
+        resets_at: row.get(5)?,
+        plan_type: row.get(6)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(recorded_at: i64, window: &str, used_percent: f64) -> RateLimitSample {
+        RateLimitSample {
+            recorded_at,
+            limit_id: "codex".to_string(),
+            window: window.to_string(),
+            used_percent,
+            window_duration_mins: Some(300),
+            resets_at: Some(1_000),
+            plan_type: None,
+        }
+    }
+
+    fn open() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory database");
+        crate::conn::init_codex_rate_limits_table(&conn).expect("create rate limit table");
+        conn
+    }
+
+    #[test]
+    fn unchanged_samples_are_not_recorded_twice() {
+        let mut conn = open();
+        let first = [sample(1, "primary", 10.0), sample(1, "secondary", 50.0)];
+        assert_eq!(record_samples_with(&mut conn, &first).unwrap().len(), 2);
+
+        let next = [sample(2, "primary", 10.0), sample(2, "secondary", 55.0)];
+        let inserted = record_samples_with(&mut conn, &next).unwrap();
+        assert_eq!(inserted.len(), 1);
+        assert_eq!(inserted[0].window, "secondary");
+
+        let mut reset = sample(3, "primary", 10.0);
+        reset.resets_at = Some(2_000);
+        assert_eq!(record_samples_with(&mut conn, &[reset]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn old_samples_are_pruned() {
+        let mut conn = open();
+        record_samples_with(&mut conn, &[sample(1, "primary", 10.0)]).unwrap();
+        let later = RETENTION_MS + 10;
+        record_samples_with(&mut conn, &[sample(later, "secondary", 20.0)]).unwrap();
+
+        let samples = list_samples_with(&conn, None, None, 0).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].recorded_at, later);
+    }
+
+    #[test]
+    fn list_returns_the_newest_samples_oldest_first() {
+        let mut conn = open();
+        for (at, used) in [(1, 10.0), (2, 20.0), (3, 30.0), (4, 40.0)] {
+            record_samples_with(&mut conn, &[sample(at, "primary", used)]).unwrap();
+        }
+        let mut other = sample(5, "primary", 1.0);
+        other.limit_id = "other".to_string();
+        record_samples_with(&mut conn, &[other]).unwrap();
+
+        let recorded: Vec<i64> = list_samples_with(&conn, Some("codex"), Some(2), 2)
+            .unwrap()
+            .iter()
+            .map(|sample| sample.recorded_at)
+            .collect();
+        assert_eq!(recorded, [3, 4]);
+        assert_eq!(list_samples_with(&conn, None, None, 0).unwrap().len(), 5);
+
+        let latest = latest_samples_with(&conn).unwrap();
+        let latest: Vec<(&str, f64)> = latest
+            .iter()
+            .map(|sample| (sample.limit_id.as_str(), sample.used_percent))
+            .collect();
+        assert_eq!(latest, [("codex", 40.0), ("other", 1.0)]);
+    }
+}
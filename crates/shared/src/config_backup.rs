@@ -0,0 +1,399 @@
+//! Timestamped backup history for agent config files Codexia rewrites
+//! (`~/.codex/config.toml`, `~/.claude.json`, `.claude/settings.json`, `.mcp.json`).
+//!
+//! Every write goes through [`write_with_backup`], which snapshots the previous
+//! content into `~/.codexia/backups/<file key>/` and keeps the newest
+//! [`MAX_BACKUPS_PER_FILE`] snapshots per file. Snapshots hold API keys and
+//! tokens, so the backup directories are private to the user (0700, files 0600).
+
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const MAX_BACKUPS_PER_FILE: usize = 20;
+
+/// Records the original path of the files backed up in a directory.
+const SOURCE_FILE: &str = "source.txt";
+const BACKUP_EXTENSION: &str = "bak";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBackup {
+    /// `<file key>/<snapshot file>`, stable across restarts.
+    pub id: String,
+    /// The config file this is a snapshot of.
+    pub path: String,
+    /// Unix milliseconds.
+    pub created_at: i64,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBackupDiff {
+    pub path: String,
+    /// Content of the backup.
+    pub old_content: String,
+    /// Current content of the file, empty if it no longer exists.
+    pub new_content: String,
+    pub has_changes: bool,
+}
+
+fn backups_root() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home.join(".codexia").join("backups"))
+}
+
+/// Write `content` to `path`, first snapshotting the existing file. The new
+/// content goes to a temp file renamed over `path`, so a crash or a full disk
+/// never leaves a truncated config.
+pub fn write_with_backup(path: &Path, content: &str) -> Result<Option<ConfigBackup>, String> {
+    write_with_backup_in(&backups_root()?, path, content)
+}
+
+/// Snapshot `path` without modifying it. Returns `None` if the file does not exist.
+pub fn backup_file(path: &Path) -> Result<Option<ConfigBackup>, String> {
+    backup_file_in(&backups_root()?, path)
+}
+
+/// Backups of `path`, or of every tracked file when `None`, newest first.
+pub fn list_backups(path: Option<&Path>) -> Result<Vec<ConfigBackup>, String> {
+    list_backups_in(&backups_root()?, path)
+}
+
+/// Compare a backup with the current content of the file it was taken from.
+pub fn diff_backup(id: &str) -> Result<ConfigBackupDiff, String> {
+    diff_backup_in(&backups_root()?, id)
+}
+
+/// Put a backup's content back in place. The content being replaced is itself
+/// backed up first, so a restore can be undone.
+pub fn restore_backup(id: &str) -> Result<ConfigBackup, String> {
+    restore_backup_in(&backups_root()?, id)
+}
+
+fn write_with_backup_in(
+    root: &Path,
+    path: &Path,
+    content: &str,
+) -> Result<Option<ConfigBackup>, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    if fs::read(path).ok().as_deref() == Some(content.as_bytes()) {
+        return Ok(None);
+    }
+    let backup = backup_file_in(root, path)?;
+    // Replace the link target, so a symlinked config stays a symlink.
+    write_private(&resolve_path(path), content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(backup)
+}
+
+fn backup_file_in(root: &Path, path: &Path) -> Result<Option<ConfigBackup>, String> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let path = resolve_path(path);
+    let content =
+        fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let key = file_key(&path);
+    let dir = root.join(&key);
+    create_private_dir(root)?;
+    create_private_dir(&dir)?;
+    write_private(&dir.join(SOURCE_FILE), path.to_string_lossy().as_bytes())
+        .map_err(|e| format!("Failed to record backup source: {}", e))?;
+
+    let existing = list_dir(root, &key)?;
+    // Repeated writes that do not change the file do not need another snapshot.
+    if let Some(latest) = existing.first()
+        && fs::read(dir.join(snapshot_name(&latest.id)))
+            .ok()
+            .as_deref()
+            == Some(content.as_slice())
+    {
+        return Ok(Some(latest.clone()));
+    }
+
+    let mut created_at = chrono::Utc::now().timestamp_millis();
+    // Keep names unique and ordered when several writes land in the same millisecond.
+    if let Some(latest) = existing.first() {
+        created_at = created_at.max(latest.created_at + 1);
+    }
+    let name = format!("{}.{}", created_at, BACKUP_EXTENSION);
+    write_private(&dir.join(&name), &content)
+        .map_err(|e| format!("Failed to write backup: {}", e))?;
+
+    for stale in existing.iter().skip(MAX_BACKUPS_PER_FILE - 1) {
+        let _ = fs::remove_file(dir.join(snapshot_name(&stale.id)));
+    }
+
+    Ok(Some(ConfigBackup {
+        id: format!("{}/{}", key, name),
+        path: path.to_string_lossy().to_string(),
+        created_at,
+        size: content.len() as u64,
+    }))
+}
+
+fn list_backups_in(root: &Path, path: Option<&Path>) -> Result<Vec<ConfigBackup>, String> {
+    if let Some(path) = path {
+        return list_dir(root, &file_key(&resolve_path(path)));
+    }
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    let entries =
+        fs::read_dir(root).map_err(|e| format!("Failed to read backup directory: {}", e))?;
+    for entry in entries.flatten() {
+        if entry.path().is_dir() {
+            backups.extend(list_dir(root, &entry.file_name().to_string_lossy())?);
+        }
+    }
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
+
+fn diff_backup_in(root: &Path, id: &str) -> Result<ConfigBackupDiff, String> {
+    let (backup_path, source) = locate_backup(root, id)?;
+    let old_content =
+        fs::read_to_string(&backup_path).map_err(|e| format!("Failed to read backup: {}", e))?;
+    let new_content = fs::read_to_string(&source).unwrap_or_default();
+    Ok(ConfigBackupDiff {
+        path: source.to_string_lossy().to_string(),
+        has_changes: old_content != new_content,
+        old_content,
+        new_content,
+    })
+}
+
+fn restore_backup_in(root: &Path, id: &str) -> Result<ConfigBackup, String> {
+    let (backup_path, source) = locate_backup(root, id)?;
+    let content =
+        fs::read_to_string(&backup_path).map_err(|e| format!("Failed to read backup: {}", e))?;
+    // Look the backup up first: snapshotting the current content may rotate it out.
+    let restored = list_dir(root, id.split('/').next().unwrap_or_default())?
+        .into_iter()
+        .find(|backup| backup.id == id)
+        .ok_or_else(|| format!("Backup '{}' not found", id))?;
+    write_with_backup_in(root, &source, &content)?;
+    Ok(restored)
+}
+
+/// Create `dir` (and missing parents) readable only by the user. Directories
+/// created before backups were private are tightened too.
+fn create_private_dir(dir: &Path) -> Result<(), String> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder
+        .create(dir)
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to secure backup directory: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Write `data` through a fresh 0600 file renamed over `path`, so readers never
+/// see a half-written file.
+pub fn write_private(path: &Path, data: &[u8]) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let written = options
+        .open(&tmp)
+        .and_then(|mut file| file.write_all(data).and_then(|_| file.sync_all()))
+        .and_then(|_| fs::rename(&tmp, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+/// Backups in one file's directory, newest first.
+fn list_dir(root: &Path, key: &str) -> Result<Vec<ConfigBackup>, String> {
+    let dir = root.join(key);
+    let Ok(source) = fs::read_to_string(dir.join(SOURCE_FILE)) else {
+        return Ok(Vec::new());
+    };
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("Failed to read backup directory: {}", e))?;
+    let mut backups: Vec<ConfigBackup> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let created_at = name
+                .strip_suffix(&format!(".{}", BACKUP_EXTENSION))?
+                .parse::<i64>()
+                .ok()?;
+            Some(ConfigBackup {
+                id: format!("{}/{}", key, name),
+                path: source.clone(),
+                created_at,
+                size: entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            })
+        })
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
+
+/// Resolve a backup id to its snapshot file and the config file it belongs to.
+fn locate_backup(root: &Path, id: &str) -> Result<(PathBuf, PathBuf), String> {
+    let invalid = || format!("Invalid backup id: {}", id);
+    let (key, name) = id.split_once('/').ok_or_else(invalid)?;
+    let valid_part =
+        |part: &str| !part.is_empty() && part != "." && part != ".." && !part.contains(['/', '\\']);
+    if !valid_part(key) || !valid_part(name) || !name.ends_with(BACKUP_EXTENSION) {
+        return Err(invalid());
+    }
+    let backup_path = root.join(key).join(name);
+    if !backup_path.is_file() {
+        return Err(format!("Backup '{}' not found", id));
+    }
+    let source = fs::read_to_string(root.join(key).join(SOURCE_FILE))
+        .map_err(|e| format!("Failed to read backup source: {}", e))?;
+    Ok((backup_path, PathBuf::from(source)))
+}
+
+fn snapshot_name(id: &str) -> &str {
+    id.rsplit('/').next().unwrap_or(id)
+}
+
+/// Follow symlinks (e.g. a dotfile-managed `~/.claude.json`) so the same file
+/// always maps to the same history.
+fn resolve_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Directory name for a file's history: a readable tail of the path plus a
+/// stable hash of the full path.
+fn file_key(path: &Path) -> String {
+    let full = path.to_string_lossy();
+    // FNV-1a, so keys stay the same across toolchains.
+    let hash = full.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    let readable: String = path
+        .components()
+        .rev()
+        .take(2)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .map(|part| part.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("_")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}-{:016x}", readable.trim_start_matches('.'), hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_diffs_and_restores() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let root = temp.path().join("backups");
+        let config = temp.path().join("project").join(".mcp.json");
+
+        assert!(
+            write_with_backup_in(&root, &config, "v0")
+                .unwrap()
+                .is_none()
+        );
+        for i in 1..=MAX_BACKUPS_PER_FILE + 5 {
+            write_with_backup_in(&root, &config, &format!("v{}", i)).unwrap();
+        }
+        // Rewriting the current content is a no-op.
+        let unchanged = format!("v{}", MAX_BACKUPS_PER_FILE + 5);
+        assert!(
+            write_with_backup_in(&root, &config, &unchanged)
+                .unwrap()
+                .is_none()
+        );
+
+        let backups = list_backups_in(&root, Some(&config)).unwrap();
+        assert_eq!(backups.len(), MAX_BACKUPS_PER_FILE);
+        assert_eq!(
+            list_backups_in(&root, None).unwrap().len(),
+            MAX_BACKUPS_PER_FILE
+        );
+
+        let newest = &backups[0];
+        let diff = diff_backup_in(&root, &newest.id).unwrap();
+        assert_eq!(diff.old_content, format!("v{}", MAX_BACKUPS_PER_FILE + 4));
+        assert!(diff.has_changes);
+
+        restore_backup_in(&root, &newest.id).unwrap();
+        assert_eq!(
+            fs::read_to_string(&config).unwrap(),
+            format!("v{}", MAX_BACKUPS_PER_FILE + 4)
+        );
+        assert!(diff_backup_in(&root, "../x/1.bak").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn backups_are_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let root = temp.path().join("backups");
+        let config = temp.path().join("providers.json");
+        fs::write(&config, r#"{"apiKey":"secret"}"#).unwrap();
+        let backup = write_with_backup_in(&root, &config, "{}").unwrap().unwrap();
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let snapshot = root.join(&backup.id);
+        assert_eq!(mode(&root), 0o700);
+        assert_eq!(mode(snapshot.parent().unwrap()), 0o700);
+        assert_eq!(mode(&snapshot), 0o600);
+        assert_eq!(fs::read_to_string(snapshot).unwrap(), r#"{"apiKey":"secret"}"#);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_replace_the_link_target_without_leaving_temp_files() {
+        let temp = tempfile::tempdir().expect("create temp dir");
+        let root = temp.path().join("backups");
+        let target = temp.path().join("dotfiles").join("config.toml");
+        fs::create_dir_all(target.parent().unwrap()).unwrap();
+        fs::write(&target, "model = \"o3\"\n").unwrap();
+        let link = temp.path().join("config.toml");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_with_backup_in(&root, &link, "model = \"gpt-5\"\n").unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "model = \"gpt-5\"\n");
+        let names: Vec<_> = fs::read_dir(target.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["config.toml"]);
+    }
+}
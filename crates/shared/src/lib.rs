@@ -1,3 +1,4 @@
+pub mod config_backup;
 pub mod dxt;
 pub mod fs;
 pub mod insights;
@@ -0,0 +1,16 @@
+use codexia_db::approval_audit::{self, ApprovalQuery, ApprovalRecord};
+use tokio::task::spawn_blocking;
+
+#[tauri::command]
+pub async fn query_approval_audit(query: ApprovalQuery) -> Result<Vec<ApprovalRecord>, String> {
+    spawn_blocking(move || approval_audit::query(&query))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
+
+#[tauri::command]
+pub async fn export_approval_audit_csv(query: ApprovalQuery) -> Result<String, String> {
+    spawn_blocking(move || approval_audit::export_csv(&query))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+}
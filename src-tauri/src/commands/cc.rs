@@ -1,14 +1,22 @@
+use codexia_cc::db::{SessionSearchHit, SessionSearchQuery};
 use codexia_cc::mcp::{
     ClaudeCodeMcpServer, ClaudeCodeResponse, cc_list_projects as mcp_cc_list_projects,
     cc_mcp_add as mcp_cc_mcp_add, cc_mcp_disable as mcp_cc_mcp_disable,
     cc_mcp_enable as mcp_cc_mcp_enable, cc_mcp_get as mcp_cc_mcp_get,
     cc_mcp_list as mcp_cc_mcp_list, cc_mcp_remove as mcp_cc_mcp_remove,
 };
+use codexia_cc::services::permission_rules::RuleKind;
+use codexia_cc::services::permission_storage::{self, ProjectPermissionRules};
+use codexia_cc::services::sensitive_paths::{self, SensitivePathPolicy};
 use codexia_cc::services::{
     message_service, session_service, settings_service, skill_service,
 };
-use codexia_cc::state::CCState;
-use codexia_cc::types::{AgentOptions, CCConnectParams};
+use codexia_cc::services::session_lifecycle::{self, CCStatus, SessionLimits};
+use codexia_cc::services::usage_service::SessionUsage;
+use codexia_cc::state::{CCState, QueuedMessage};
+use codexia_cc::transcript::{self, ImportedSession};
+use codexia_cc::types::{AgentOptions, CCConnectParams, PermissionTimeout};
+use codexia_codex::export::{ExportOptions, ThreadExport};
 use session_service::SessionListResult;
 use tauri::State;
 
@@ -25,12 +33,14 @@ pub async fn cc_send_message(
     session_id: String,
     message: String,
     image_paths: Option<Vec<String>>,
+    steer: Option<bool>,
     state: State<'_, CCState>,
-) -> Result<(), String> {
+) -> Result<QueuedMessage, String> {
     let image_paths = image_paths.unwrap_or_default();
+    let steer = steer.unwrap_or(false);
     let cc_state = state.inner().clone();
     let sid = session_id.clone();
-    message_service::send_message(&session_id, &message, &image_paths, &state, move |msg| {
+    message_service::send_message(&session_id, &message, &image_paths, steer, &state, move |msg| {
         if let Ok(mut payload) = serde_json::to_value(&msg) {
             if let Some(obj) = payload.as_object_mut() {
                 // Always override session_id with the caller's sid so that resumed sessions
@@ -43,11 +53,54 @@ pub async fn cc_send_message(
     .await
 }
 
+#[tauri::command]
+pub fn cc_list_queue(session_id: String, state: State<'_, CCState>) -> Vec<QueuedMessage> {
+    message_service::list_queue(&session_id, &state)
+}
+
+#[tauri::command]
+pub fn cc_cancel_queued(
+    session_id: String,
+    message_id: String,
+    state: State<'_, CCState>,
+) -> Result<(), String> {
+    message_service::cancel_queued(&session_id, &message_id, &state)
+}
+
+#[tauri::command]
+pub fn cc_reorder_queue(
+    session_id: String,
+    message_ids: Vec<String>,
+    state: State<'_, CCState>,
+) -> Result<(), String> {
+    message_service::reorder_queue(&session_id, &message_ids, &state)
+}
+
 #[tauri::command]
 pub async fn cc_disconnect(session_id: String, state: State<'_, CCState>) -> Result<(), String> {
     session_service::disconnect(&session_id, &state).await
 }
 
+#[tauri::command]
+pub fn cc_get_session_usage(session_id: String, state: State<'_, CCState>) -> SessionUsage {
+    state.get_usage(&session_id)
+}
+
+#[tauri::command]
+pub async fn cc_get_status(state: State<'_, CCState>) -> Result<CCStatus, String> {
+    Ok(session_lifecycle::status(&state).await)
+}
+
+#[tauri::command]
+pub fn cc_get_session_limits() -> Result<SessionLimits, String> {
+    session_lifecycle::read_limits()
+}
+
+#[tauri::command]
+pub fn cc_set_session_limits(limits: SessionLimits) -> Result<(), String> {
+    session_lifecycle::write_limits(limits)
+}
+
 #[tauri::command]
 pub async fn cc_new_session(
     options: AgentOptions,
@@ -105,6 +158,42 @@ pub fn cc_list_sessions(
     )
 }
 
+#[tauri::command]
+pub async fn cc_search_sessions(query: SessionSearchQuery) -> Result<Vec<SessionSearchHit>, String> {
+    // Without a watcher, searching first re-reads every changed transcript.
+    tokio::task::spawn_blocking(move || session_service::search_sessions(&query))
+        .await
+        .map_err(|e| format!("Failed to search sessions: {}", e))?
+}
+
+#[tauri::command]
+pub fn cc_set_session_title(session_id: String, title: String) -> Result<(), String> {
+    session_service::set_session_title(&session_id, &title)
+}
+
+#[tauri::command]
+pub fn cc_set_session_tags(session_id: String, tags: Vec<String>) -> Result<Vec<String>, String> {
+    session_service::set_session_tags(&session_id, &tags)
+}
+
+#[tauri::command]
+pub fn cc_fork_session(
+    session_id: String,
+    message_uuid: String,
+) -> Result<codexia_cc::rewind::ForkedSession, String> {
+    session_service::fork_session(&session_id, &message_uuid)
+}
+
+#[tauri::command]
+pub async fn cc_rewind_session(
+    session_id: String,
+    message_uuid: String,
+    revert_files: Option<bool>,
+    state: State<'_, CCState>,
+) -> Result<codexia_cc::rewind::RewindResult, String> {
+    session_service::rewind_session(&session_id, &message_uuid, revert_files.unwrap_or(false), &state).await
+}
+
 #[tauri::command]
 pub fn cc_delete_session(session_id: String) -> Result<(), String> {
     claude_agent_sdk_rs::session_mutations::delete_session(&session_id, None)
@@ -120,6 +209,49 @@ pub fn cc_get_session_messages(
     Ok(claude_agent_sdk_rs::sessions::get_session_messages(&session_id, None, None, 0))
 }
 
+#[tauri::command]
+pub fn cc_get_permission_rules(cwd: String) -> Result<ProjectPermissionRules, String> {
+    permission_storage::get_project_rules(&cwd)
+}
+
+#[tauri::command]
+pub fn cc_add_permission_rule(cwd: String, kind: RuleKind, rule: String) -> Result<(), String> {
+    permission_storage::add_project_rule(&cwd, kind, &rule)
+}
+
+#[tauri::command]
+pub fn cc_remove_permission_rule(cwd: String, kind: RuleKind, rule: String) -> Result<(), String> {
+    permission_storage::remove_project_rule(&cwd, kind, &rule)
+}
+
+#[tauri::command]
+pub fn cc_get_sensitive_path_policy() -> Result<SensitivePathPolicy, String> {
+    sensitive_paths::read_policy()
+}
+
+#[tauri::command]
+pub fn cc_set_sensitive_path_policy(policy: SensitivePathPolicy) -> Result<(), String> {
+    sensitive_paths::write_policy(policy)
+}
+
+#[tauri::command]
+pub async fn cc_export_session(
+    session_id: String,
+    options: Option<ExportOptions>,
+) -> Result<ThreadExport, String> {
+    transcript::export_session(&session_id, &options.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn cc_import_session(
+    content: String,
+    cwd: Option<String>,
+) -> Result<ImportedSession, String> {
+    tokio::task::spawn_blocking(move || transcript::import_session(&content, cwd.as_deref()))
+        .await
+        .map_err(|e| format!("Failed to import session: {}", e))?
+}
+
 #[tauri::command]
 pub async fn cc_mcp_list(working_dir: String) -> Result<Vec<ClaudeCodeMcpServer>, String> {
     mcp_cc_mcp_list(working_dir).await
@@ -176,6 +308,23 @@ pub async fn cc_resolve_permission(
     state.resolve_permission(&request_id, decision)
 }
 
+#[tauri::command]
+pub fn cc_pending_permissions(
+    session_id: Option<String>,
+    state: State<'_, CCState>,
+) -> Vec<serde_json::Value> {
+    state.list_pending_permissions(session_id.as_deref())
+}
+
+#[tauri::command]
+pub fn cc_set_permission_timeout(
+    session_id: String,
+    timeout: PermissionTimeout,
+    state: State<'_, CCState>,
+) -> Result<(), String> {
+    session_service::set_permission_timeout(&session_id, timeout, &state)
+}
+
 #[tauri::command]
 pub async fn cc_set_permission_mode(
     session_id: String,
@@ -13,9 +13,16 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use std::time::Instant;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 use codexia_codex::{AppState, CodexInitializationState, initialize_codex, scan};
+use codexia_codex::config::provider::resync_model_providers;
+use codexia_codex::export::{ExportOptions, ThreadExport};
+use codexia_codex::providers::{self, ProviderConfig, ProviderEntry, ProviderTestResult};
+use codexia_codex::approval_audit;
+use codexia_codex::rate_limits::{self, RateLimitAlertSettings};
+use codexia_db::codex_search::{CodexSearchHit, CodexSearchQuery};
+use codexia_db::rate_limits::RateLimitSample;
 
 fn to_value<T: serde::Serialize>(value: T) -> Result<Value, String> {
     serde_json::to_value(value).map_err(|e| e.to_string())
@@ -31,8 +38,10 @@ pub fn codex_home() -> PathBuf {
 }
 
 #[tauri::command]
-pub async fn list_other_models() -> Result<Vec<codexia_codex::providers::FrontendProviderModels>, String> {
-    codexia_codex::providers::load_and_fetch_models().await.map_err(|e| e.to_string())
+pub async fn list_other_models(
+    refresh: Option<bool>,
+) -> Result<Vec<codexia_codex::providers::FrontendProviderModels>, String> {
+    providers::fetch_models(refresh.unwrap_or(false)).await
 }
 
 #[tauri::command]
@@ -40,6 +49,38 @@ pub async fn load_env_keys() -> Result<Vec<codexia_codex::providers::EnvStatusIt
     codexia_codex::providers::load_env_keys().await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn list_model_providers() -> Result<Vec<ProviderEntry>, String> {
+    providers::list_providers()
+}
+
+#[tauri::command]
+pub async fn upsert_model_provider(app: AppHandle, provider: ProviderConfig) -> Result<(), String> {
+    providers::upsert_user_provider(provider)?;
+    let state = app.try_state::<AppState>();
+    resync_model_providers(state.as_ref().map(|state| state.codex.as_ref()), None).await
+}
+
+#[tauri::command]
+pub async fn delete_model_provider(app: AppHandle, model_provider: String) -> Result<(), String> {
+    providers::delete_user_provider(&model_provider)?;
+    let state = app.try_state::<AppState>();
+    resync_model_providers(
+        state.as_ref().map(|state| state.codex.as_ref()),
+        Some(&model_provider),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn test_model_provider(
+    model_provider: String,
+    model: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<ProviderTestResult, String> {
+    providers::test_provider(&model_provider, model, timeout_secs).await
+}
+
 #[tauri::command]
 pub async fn initialize_codex_async(
     state: State<'_, AppState>,
@@ -140,6 +181,19 @@ pub async fn list_archived_threads(params: ThreadListParams) -> Result<Value, St
     scan::list_archived_threads_payload(params_value).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn search_threads(query: CodexSearchQuery) -> Result<Vec<CodexSearchHit>, String> {
+    codexia_codex::search::search_threads(&query)
+}
+
+#[tauri::command]
+pub async fn export_thread(
+    path: String,
+    options: Option<ExportOptions>,
+) -> Result<ThreadExport, String> {
+    codexia_codex::export::export_thread(&path, &options.unwrap_or_default())
+}
+
 #[tauri::command]
 pub async fn archive_thread(
     thread_id: String,
@@ -185,14 +239,35 @@ pub async fn model_list(state: State<'_, AppState>) -> Result<ModelListResponse,
 #[tauri::command]
 pub async fn account_rate_limits(
     state: State<'_, AppState>,
+    init_state: State<'_, CodexInitializationState>,
 ) -> Result<GetAccountRateLimitsResponse, String> {
     let result = state
         .codex
         .send_request("account/rateLimits/read", Value::Null)
         .await?;
+    rate_limits::record_read_response(init_state.event_sink.as_ref(), &result);
     Ok(from_value(result)?)
 }
 
+#[tauri::command]
+pub async fn rate_limit_history(
+    limit_id: Option<String>,
+    since: Option<i64>,
+    limit: Option<usize>,
+) -> Result<Vec<RateLimitSample>, String> {
+    rate_limits::rate_limit_history(limit_id.as_deref(), since, limit.unwrap_or(0))
+}
+
+#[tauri::command]
+pub async fn read_rate_limit_alerts() -> Result<RateLimitAlertSettings, String> {
+    rate_limits::read_alert_settings()
+}
+
+#[tauri::command]
+pub async fn write_rate_limit_alerts(settings: RateLimitAlertSettings) -> Result<(), String> {
+    rate_limits::write_alert_settings(settings)
+}
+
 #[tauri::command]
 pub async fn skills_list(
     cwd: String,
@@ -233,7 +308,10 @@ pub async fn respond_to_command_execution_approval(
         "codex:response: {}",
         serde_json::to_string(&result_value).unwrap_or_default()
     );
-    state.codex.send_response(request_id, result_value).await?;
+    let decision = result_value["decision"].clone();
+    state.codex.send_response(request_id.clone(), result_value).await?;
+    // Only a delivered answer is a decision.
+    approval_audit::record_decision(&request_id, &decision);
     Ok(())
 }
 
@@ -248,7 +326,10 @@ pub async fn respond_to_file_change_approval(
         "codex:response: {}",
         serde_json::to_string(&result_value).unwrap_or_default()
     );
-    state.codex.send_response(request_id, result_value).await?;
+    let decision = result_value["decision"].clone();
+    state.codex.send_response(request_id.clone(), result_value).await?;
+    // Only a delivered answer is a decision.
+    approval_audit::record_decision(&request_id, &decision);
     Ok(())
 }
 
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use codexia_codex::config::settings::{self, CodexSettings, ConfigProfile, TrustLevel};
+
+#[tauri::command]
+pub async fn read_codex_settings() -> Result<CodexSettings, String> {
+    settings::read_codex_settings().await
+}
+
+#[tauri::command]
+pub async fn write_codex_settings(settings: CodexSettings) -> Result<(), String> {
+    settings::write_codex_settings(settings).await
+}
+
+#[tauri::command]
+pub async fn list_codex_profiles() -> Result<BTreeMap<String, ConfigProfile>, String> {
+    settings::list_profiles().await
+}
+
+#[tauri::command]
+pub async fn create_codex_profile(name: String, profile: ConfigProfile) -> Result<(), String> {
+    settings::create_profile(name, profile).await
+}
+
+#[tauri::command]
+pub async fn update_codex_profile(name: String, profile: ConfigProfile) -> Result<(), String> {
+    settings::update_profile(name, profile).await
+}
+
+#[tauri::command]
+pub async fn clone_codex_profile(source: String, target: String) -> Result<(), String> {
+    settings::clone_profile(source, target).await
+}
+
+#[tauri::command]
+pub async fn delete_codex_profile(name: String) -> Result<(), String> {
+    settings::delete_profile(name).await
+}
+
+#[tauri::command]
+pub async fn set_active_codex_profile(name: Option<String>) -> Result<(), String> {
+    settings::set_active_profile(name).await
+}
+
+#[tauri::command]
+pub async fn read_codex_project_trust() -> Result<BTreeMap<String, TrustLevel>, String> {
+    settings::read_project_trust().await
+}
+
+#[tauri::command]
+pub async fn set_codex_project_trust(
+    path: String,
+    trust_level: Option<TrustLevel>,
+) -> Result<(), String> {
+    settings::set_project_trust(path, trust_level).await
+}
@@ -0,0 +1,18 @@
+use std::path::Path;
+
+use codexia_shared::config_backup::{self, ConfigBackup, ConfigBackupDiff};
+
+#[tauri::command]
+pub async fn list_config_backups(path: Option<String>) -> Result<Vec<ConfigBackup>, String> {
+    config_backup::list_backups(path.as_deref().map(Path::new))
+}
+
+#[tauri::command]
+pub async fn diff_config_backup(id: String) -> Result<ConfigBackupDiff, String> {
+    config_backup::diff_backup(&id)
+}
+
+#[tauri::command]
+pub async fn restore_config_backup(id: String) -> Result<ConfigBackup, String> {
+    config_backup::restore_backup(&id)
+}
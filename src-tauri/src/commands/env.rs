@@ -2,10 +2,32 @@ use codexia_codex;
 
 #[tauri::command]
 pub async fn get_env(key: String) -> Result<String, String> {
-    codexia_codex::env::get_env(key).map_err(|e| e.to_string())
+    tokio::task::spawn_blocking(move || codexia_codex::env::get_env(key))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
 pub async fn set_env(key: String, value: String) -> Result<(), String> {
-    codexia_codex::env::set_env(key, value).map_err(|e| e.to_string())
+    tokio::task::spawn_blocking(move || codexia_codex::env::set_env(key, value))
+        .await
+        .map_err(|e| e.to_string())??;
+    codexia_codex::providers::clear_models_cache();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_secrets() -> Result<Vec<codexia_codex::secrets::StoredSecret>, String> {
+    tokio::task::spawn_blocking(codexia_codex::secrets::list_secrets)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn delete_secret(name: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || codexia_codex::secrets::delete_secret(&name))
+        .await
+        .map_err(|e| e.to_string())??;
+    codexia_codex::providers::clear_models_cache();
+    Ok(())
 }
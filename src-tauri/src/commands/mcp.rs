@@ -1,4 +1,7 @@
+use codexia_cc::mcp_catalog::{self, McpCatalog, McpCatalogStatus, McpSyncRequest, McpSyncResult};
 use codexia_cc::mcp_unified as mcp;
+use codexia_codex::config::mcp::McpServerConfig;
+use codexia_codex::mcp_health::{self, McpHealth};
 use serde_json::Value as JsonValue;
 
 #[tauri::command]
@@ -47,3 +50,43 @@ pub async fn unified_read_mcp_config(
 ) -> Result<JsonValue, String> {
     mcp::unified_read_mcp_config(client_name, path).await
 }
+
+#[tauri::command]
+pub fn mcp_catalog_read() -> Result<McpCatalog, String> {
+    mcp_catalog::read_catalog()
+}
+
+#[tauri::command]
+pub fn mcp_catalog_write(catalog: McpCatalog) -> Result<(), String> {
+    mcp_catalog::write_catalog(&catalog)
+}
+
+#[tauri::command]
+pub async fn mcp_catalog_status(path: Option<String>) -> Result<McpCatalogStatus, String> {
+    mcp_catalog::catalog_status(path.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn mcp_catalog_sync(request: McpSyncRequest) -> Result<McpSyncResult, String> {
+    mcp_catalog::sync_catalog(request).await
+}
+
+#[tauri::command]
+pub async fn check_mcp_servers(
+    names: Option<Vec<String>>,
+    refresh: Option<bool>,
+    timeout_secs: Option<u64>,
+) -> Result<Vec<McpHealth>, String> {
+    mcp_health::check_mcp_servers(names, refresh.unwrap_or(false), timeout_secs).await
+}
+
+#[tauri::command]
+pub async fn probe_mcp_server(
+    server_name: String,
+    server_config: JsonValue,
+    timeout_secs: Option<u64>,
+) -> Result<McpHealth, String> {
+    let config: McpServerConfig = serde_json::from_value(server_config)
+        .map_err(|e| format!("Failed to parse MCP server config: {}", e))?;
+    Ok(mcp_health::probe_mcp_server(&server_name, &config, timeout_secs).await)
+}
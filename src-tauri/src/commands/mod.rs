@@ -1,6 +1,9 @@
+pub mod audit;
 pub mod automation;
 pub mod cc;
 pub mod codex;
+pub mod codex_config;
+pub mod config_backup;
 pub mod dxt;
 pub mod env;
 pub mod fs;
@@ -57,17 +57,26 @@ pub fn run() {
             .invoke_handler(tauri::generate_handler![
                 commands::codex::list_other_models,
                 commands::codex::load_env_keys,
+                commands::codex::list_model_providers,
+                commands::codex::upsert_model_provider,
+                commands::codex::delete_model_provider,
+                commands::codex::test_model_provider,
                 commands::codex::start_thread,
                 commands::codex::resume_thread,
                 commands::codex::fork_thread,
                 commands::codex::rollback_thread,
                 commands::codex::list_threads,
                 commands::codex::list_archived_threads,
+                commands::codex::search_threads,
+                commands::codex::export_thread,
                 commands::codex::archive_thread,
                 commands::codex::turn_start,
                 commands::codex::turn_interrupt,
                 commands::codex::model_list,
                 commands::codex::account_rate_limits,
+                commands::codex::rate_limit_history,
+                commands::codex::read_rate_limit_alerts,
+                commands::codex::write_rate_limit_alerts,
                 commands::codex::get_account,
                 commands::codex::login_account,
                 commands::codex::skills_list,
@@ -95,18 +104,39 @@ pub fn run() {
                 commands::cc::cc_connect,
                 commands::cc::cc_new_session,
                 commands::cc::cc_send_message,
+                commands::cc::cc_list_queue,
+                commands::cc::cc_cancel_queued,
+                commands::cc::cc_reorder_queue,
                 commands::cc::cc_disconnect,
                 commands::cc::cc_interrupt,
+                commands::cc::cc_get_session_usage,
+                commands::cc::cc_get_status,
+                commands::cc::cc_get_session_limits,
+                commands::cc::cc_set_session_limits,
                 commands::cc::cc_resume_session,
                 commands::cc::cc_list_sessions,
+                commands::cc::cc_search_sessions,
+                commands::cc::cc_set_session_title,
+                commands::cc::cc_set_session_tags,
+                commands::cc::cc_fork_session,
+                commands::cc::cc_rewind_session,
                 commands::cc::cc_delete_session,
                 commands::cc::cc_get_session_messages,
+                commands::cc::cc_export_session,
+                commands::cc::cc_import_session,
+                commands::cc::cc_get_permission_rules,
+                commands::cc::cc_add_permission_rule,
+                commands::cc::cc_remove_permission_rule,
+                commands::cc::cc_get_sensitive_path_policy,
+                commands::cc::cc_set_sensitive_path_policy,
                 commands::cc::cc_get_installed_skills,
                 commands::cc::cc_get_slash_commands,
                 commands::cc::cc_get_settings,
                 commands::cc::cc_update_settings,
                 commands::cc::cc_resolve_permission,
+                commands::cc::cc_pending_permissions,
                 commands::cc::cc_set_permission_mode,
+                commands::cc::cc_set_permission_timeout,
                 commands::cc::cc_mcp_list,
                 commands::cc::cc_mcp_get,
                 commands::cc::cc_mcp_add,
@@ -119,6 +149,25 @@ pub fn run() {
                 commands::mcp::unified_enable_mcp_server,
                 commands::mcp::unified_disable_mcp_server,
                 commands::mcp::unified_read_mcp_config,
+                commands::mcp::mcp_catalog_read,
+                commands::mcp::mcp_catalog_write,
+                commands::mcp::mcp_catalog_status,
+                commands::mcp::mcp_catalog_sync,
+                commands::mcp::check_mcp_servers,
+                commands::mcp::probe_mcp_server,
+                commands::codex_config::read_codex_settings,
+                commands::codex_config::write_codex_settings,
+                commands::codex_config::list_codex_profiles,
+                commands::codex_config::create_codex_profile,
+                commands::codex_config::update_codex_profile,
+                commands::codex_config::clone_codex_profile,
+                commands::codex_config::delete_codex_profile,
+                commands::codex_config::set_active_codex_profile,
+                commands::codex_config::read_codex_project_trust,
+                commands::codex_config::set_codex_project_trust,
+                commands::config_backup::list_config_backups,
+                commands::config_backup::diff_config_backup,
+                commands::config_backup::restore_config_backup,
                 commands::skillssh::fetch_market_leaderboard,
                 commands::skillssh::search_market_skills,
                 commands::skillssh::install_from_market,
@@ -171,6 +220,8 @@ pub fn run() {
                 commands::insights::get_agent_heatmaps,
                 commands::insights::get_insight_filter_options,
                 commands::insights::get_insight_rankings,
+                commands::audit::query_approval_audit,
+                commands::audit::export_approval_audit_csv,
                 commands::dxt::load_manifests,
                 commands::dxt::load_manifest,
                 commands::dxt::read_dxt_setting,
@@ -181,13 +232,19 @@ pub fn run() {
                 commands::codex::codex_home,
                 commands::env::get_env,
                 commands::env::set_env,
+                commands::env::list_secrets,
+                commands::env::delete_secret,
             ])
             .setup(|app| {
                 let app_handle = app.handle().clone();
                 let event_sink: Arc<dyn codexia_shared::event_sink::EventSink> =
                     Arc::new(event_sink::TauriEventSink::new(app_handle));
 
-                app.manage(CCState::new(Arc::clone(&event_sink)));
+                let cc_state = CCState::new(Arc::clone(&event_sink));
+                tauri::async_runtime::spawn(
+                    codexia_cc::services::session_lifecycle::run_session_reaper(cc_state.clone()),
+                );
+                app.manage(cc_state);
 
                 let codex_init_started_at = Instant::now();
                 let init_result = tauri::async_runtime::block_on(async {
@@ -227,7 +284,7 @@ pub fn run() {
                 }
 
                 codex::scan::start_history_scanner(event_sink.clone());
-                cc::scan::start_session_scanner();
+                cc::scan::start_session_scanner(event_sink.clone());
 
                 tauri::async_runtime::spawn(async {
                     tokio::task::spawn_blocking(codexia_git::scan_all_orphan_worktrees)
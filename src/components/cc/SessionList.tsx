@@ -3,6 +3,7 @@ import { useCCStore } from '@/stores/cc';
 import { listSessions, type SdkSessionInfo } from '@/lib/sessions';
 import { ccGetSessionMessages, ccDeleteSession } from '@/services/tauri/cc';
 import { fromSdkMessages } from '@/components/cc/utils/fromSdkMessages';
+import { useCCSessionsUpdatedListener } from './hooks';
 import { MoreVertical, Copy, Loader2, Trash2, FolderX } from 'lucide-react';
 import { gitRemoveWorktree } from '@/services/tauri/git';
 import {
@@ -99,6 +100,15 @@ export function ClaudeCodeSessionList({ directory, sessions, onSelectSession }:
     void loadSessions();
   }, [loadSessions, sessions]);
 
+  // Reload when a session of this directory (or one of its worktrees) changes on disk.
+  useCCSessionsUpdatedListener(({ updated, removed }) => {
+    const worktreePrefix = `${directory.replace(/\/+$/, '')}/.codexia/worktrees/`;
+    const affected =
+      updated.some((s) => s.cwd === directory || s.cwd?.startsWith(worktreePrefix)) ||
+      removed.some((id) => loadedSessions.some((s) => s.session_id === id));
+    if (affected) void loadSessions();
+  }, sessions !== undefined || !directory);
+
   const [expanded, setExpanded] = useState(false);
 
   const baseList = sessions ?? loadedSessions;
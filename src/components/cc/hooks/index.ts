@@ -1,8 +1,10 @@
-import { useEffect } from 'react';
+import { useEffect, useRef } from 'react';
 import { listen } from '@tauri-apps/api/event';
 import { useCCStore } from '@/stores/cc';
 import { buildUrl, isDesktopTauri } from '@/hooks/runtime';
+import { ccPendingPermissions } from '@/services/tauri';
 import type { CCMessage, SystemMessage } from '../types/messages';
+import type { SdkSessionInfo } from '@/lib/sessions';
 
 const CC_LISTENER_READY_EVENT = 'cc-session-listener-ready';
 const CC_PERMISSION_LISTENER_READY_EVENT = 'cc-permission-listener-ready';
@@ -110,10 +112,20 @@ export function useCCPermissionListener({ disabled = false, sessionId }: CCListe
       toolName: string;
       toolInput: Record<string, unknown>;
       alwaysAllowTarget?: 'project' | 'session';
+      suggestedRules?: string[];
+      sensitive?: { path: string; pattern: string } | null;
     };
 
     const handlePermission = (payload: PermPayload) => {
-      const { requestId, sessionId: evtSessionId, toolName, toolInput, alwaysAllowTarget } = payload;
+      const {
+        requestId,
+        sessionId: evtSessionId,
+        toolName,
+        toolInput,
+        alwaysAllowTarget,
+        suggestedRules,
+        sensitive,
+      } = payload;
       if (evtSessionId !== targetSessionId) {
         if (!sessionId) {
           console.warn('[CCSession] Ignoring permission request for inactive session', {
@@ -125,12 +137,21 @@ export function useCCPermissionListener({ disabled = false, sessionId }: CCListe
         return;
       }
 
+      // Prompts replayed on reconnect may already be in the list.
+      const store = useCCStore.getState();
+      const existing = sessionId ? (store.sessionMessagesMap[sessionId] ?? []) : store.messages;
+      if (existing.some((m) => m.type === 'permission_request' && m.requestId === requestId)) {
+        return;
+      }
+
       const permissionMessage = {
         type: 'permission_request',
         requestId,
         sessionId: evtSessionId,
         toolName,
         alwaysAllowTarget,
+        suggestedRules,
+        sensitive,
         toolInput,
       } as CCMessage;
 
@@ -141,6 +162,11 @@ export function useCCPermissionListener({ disabled = false, sessionId }: CCListe
       }
     };
 
+    // Replay prompts still waiting from before this listener was bound.
+    void ccPendingPermissions(targetSessionId)
+      .then((pending) => pending.forEach((payload) => handlePermission(payload as PermPayload)))
+      .catch((err) => console.warn('[CCSession] Failed to load pending permissions', err));
+
     if (isDesktopTauri()) {
       const unlistenPromise = listen<PermPayload>('cc-permission-request', (event) => {
         handlePermission(event.payload);
@@ -182,3 +208,46 @@ export function useCCPermissionListener({ disabled = false, sessionId }: CCListe
     };
   }, [disabled, targetSessionId, sessionId, addMessage, addMessageToSession]);
 }
+
+/** Payload of `cc:sessions/updated`, emitted when transcripts under `~/.claude/projects` change. */
+export interface CCSessionsUpdatedPayload {
+  updated: SdkSessionInfo[];
+  removed: string[];
+}
+
+/**
+ * Hook to refresh session lists when the backend session cache changes.
+ */
+export function useCCSessionsUpdatedListener(
+  onUpdate: (payload: CCSessionsUpdatedPayload) => void,
+  disabled = false,
+) {
+  const onUpdateRef = useRef(onUpdate);
+  onUpdateRef.current = onUpdate;
+
+  useEffect(() => {
+    if (disabled) return;
+
+    if (isDesktopTauri()) {
+      const unlistenPromise = listen<CCSessionsUpdatedPayload>('cc:sessions/updated', (event) => {
+        onUpdateRef.current(event.payload);
+      });
+      return () => {
+        void unlistenPromise.then((fn) => fn());
+      };
+    }
+
+    const es = new EventSource(buildUrl('/api/events'));
+    es.onmessage = (e) => {
+      try {
+        const envelope = JSON.parse(e.data as string) as { event?: string; payload?: unknown };
+        if (envelope.event === 'cc:sessions/updated') {
+          onUpdateRef.current(envelope.payload as CCSessionsUpdatedPayload);
+        }
+      } catch { }
+    };
+    return () => {
+      es.close();
+    };
+  }, [disabled]);
+}
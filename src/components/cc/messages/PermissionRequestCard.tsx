@@ -34,6 +34,13 @@ export function PermissionRequestCard({ msg, onResolve }: Props) {
         <span className="text-sm font-semibold uppercase tracking-wide">Permission Request</span>
       </div>
 
+      {msg.sensitive && (
+        <div className="mb-3 text-xs text-red-600 dark:text-red-400">
+          Sensitive path <code className="font-mono">{msg.sensitive.path}</code> (matches{' '}
+          <code className="font-mono">{msg.sensitive.pattern}</code>)
+        </div>
+      )}
+
       {/* Tool info */}
       <div className="flex flex-col gap-1.5 p-3 rounded-lg bg-background/50 border border-amber-500/20 text-xs mb-3">
         {!NO_RAW_INPUT_TOOLS.includes(msg.toolName) && (
@@ -101,6 +108,7 @@ export function PermissionRequestCard({ msg, onResolve }: Props) {
               size="sm"
               variant="ghost"
               className="h-8 text-blue-600 dark:text-blue-400 hover:bg-blue-500/10"
+              title={msg.suggestedRules?.join(', ')}
               onClick={() => onResolve(msg.requestId, 'allow_project')}
             >
               <ShieldCheck className="w-3 h-3 mr-1" /> Always Allow (project)
@@ -110,6 +118,7 @@ export function PermissionRequestCard({ msg, onResolve }: Props) {
               size="sm"
               variant="ghost"
               className="h-8 text-amber-600 dark:text-amber-400 hover:bg-amber-500/10"
+              title={msg.suggestedRules?.join(', ')}
               onClick={() => onResolve(msg.requestId, 'allow_always')}
             >
               <ShieldCheck className="w-3 h-3 mr-1" /> Always Allow (session)
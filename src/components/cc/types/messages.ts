@@ -110,6 +110,10 @@ export interface PermissionRequestMessage {
   toolInput: Record<string, any>;
   /** Whether "always allow" targets project settings or session memory (only one shown) */
   alwaysAllowTarget?: 'project' | 'session';
+  /** Rules "always allow" saves, e.g. `Bash(git status:*)` */
+  suggestedRules?: string[];
+  /** Set when the call touches a path matching the sensitive-path policy */
+  sensitive?: { path: string; pattern: string } | null;
   resolved?: 'allow' | 'allow_always' | 'allow_project' | 'deny';
 }
 
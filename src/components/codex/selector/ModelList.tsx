@@ -12,6 +12,9 @@ export type FrontendModel = {
 export type FrontendProviderModels = {
   provider: string;
   models: FrontendModel[];
+  status: 'ok' | 'unauthorized' | 'unreachable' | 'error';
+  /** Why `models` is empty when `status` is not `ok` */
+  error?: string | null;
 };
 
 // Matches Rust EnvStatusItem
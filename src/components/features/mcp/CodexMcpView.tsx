@@ -12,7 +12,6 @@ import {
 } from '@/components/features/mcp';
 import {
   unifiedReadMcpConfig,
-  unifiedRemoveMcpServer,
   unifiedAddMcpServer,
 } from '@/services';
 
@@ -28,7 +27,7 @@ export function CodexMcpView({ refreshKey }: CodexMcpViewProps) {
     name: string;
     protocol: 'stdio' | 'http' | 'sse';
     command: { command: string; args: string; env: string };
-    http: { url: string };
+    http: { url: string; headers: string };
   } | null>(null);
 
   const loadServers = async () => {
@@ -61,6 +60,10 @@ export function CodexMcpView({ refreshKey }: CodexMcpViewProps) {
       },
       http: {
         url: httpUrl,
+        headers:
+          config.type !== 'stdio' && 'http_headers' in config && config.http_headers
+            ? JSON.stringify(config.http_headers, null, 2)
+            : '',
       },
     });
   };
@@ -91,12 +94,28 @@ export function CodexMcpView({ refreshKey }: CodexMcpViewProps) {
           type: editConfig.protocol,
           url: editConfig.http.url,
         };
+
+        if (editConfig.http.headers.trim()) {
+          try {
+            config.http_headers = JSON.parse(editConfig.http.headers);
+          } catch (e) {
+            toast.error('Invalid JSON format for headers');
+            return;
+          }
+        }
       }
 
-      await unifiedRemoveMcpServer({
-        clientName: 'codex',
-        serverName: editingServer,
-      });
+      // Keep settings the form does not edit (cwd, timeouts, bearer token, enabled).
+      const original = servers[editingServer];
+      if (original && getServerProtocol(original) === editConfig.protocol) {
+        const { command, args, env, url, http_headers, ...extra } = original as Record<
+          string,
+          unknown
+        >;
+        config = { ...extra, ...config } as McpServerConfig;
+      }
+
+      // Adding over the existing entry replaces it in place; the name is not editable.
       await unifiedAddMcpServer({
         clientName: 'codex',
         serverName: editConfig.name,
@@ -156,7 +175,7 @@ export function CodexMcpView({ refreshKey }: CodexMcpViewProps) {
                           onCommandConfigChange={(command) =>
                             setEditConfig((prev) => (prev ? { ...prev, command } : null))
                           }
-                          httpConfig={editConfig?.http ?? { url: '' }}
+                          httpConfig={editConfig?.http ?? { url: '', headers: '' }}
                           onHttpConfigChange={(http) =>
                             setEditConfig((prev) => (prev ? { ...prev, http } : null))
                           }
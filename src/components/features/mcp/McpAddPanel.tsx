@@ -16,19 +16,21 @@ export function McpAddPanel({ onAdded }: McpAddPanelProps) {
   const [serverName, setServerName] = useState('');
   const [protocol, setProtocol] = useState<'stdio' | 'http' | 'sse'>('stdio');
   const [commandConfig, setCommandConfig] = useState({ command: '', args: '', env: '' });
-  const [httpConfig, setHttpConfig] = useState({ url: '' });
+  const [httpConfig, setHttpConfig] = useState({ url: '', headers: '' });
 
   const resetForm = () => {
     setServerName('');
     setProtocol('stdio');
     setCommandConfig({ command: '', args: '', env: '' });
-    setHttpConfig({ url: '' });
+    setHttpConfig({ url: '', headers: '' });
   };
 
-  const parseEnv = (raw: string): Record<string, string> | null => {
+  const parseJson = (raw: string, label: string): Record<string, string> | null => {
     try { return JSON.parse(raw); }
-    catch { toast.error('Invalid JSON for environment variables'); return null; }
+    catch { toast.error(`Invalid JSON for ${label}`); return null; }
   };
+  const parseEnv = (raw: string) => parseJson(raw, 'environment variables');
+  const parseHeaders = (raw: string) => parseJson(raw, 'headers');
 
   const splitArgs = (raw: string) => raw.split(' ').filter((a) => a.trim());
 
@@ -46,6 +48,11 @@ export function McpAddPanel({ onAdded }: McpAddPanelProps) {
           }
         } else {
           config = { type: protocol, url: httpConfig.url };
+          if (httpConfig.headers.trim()) {
+            const headers = parseHeaders(httpConfig.headers);
+            if (!headers) return;
+            config.http_headers = headers;
+          }
         }
         await unifiedAddMcpServer({ clientName: 'codex', serverName, serverConfig: config });
       } else {
@@ -62,6 +69,11 @@ export function McpAddPanel({ onAdded }: McpAddPanelProps) {
         } else {
           if (!httpConfig.url.trim()) { toast.error('URL is required'); return; }
           request.url = httpConfig.url;
+          if (httpConfig.headers.trim()) {
+            const headers = parseHeaders(httpConfig.headers);
+            if (!headers) return;
+            request.headers = headers;
+          }
         }
         await ccMcpAdd(request, cwd || '');
       }
@@ -1,15 +1,32 @@
-import type { Dispatch, SetStateAction } from 'react';
+import { useState, type Dispatch, type SetStateAction } from 'react';
 import { Button } from '@/components/ui/button';
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card';
 import { Switch } from '@/components/ui/switch';
-import { Trash2, Edit } from 'lucide-react';
+import { Trash2, Edit, Activity, Loader2 } from 'lucide-react';
 import { McpServerConfig } from '@/types';
 import { toast } from 'sonner';
-import { unifiedDisableMcpServer, unifiedEnableMcpServer, unifiedRemoveMcpServer } from '@/services';
+import {
+  type McpHealth,
+  checkMcpServers,
+  unifiedDisableMcpServer,
+  unifiedEnableMcpServer,
+  unifiedRemoveMcpServer,
+} from '@/services';
 
 export const getServerProtocol = (config: McpServerConfig): 'stdio' | 'http' | 'sse' =>
   config.type ?? 'stdio';
 
+export const describeMcpHealth = (health: McpHealth): string => {
+  if (health.status !== 'healthy') {
+    return health.status === 'timed_out' ? 'Timed out' : health.error ?? 'Failed';
+  }
+  const count = (n: number, label: string) => `${n} ${label}${n === 1 ? '' : 's'}`;
+  const parts = [count(health.tools.length, 'tool')];
+  if (health.prompts.length > 0) parts.push(count(health.prompts.length, 'prompt'));
+  if (health.resources.length > 0) parts.push(count(health.resources.length, 'resource'));
+  return `Healthy, ${parts.join(', ')}`;
+};
+
 interface McpServerCardProps {
   name: string;
   config: McpServerConfig;
@@ -27,6 +44,21 @@ export function McpServerCard({
 }: McpServerCardProps) {
   const serverType = getServerProtocol(config);
   const isEnabled = config.enabled ?? true;
+  const [health, setHealth] = useState<McpHealth | null>(null);
+  const [checking, setChecking] = useState(false);
+
+  const handleCheckHealth = async () => {
+    setChecking(true);
+    try {
+      const [result] = await checkMcpServers([name], true);
+      setHealth(result ?? null);
+    } catch (error) {
+      console.error('Failed to check MCP server:', error);
+      toast.error('Failed to check MCP server: ' + error);
+    } finally {
+      setChecking(false);
+    }
+  };
 
   const handleDeleteServer = async () => {
     try {
@@ -75,6 +107,15 @@ export function McpServerCard({
               onCheckedChange={(checked) => handleToggleServerEnabled(checked)}
               aria-label={`Toggle ${name} server`}
             />
+            <Button
+              size="sm"
+              variant="ghost"
+              onClick={handleCheckHealth}
+              disabled={checking}
+              aria-label={`Check ${name} server`}
+            >
+              {checking ? <Loader2 className="h-4 w-4 animate-spin" /> : <Activity className="h-4 w-4" />}
+            </Button>
             <Button size="sm" variant="ghost" onClick={() => onEdit(name, config)}>
               <Edit className="h-4 w-4" />
             </Button>
@@ -111,6 +152,14 @@ export function McpServerCard({
               <strong>url:</strong> {config.url}
             </div>
           )}
+          {health && (
+            <div
+              className={health.status === 'healthy' ? 'text-green-600' : 'text-red-600'}
+              title={health.stderr ?? undefined}
+            >
+              {describeMcpHealth(health)}
+            </div>
+          )}
         </div>
       </CardContent>
     </Card>
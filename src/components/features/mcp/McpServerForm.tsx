@@ -10,8 +10,8 @@ interface McpServerFormProps {
   onProtocolChange: (protocol: 'stdio' | 'http' | 'sse') => void;
   commandConfig: { command: string; args: string; env: string };
   onCommandConfigChange: (config: { command: string; args: string; env: string }) => void;
-  httpConfig: { url: string };
-  onHttpConfigChange: (config: { url: string }) => void;
+  httpConfig: { url: string; headers: string };
+  onHttpConfigChange: (config: { url: string; headers: string }) => void;
   isEditMode?: boolean;
 }
 
@@ -85,6 +85,15 @@ export const McpServerForm: React.FC<McpServerFormProps> = ({
               placeholder="https://mcp.deepwiki.com/mcp"
             />
           </div>
+          <div>
+            <div className="text-sm font-medium mb-1">Headers (JSON, optional)</div>
+            <Textarea
+              value={httpConfig.headers}
+              onChange={(e) => onHttpConfigChange({ ...httpConfig, headers: e.target.value })}
+              placeholder='{"Authorization": "Bearer token"} - Leave empty if not needed'
+              rows={3}
+            />
+          </div>
         </TabsContent>
         <TabsContent value="sse" className="space-y-4">
           <div>
@@ -95,6 +104,15 @@ export const McpServerForm: React.FC<McpServerFormProps> = ({
               placeholder="https://mcp.deepwiki.com/sse"
             />
           </div>
+          <div>
+            <div className="text-sm font-medium mb-1">Headers (JSON, optional)</div>
+            <Textarea
+              value={httpConfig.headers}
+              onChange={(e) => onHttpConfigChange({ ...httpConfig, headers: e.target.value })}
+              placeholder='{"Authorization": "Bearer token"} - Leave empty if not needed'
+              rows={3}
+            />
+          </div>
         </TabsContent>
       </Tabs>
     </div>
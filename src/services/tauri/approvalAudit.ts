@@ -0,0 +1,46 @@
+import { invokeTauri, isDesktopTauri, postJson } from './shared';
+
+export type ApprovalDecidedBy = 'user' | 'rule' | 'automation';
+
+export interface ApprovalRecord {
+  id: number;
+  /** Unix milliseconds */
+  recordedAt: number;
+  agent: 'cc' | 'codex';
+  /** Claude session id or codex thread id */
+  sessionId: string;
+  toolName: string;
+  inputSummary: string;
+  decision: string;
+  decidedBy: ApprovalDecidedBy;
+  reason: string | null;
+}
+
+export interface ApprovalQuery {
+  agent?: 'cc' | 'codex';
+  sessionId?: string;
+  toolName?: string;
+  decision?: string;
+  decidedBy?: ApprovalDecidedBy;
+  /** Unix milliseconds, inclusive */
+  since?: number;
+  /** Unix milliseconds, exclusive */
+  until?: number;
+  limit?: number;
+  offset?: number;
+}
+
+/** Recorded tool approvals and denials, newest first. */
+export async function queryApprovalAudit(query: ApprovalQuery = {}) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ApprovalRecord[]>('query_approval_audit', { query });
+  }
+  return await postJson<ApprovalRecord[]>('/api/audit/approvals', query);
+}
+
+export async function exportApprovalAuditCsv(query: ApprovalQuery = {}) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<string>('export_approval_audit_csv', { query });
+  }
+  return await postJson<string>('/api/audit/approvals/csv', query);
+}
@@ -1,25 +1,181 @@
 import { invokeTauri, isDesktopTauri, postNoContent, postJson, getJson } from './shared';
+import type { ThreadExport, ThreadExportOptions } from './codex';
 
-export async function ccNewSession(options: Record<string, unknown>) {
+export type CCMcpServerConfig =
+  | { type: 'stdio'; command: string; args?: string[]; env?: Record<string, string> }
+  | { type: 'http' | 'sse'; url: string; headers?: Record<string, string> };
+
+/** Options accepted by `ccConnect`, `ccNewSession` and `ccResumeSession`; validated by the backend. */
+export interface CCAgentOptions {
+  cwd: string;
+  model?: string;
+  permissionMode?: string;
+  fallbackModel?: string;
+  maxTurns?: number;
+  maxBudgetUsd?: number;
+  maxThinkingTokens?: number;
+  settings?: string;
+  allowedTools?: string[];
+  disallowedTools?: string[];
+  mcpServers?: Record<string, CCMcpServerConfig>;
+  resume?: string;
+  continueConversation?: boolean;
+  /** Replaces Claude Code's default system prompt */
+  systemPrompt?: string;
+  permissionTimeoutSecs?: number;
+  permissionFallback?: PermissionFallback;
+  /** Emit `cc-stream-event`s for partial messages; defaults to true */
+  includePartialMessages?: boolean;
+  /** Interrupt as soon as the live cost reaches `maxBudgetUsd` */
+  budgetGuard?: boolean;
+}
+
+/** Payload of `cc-usage` events and `ccGetSessionUsage`. */
+export interface CCSessionUsage {
+  inputTokens: number;
+  outputTokens: number;
+  cacheCreationInputTokens: number;
+  cacheReadInputTokens: number;
+  totalCostUsd: number;
+  /** True while a query runs and its cost is estimated from token counts */
+  costEstimated: boolean;
+}
+
+export async function ccGetSessionUsage(sessionId: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CCSessionUsage>('cc_get_session_usage', { sessionId });
+  }
+  return await postJson<CCSessionUsage>('/api/cc/session-usage', { session_id: sessionId });
+}
+
+/** Idle reaping and live-session cap for Claude CLI processes; 0 disables either. */
+export interface CCSessionLimits {
+  idleTimeoutSecs: number;
+  maxLiveSessions: number;
+  /** Used by sessions that pass no `permissionTimeoutSecs` / `permissionFallback` */
+  permissionTimeout?: PermissionTimeout;
+}
+
+export interface CCSessionStatus {
+  sessionId: string;
+  /** False once reaped; the next message resumes it */
+  live: boolean;
+  busy: boolean;
+  queued: number;
+  cwd: string;
+  /** Unix milliseconds */
+  lastActiveAt: number;
+}
+
+export interface CCStatus {
+  liveSessions: number;
+  dormantSessions: number;
+  busySessions: number;
+  pendingPermissions: number;
+  /** Null where processes cannot be inspected */
+  claudeProcesses: number | null;
+  claudeRssBytes: number | null;
+  appRssBytes: number | null;
+  limits: CCSessionLimits;
+  sessions: CCSessionStatus[];
+}
+
+export async function ccGetStatus() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CCStatus>('cc_get_status');
+  }
+  return await getJson<CCStatus>('/api/cc/status');
+}
+
+export async function ccGetSessionLimits() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CCSessionLimits>('cc_get_session_limits');
+  }
+  return await getJson<CCSessionLimits>('/api/cc/session-limits');
+}
+
+export async function ccSetSessionLimits(limits: CCSessionLimits) {
+  if (isDesktopTauri()) {
+    await invokeTauri('cc_set_session_limits', { limits });
+    return;
+  }
+  await postNoContent('/api/cc/session-limits', { limits });
+}
+
+export async function ccConnect(sessionId: string, options: CCAgentOptions, resumeId?: string) {
+  const params = { ...options, sessionId, resumeId };
+  if (isDesktopTauri()) {
+    await invokeTauri('cc_connect', { params });
+    return;
+  }
+  await postNoContent('/api/cc/connect', params);
+}
+
+export async function ccNewSession(options: CCAgentOptions) {
   if (isDesktopTauri()) {
     return await invokeTauri<string>('cc_new_session', { options });
   }
   return await postJson<string>('/api/cc/new-session', { options });
 }
 
-export async function ccSendMessage(sessionId: string, message: string, imagePaths: string[] = []) {
+export interface CCQueuedMessage {
+  id: string;
+  message: string;
+  imagePaths: string[];
+  /** Unix milliseconds */
+  queuedAt: number;
+}
+
+/**
+ * Queue a message; it is sent once earlier messages finish. `steer` interrupts
+ * the running exchange and sends this message next.
+ */
+export async function ccSendMessage(
+  sessionId: string,
+  message: string,
+  imagePaths: string[] = [],
+  steer = false
+) {
   const trimmed = message.trim();
   if (isDesktopTauri()) {
-    await invokeTauri('cc_send_message', { sessionId, message: trimmed, imagePaths });
-    return;
+    return await invokeTauri<CCQueuedMessage>('cc_send_message', {
+      sessionId,
+      message: trimmed,
+      imagePaths,
+      steer,
+    });
   }
-  await postNoContent('/api/cc/send-message', {
+  return await postJson<CCQueuedMessage>('/api/cc/send-message', {
     session_id: sessionId,
     message: trimmed,
     image_paths: imagePaths,
+    steer,
   });
 }
 
+export async function ccListQueue(sessionId: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CCQueuedMessage[]>('cc_list_queue', { sessionId });
+  }
+  return await postJson<CCQueuedMessage[]>('/api/cc/queue', { session_id: sessionId });
+}
+
+export async function ccCancelQueued(sessionId: string, messageId: string) {
+  if (isDesktopTauri()) {
+    await invokeTauri('cc_cancel_queued', { sessionId, messageId });
+    return;
+  }
+  await postNoContent('/api/cc/queue/cancel', { session_id: sessionId, message_id: messageId });
+}
+
+export async function ccReorderQueue(sessionId: string, messageIds: string[]) {
+  if (isDesktopTauri()) {
+    await invokeTauri('cc_reorder_queue', { sessionId, messageIds });
+    return;
+  }
+  await postNoContent('/api/cc/queue/reorder', { session_id: sessionId, message_ids: messageIds });
+}
+
 export async function ccInterrupt(sessionId: string) {
   if (isDesktopTauri()) {
     await invokeTauri('cc_interrupt', { sessionId });
@@ -28,7 +184,7 @@ export async function ccInterrupt(sessionId: string) {
   await postNoContent('/api/cc/interrupt', { session_id: sessionId });
 }
 
-export async function ccResumeSession(sessionId: string, options: Record<string, unknown>) {
+export async function ccResumeSession(sessionId: string, options: CCAgentOptions) {
   if (isDesktopTauri()) {
     await invokeTauri('cc_resume_session', { sessionId, options });
     return;
@@ -87,6 +243,92 @@ export async function ccListSessions<T = unknown>(directory?: string | null, opt
   return await getJson<CcSessionListResult<T>>(`/api/cc/sessions?${params.toString()}`);
 }
 
+export interface CCSessionSearchQuery {
+  /** Free text; when empty, matching sessions are listed newest first */
+  query?: string;
+  /** Sessions of this project or one of its worktrees */
+  cwd?: string;
+  gitBranch?: string;
+  tag?: string;
+  /** Unix milliseconds bounds on the last modification time */
+  from?: number;
+  to?: number;
+  limit?: number;
+}
+
+/** A session with its tags and, for text queries, the best matching segment. */
+export type CCSessionSearchHit<T = unknown> = T & {
+  tags: string[];
+  kind: string | null;
+  snippet: string | null;
+};
+
+export async function ccSearchSessions<T = unknown>(query: CCSessionSearchQuery) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CCSessionSearchHit<T>[]>('cc_search_sessions', { query });
+  }
+  return await postJson<CCSessionSearchHit<T>[]>('/api/cc/sessions/search', query);
+}
+
+/** Rename a session; written to the transcript so Claude Code shows it too. */
+export async function ccSetSessionTitle(sessionId: string, title: string) {
+  if (isDesktopTauri()) {
+    await invokeTauri('cc_set_session_title', { sessionId, title });
+    return;
+  }
+  await postNoContent('/api/cc/session/title', { session_id: sessionId, title });
+}
+
+/** Replace a session's tags; returns them trimmed and deduplicated. */
+export async function ccSetSessionTags(sessionId: string, tags: string[]) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<string[]>('cc_set_session_tags', { sessionId, tags });
+  }
+  return await postJson<string[]>('/api/cc/session/tags', { session_id: sessionId, tags });
+}
+
+export interface CCForkedSession {
+  sessionId: string;
+  forkedFrom: string;
+  path: string;
+}
+
+export interface CCRewindResult {
+  sessionId: string;
+  removedRecords: number;
+  backupDir: string;
+  revertedFiles: string[];
+  conflicts: string[];
+  skipped: string[];
+}
+
+/** Copy a session up to and including `messageUuid` into a new session. */
+export async function ccForkSession(sessionId: string, messageUuid: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CCForkedSession>('cc_fork_session', { sessionId, messageUuid });
+  }
+  return await postJson<CCForkedSession>('/api/cc/session/fork', {
+    session_id: sessionId,
+    message_uuid: messageUuid,
+  });
+}
+
+/** Truncate a session after `messageUuid`, optionally undoing later file edits. */
+export async function ccRewindSession(sessionId: string, messageUuid: string, revertFiles = false) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CCRewindResult>('cc_rewind_session', {
+      sessionId,
+      messageUuid,
+      revertFiles,
+    });
+  }
+  return await postJson<CCRewindResult>('/api/cc/session/rewind', {
+    session_id: sessionId,
+    message_uuid: messageUuid,
+    revert_files: revertFiles,
+  });
+}
+
 export async function ccGetSettings<T = unknown>() {
   if (isDesktopTauri()) {
     return await invokeTauri<T>('cc_get_settings');
@@ -178,6 +420,78 @@ export async function ccGetSessionMessages(sessionId: string): Promise<SdkSessio
   return await postJson<SdkSessionMessage[]>('/api/cc/session-messages', { session_id: sessionId });
 }
 
+export async function ccExportSession(sessionId: string, options: ThreadExportOptions = {}) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ThreadExport>('cc_export_session', { sessionId, options });
+  }
+  return await postJson<ThreadExport>('/api/cc/session/export', { session_id: sessionId, options });
+}
+
+export interface ImportedCcSession {
+  sessionId: string;
+  cwd: string;
+  path: string;
+}
+
+/** Import a JSONL transcript into ~/.claude/projects so it can be resumed. */
+export async function ccImportSession(content: string, cwd?: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ImportedCcSession>('cc_import_session', { content, cwd: cwd ?? null });
+  }
+  return await postJson<ImportedCcSession>('/api/cc/session/import', { content, cwd: cwd ?? null });
+}
+
+export type PermissionRuleKind = 'allow' | 'deny' | 'ask';
+
+export interface ProjectPermissionRules {
+  allow: string[];
+  deny: string[];
+  ask: string[];
+}
+
+/** Rules in `{cwd}/.claude/settings.json`, e.g. `Bash(git status:*)`, `Edit(src/**)`. */
+export async function ccGetPermissionRules(cwd: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ProjectPermissionRules>('cc_get_permission_rules', { cwd });
+  }
+  return await postJson<ProjectPermissionRules>('/api/cc/permission-rules', { cwd });
+}
+
+export async function ccAddPermissionRule(cwd: string, kind: PermissionRuleKind, rule: string) {
+  if (isDesktopTauri()) {
+    await invokeTauri('cc_add_permission_rule', { cwd, kind, rule });
+    return;
+  }
+  await postNoContent('/api/cc/permission-rules/add', { cwd, kind, rule });
+}
+
+export async function ccRemovePermissionRule(cwd: string, kind: PermissionRuleKind, rule: string) {
+  if (isDesktopTauri()) {
+    await invokeTauri('cc_remove_permission_rule', { cwd, kind, rule });
+    return;
+  }
+  await postNoContent('/api/cc/permission-rules/remove', { cwd, kind, rule });
+}
+
+export interface SensitivePathPolicy {
+  patterns: string[];
+}
+
+export async function ccGetSensitivePathPolicy() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<SensitivePathPolicy>('cc_get_sensitive_path_policy');
+  }
+  return await getJson<SensitivePathPolicy>('/api/cc/sensitive-paths');
+}
+
+export async function ccSetSensitivePathPolicy(policy: SensitivePathPolicy) {
+  if (isDesktopTauri()) {
+    await invokeTauri('cc_set_sensitive_path_policy', { policy });
+    return;
+  }
+  await postNoContent('/api/cc/sensitive-paths', { policy });
+}
+
 export async function ccResolvePermission(requestId: string, decision: string): Promise<void> {
   if (isDesktopTauri()) {
     return invokeTauri('cc_resolve_permission', { requestId, decision });
@@ -185,6 +499,30 @@ export async function ccResolvePermission(requestId: string, decision: string):
   await postNoContent('/api/cc/resolve-permission', { request_id: requestId, decision });
 }
 
+export type PermissionFallback = 'deny' | 'allowReadOnly' | 'allow';
+
+export interface PermissionTimeout {
+  /** Seconds to wait for a decision; `null` waits indefinitely */
+  timeoutSecs: number | null;
+  fallback: PermissionFallback;
+}
+
+/** Outstanding `cc-permission-request` payloads, oldest first. */
+export async function ccPendingPermissions(sessionId?: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<Record<string, unknown>[]>('cc_pending_permissions', { sessionId });
+  }
+  return await postJson<Record<string, unknown>[]>('/api/cc/pending-permissions', { session_id: sessionId });
+}
+
+export async function ccSetPermissionTimeout(sessionId: string, timeout: PermissionTimeout) {
+  if (isDesktopTauri()) {
+    await invokeTauri('cc_set_permission_timeout', { sessionId, timeout });
+    return;
+  }
+  await postNoContent('/api/cc/set-permission-timeout', { session_id: sessionId, timeout });
+}
+
 export async function ccSetPermissionMode(sessionId: string, mode: string) {
   if (isDesktopTauri()) {
     await invokeTauri('cc_set_permission_mode', { sessionId, mode });
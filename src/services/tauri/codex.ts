@@ -41,6 +41,7 @@ import {
 } from './shared';
 import type { FrontendProviderModels, EnvStatusItem } from '@/components/codex/selector/ModelList';
 export * from './mcp';
+export * from './codexConfig';
 export * from './skills';
 
 export async function initializeCodexAsync() {
@@ -116,6 +117,55 @@ export async function threadListArchived(params: ThreadListParams) {
   return await postJson<ThreadListResponse>('/api/codex/thread/list-archived', params);
 }
 
+export interface CodexSearchQuery {
+  query: string;
+  cwd?: string | null;
+  /** Unix seconds, inclusive */
+  from?: number | null;
+  to?: number | null;
+  archived?: boolean | null;
+  limit?: number | null;
+}
+
+export interface CodexSearchHit {
+  path: string;
+  thread_id: string;
+  cwd: string;
+  preview: string;
+  created_at: number;
+  updated_at: number;
+  archived: boolean;
+  /** user | assistant | command | file */
+  kind: string;
+  snippet: string;
+}
+
+export async function threadSearch(query: CodexSearchQuery) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CodexSearchHit[]>('search_threads', { query });
+  }
+  return await postJson<CodexSearchHit[]>('/api/codex/thread/search', query);
+}
+
+export interface ThreadExportOptions {
+  format?: 'markdown' | 'html' | 'json' | 'jsonl';
+  redactPaths?: boolean;
+  redactSecrets?: boolean;
+}
+
+export interface ThreadExport {
+  fileName: string;
+  mimeType: string;
+  content: string;
+}
+
+export async function threadExport(path: string, options: ThreadExportOptions = {}) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ThreadExport>('export_thread', { path, options });
+  }
+  return await postJson<ThreadExport>('/api/codex/thread/export', { path, options });
+}
+
 export async function threadArchive(threadId: ThreadId) {
   if (isDesktopTauri()) {
     return await invokeTauri('archive_thread', { threadId });
@@ -173,6 +223,50 @@ export async function getAccountRateLimits() {
   return await getJson<GetAccountRateLimitsResponse>('/api/codex/account/rate-limits');
 }
 
+export interface RateLimitSample {
+  recorded_at: number;
+  limit_id: string;
+  window: 'primary' | 'secondary';
+  used_percent: number;
+  window_duration_mins: number | null;
+  resets_at: number | null;
+  plan_type: string | null;
+}
+
+export interface RateLimitAlertSettings {
+  /** Percent-used levels that emit `codex:rateLimitWarning` */
+  thresholds: number[];
+  /** Scheduled codex automations are deferred at or above this percent */
+  defer_automations_at: number | null;
+}
+
+/** Recorded rate-limit samples, oldest first. `since` is Unix milliseconds. */
+export async function getRateLimitHistory(limitId?: string, since?: number, limit?: number) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<RateLimitSample[]>('rate_limit_history', { limitId, since, limit });
+  }
+  return await postJson<RateLimitSample[]>('/api/codex/account/rate-limits/history', {
+    limit_id: limitId,
+    since,
+    limit,
+  });
+}
+
+export async function readRateLimitAlerts() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<RateLimitAlertSettings>('read_rate_limit_alerts');
+  }
+  return await postJson<RateLimitAlertSettings>('/api/codex/account/rate-limits/alerts/read', {});
+}
+
+export async function writeRateLimitAlerts(settings: RateLimitAlertSettings) {
+  if (isDesktopTauri()) {
+    await invokeTauri('write_rate_limit_alerts', { settings });
+    return;
+  }
+  await postNoContent('/api/codex/account/rate-limits/alerts/write', settings);
+}
+
 export async function respondToRequestUserInput(
   requestId: RequestId,
   response: unknown
@@ -226,11 +320,13 @@ export async function allowSleep(conversationId?: string | null) {
   await postNoContent('/api/sleep/allow', { conversation_id: conversationId ?? null });
 }
 
-export async function listOtherModels() {
+export async function listOtherModels(refresh = false) {
   if (isDesktopTauri()) {
-    return await invokeTauri<FrontendProviderModels[]>('list_other_models');
+    return await invokeTauri<FrontendProviderModels[]>('list_other_models', { refresh });
   }
-  return await getJson<FrontendProviderModels[]>('/api/codex/model/list-other');
+  return await getJson<FrontendProviderModels[]>(
+    `/api/codex/model/list-other${refresh ? '?refresh=true' : ''}`
+  );
 }
 
 export async function loadEnvKeys() {
@@ -240,9 +336,110 @@ export async function loadEnvKeys() {
   return null;
 }
 
+export interface ModelProviderConfig {
+  model_provider: string;
+  base_url: string;
+  api_key_url?: string;
+  signup_url?: string;
+  /** Env var holding the API key; empty when the provider needs none */
+  env_key?: string;
+  auto_discover?: boolean;
+  models?: { id: string; context_length?: number }[];
+  headers?: Record<string, string>;
+}
+
+export interface ModelProviderEntry extends ModelProviderConfig {
+  source: 'bundled' | 'user' | 'override';
+}
+
+export async function listModelProviders() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ModelProviderEntry[]>('list_model_providers');
+  }
+  return await postJson<ModelProviderEntry[]>('/api/codex/providers/list', {});
+}
+
+export async function upsertModelProvider(provider: ModelProviderConfig) {
+  if (isDesktopTauri()) {
+    await invokeTauri('upsert_model_provider', { provider });
+    return;
+  }
+  await postNoContent('/api/codex/providers/upsert', provider);
+}
+
+export async function deleteModelProvider(modelProvider: string) {
+  if (isDesktopTauri()) {
+    await invokeTauri('delete_model_provider', { modelProvider });
+    return;
+  }
+  await postNoContent('/api/codex/providers/delete', { model_provider: modelProvider });
+}
+
+export interface ProviderEndpointCheck {
+  url: string;
+  status: number | null;
+  latency_ms: number;
+  ok: boolean;
+  error: string | null;
+}
+
+export interface ProviderTestResult {
+  provider: string;
+  base_url: string;
+  env_key: string;
+  api_key_set: boolean;
+  model: string | null;
+  model_found: boolean | null;
+  models: ProviderEndpointCheck;
+  completion: ProviderEndpointCheck | null;
+}
+
+/** List the provider's models and send a one-token completion, reporting each step. */
+export async function testModelProvider(
+  modelProvider: string,
+  model?: string,
+  timeoutSecs?: number
+) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ProviderTestResult>('test_model_provider', {
+      modelProvider,
+      model,
+      timeoutSecs,
+    });
+  }
+  return await postJson<ProviderTestResult>('/api/codex/providers/test', {
+    model_provider: modelProvider,
+    model,
+    timeout_secs: timeoutSecs,
+  });
+}
+
 export async function setEnv(key: string, value: string) {
   if (isDesktopTauri()) {
     return await invokeTauri('set_env', { key, value });
   }
   return null
-}
\ No newline at end of file
+}
+
+export type SecretBackend = 'secretService' | 'encryptedFile';
+
+export interface StoredSecret {
+  name: string;
+  backend: SecretBackend;
+}
+
+/** Names of API keys kept in the secret store; values are never returned. */
+export async function listSecrets() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<StoredSecret[]>('list_secrets');
+  }
+  return await postJson<StoredSecret[]>('/api/codex/secrets/list', {});
+}
+
+export async function deleteSecret(name: string) {
+  if (isDesktopTauri()) {
+    await invokeTauri('delete_secret', { name });
+    return;
+  }
+  await postNoContent('/api/codex/secrets/delete', { name });
+}
@@ -0,0 +1,122 @@
+import { invokeTauri, isDesktopTauri, postJson, postNoContent } from './shared';
+
+// Reads may also return a value a newer codex added; it is passed back as written.
+export type ReasoningEffort = 'none' | 'minimal' | 'low' | 'medium' | 'high' | 'xhigh';
+export type SandboxMode = 'read-only' | 'workspace-write' | 'danger-full-access';
+export type ApprovalPolicy = 'untrusted' | 'on-failure' | 'on-request' | 'never';
+export type TrustLevel = 'trusted' | 'untrusted';
+
+export interface SandboxWorkspaceWrite {
+  writable_roots?: string[];
+  network_access?: boolean | null;
+  exclude_tmpdir_env_var?: boolean | null;
+  exclude_slash_tmp?: boolean | null;
+}
+
+export interface ShellEnvironmentPolicy {
+  inherit?: 'all' | 'core' | 'none' | null;
+  ignore_default_excludes?: boolean | null;
+  exclude?: string[];
+  include_only?: string[];
+  set?: Record<string, string>;
+}
+
+export interface CodexSettings {
+  model?: string | null;
+  model_provider?: string | null;
+  model_reasoning_effort?: ReasoningEffort | null;
+  approval_policy?: ApprovalPolicy | null;
+  sandbox_mode?: SandboxMode | null;
+  sandbox_workspace_write?: SandboxWorkspaceWrite | null;
+  shell_environment_policy?: ShellEnvironmentPolicy | null;
+  /** Active `[profiles.*]` entry */
+  profile?: string | null;
+}
+
+export interface CodexProfile {
+  model?: string | null;
+  model_provider?: string | null;
+  model_reasoning_effort?: ReasoningEffort | null;
+  approval_policy?: ApprovalPolicy | null;
+  sandbox_mode?: SandboxMode | null;
+}
+
+export async function readCodexSettings() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<CodexSettings>('read_codex_settings');
+  }
+  return await postJson<CodexSettings>('/api/codex/config/settings/read', {});
+}
+
+export async function writeCodexSettings(settings: CodexSettings) {
+  if (isDesktopTauri()) {
+    await invokeTauri('write_codex_settings', { settings });
+    return;
+  }
+  await postNoContent('/api/codex/config/settings/write', { settings });
+}
+
+export async function listCodexProfiles() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<Record<string, CodexProfile>>('list_codex_profiles');
+  }
+  return await postJson<Record<string, CodexProfile>>('/api/codex/config/profiles/list', {});
+}
+
+export async function createCodexProfile(name: string, profile: CodexProfile) {
+  if (isDesktopTauri()) {
+    await invokeTauri('create_codex_profile', { name, profile });
+    return;
+  }
+  await postNoContent('/api/codex/config/profiles/create', { name, profile });
+}
+
+export async function updateCodexProfile(name: string, profile: CodexProfile) {
+  if (isDesktopTauri()) {
+    await invokeTauri('update_codex_profile', { name, profile });
+    return;
+  }
+  await postNoContent('/api/codex/config/profiles/update', { name, profile });
+}
+
+export async function cloneCodexProfile(source: string, target: string) {
+  if (isDesktopTauri()) {
+    await invokeTauri('clone_codex_profile', { source, target });
+    return;
+  }
+  await postNoContent('/api/codex/config/profiles/clone', { source, target });
+}
+
+export async function deleteCodexProfile(name: string) {
+  if (isDesktopTauri()) {
+    await invokeTauri('delete_codex_profile', { name });
+    return;
+  }
+  await postNoContent('/api/codex/config/profiles/delete', { name });
+}
+
+export async function setActiveCodexProfile(name: string | null) {
+  if (isDesktopTauri()) {
+    await invokeTauri('set_active_codex_profile', { name });
+    return;
+  }
+  await postNoContent('/api/codex/config/profiles/activate', { name });
+}
+
+export async function readCodexProjectTrust() {
+  if (isDesktopTauri()) {
+    return await invokeTauri<Record<string, TrustLevel>>('read_codex_project_trust');
+  }
+  return await postJson<Record<string, TrustLevel>>('/api/codex/config/projects/trust/read', {});
+}
+
+export async function setCodexProjectTrust(path: string, trustLevel: TrustLevel | null) {
+  if (isDesktopTauri()) {
+    await invokeTauri('set_codex_project_trust', { path, trustLevel });
+    return;
+  }
+  await postNoContent('/api/codex/config/projects/trust/write', {
+    path,
+    trust_level: trustLevel,
+  });
+}
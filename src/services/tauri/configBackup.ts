@@ -0,0 +1,38 @@
+import { invokeTauri, isDesktopTauri, postJson } from './shared';
+
+export interface ConfigBackup {
+  id: string;
+  /** Config file the snapshot was taken from */
+  path: string;
+  /** Unix milliseconds */
+  createdAt: number;
+  size: number;
+}
+
+export interface ConfigBackupDiff {
+  path: string;
+  oldContent: string;
+  newContent: string;
+  hasChanges: boolean;
+}
+
+export async function listConfigBackups(path?: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ConfigBackup[]>('list_config_backups', { path });
+  }
+  return await postJson<ConfigBackup[]>('/api/config/backups/list', { path });
+}
+
+export async function diffConfigBackup(id: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ConfigBackupDiff>('diff_config_backup', { id });
+  }
+  return await postJson<ConfigBackupDiff>('/api/config/backups/diff', { id });
+}
+
+export async function restoreConfigBackup(id: string) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<ConfigBackup>('restore_config_backup', { id });
+  }
+  return await postJson<ConfigBackup>('/api/config/backups/restore', { id });
+}
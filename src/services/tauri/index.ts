@@ -13,8 +13,10 @@ export {
 } from './shared';
 
 export * from './cc';
+export * from './approvalAudit';
 export * from './automation';
 export * from './codex';
+export * from './configBackup';
 export * from './dxt';
 export * from './filesystem';
 export * from './git';
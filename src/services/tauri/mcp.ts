@@ -1,6 +1,7 @@
 import {
   type UnifiedMcpClientName,
   type UnifiedMcpConfig,
+  getJson,
   invokeTauri,
   isDesktopTauri,
   postJson,
@@ -86,3 +87,130 @@ export async function unifiedDisableMcpServer(params: {
     server_name: params.serverName,
   });
 }
+
+export type McpTarget = 'codex' | 'ccGlobal' | 'ccProject' | 'ccLocal';
+
+export interface McpCatalogServer {
+  type: 'stdio' | 'http' | 'sse';
+  url?: string;
+  command?: string;
+  args?: string[];
+  env?: Record<string, string>;
+  headers?: Record<string, string>;
+}
+
+export interface McpCatalog {
+  servers: Record<string, McpCatalogServer>;
+}
+
+export interface McpTargetState {
+  target: McpTarget;
+  status: 'inSync' | 'differs' | 'missing' | 'untracked' | 'absent';
+  enabled?: boolean;
+  config?: McpCatalogServer;
+  differences: { field: string; catalog: unknown; target: unknown }[];
+  /**
+   * Catalog fields the target cannot store (env on a codex http server), and
+   * target settings the catalog cannot store (a codex bearer_token_env_var).
+   */
+  unsupported: string[];
+}
+
+export interface McpCatalogStatus {
+  targets: McpTarget[];
+  servers: { name: string; catalog?: McpCatalogServer; targets: McpTargetState[] }[];
+}
+
+export interface McpSyncRequest {
+  direction: 'pull' | 'push';
+  target: McpTarget;
+  path?: string;
+  servers?: string[];
+  prune?: boolean;
+}
+
+export interface McpSyncResult {
+  written: string[];
+  removed: string[];
+  skipped: { name: string; reason: string }[];
+}
+
+export async function mcpCatalogRead(): Promise<McpCatalog> {
+  if (isDesktopTauri()) {
+    return await invokeTauri<McpCatalog>('mcp_catalog_read');
+  }
+  return await getJson<McpCatalog>('/api/codex/mcp/catalog');
+}
+
+export async function mcpCatalogWrite(catalog: McpCatalog) {
+  if (isDesktopTauri()) {
+    await invokeTauri('mcp_catalog_write', { catalog });
+    return;
+  }
+  await postNoContent('/api/codex/mcp/catalog/write', { catalog });
+}
+
+/** Compare the catalog with codex and each Claude scope; project scopes need `path`. */
+export async function mcpCatalogStatus(path?: string): Promise<McpCatalogStatus> {
+  if (isDesktopTauri()) {
+    return await invokeTauri<McpCatalogStatus>('mcp_catalog_status', { path });
+  }
+  return await postJson<McpCatalogStatus>('/api/codex/mcp/catalog/status', { path });
+}
+
+export async function mcpCatalogSync(request: McpSyncRequest): Promise<McpSyncResult> {
+  if (isDesktopTauri()) {
+    return await invokeTauri<McpSyncResult>('mcp_catalog_sync', { request });
+  }
+  return await postJson<McpSyncResult>('/api/codex/mcp/catalog/sync', { request });
+}
+
+export interface McpListItem {
+  name: string;
+  description?: string;
+  uri?: string;
+}
+
+export interface McpHealth {
+  name: string;
+  status: 'healthy' | 'failed' | 'timed_out';
+  server_name: string | null;
+  server_version: string | null;
+  protocol_version: string | null;
+  tools: McpListItem[];
+  prompts: McpListItem[];
+  resources: McpListItem[];
+  error: string | null;
+  /** Tail of a stdio server's stderr when the probe did not succeed. */
+  stderr: string | null;
+  latency_ms: number;
+  checked_at: number;
+}
+
+/** Health of servers in codex config.toml; cached results are reused unless `refresh`. */
+export async function checkMcpServers(names?: string[], refresh = false, timeoutSecs?: number) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<McpHealth[]>('check_mcp_servers', { names, refresh, timeoutSecs });
+  }
+  return await postJson<McpHealth[]>('/api/codex/mcp/health', {
+    names,
+    refresh,
+    timeout_secs: timeoutSecs,
+  });
+}
+
+/** Probe a server config that need not be installed yet. */
+export async function probeMcpServer(serverName: string, serverConfig: unknown, timeoutSecs?: number) {
+  if (isDesktopTauri()) {
+    return await invokeTauri<McpHealth>('probe_mcp_server', {
+      serverName,
+      serverConfig,
+      timeoutSecs,
+    });
+  }
+  return await postJson<McpHealth>('/api/codex/mcp/probe', {
+    server_name: serverName,
+    server_config: serverConfig,
+    timeout_secs: timeoutSecs,
+  });
+}
@@ -35,6 +35,7 @@ export interface ClaudeCodeMcpServer {
   command?: string;
   args?: string[];
   env?: Record<string, string>;
+  headers?: Record<string, string>;
   scope: 'global' | 'project' | 'local';
   enabled?: boolean;
 }
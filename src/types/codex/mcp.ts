@@ -1,21 +1,37 @@
-type StdioMcpServerConfig = {
+type McpTimeouts = {
+  startup_timeout_sec?: number;
+  tool_timeout_sec?: number;
+};
+
+type StdioMcpServerConfig = McpTimeouts & {
   type?: 'stdio';
   command: string;
   args: string[];
   env?: Record<string, string>;
+  cwd?: string;
   enabled?: boolean;
 };
 
-type HttpMcpServerConfig = {
-  type: 'http';
-  url: string;
-  enabled?: boolean;
+type McpHttpAuth = {
+  http_headers?: Record<string, string>;
+  /** Header name to the environment variable holding its value. */
+  env_http_headers?: Record<string, string>;
+  /** Environment variable holding a token sent as `Authorization: Bearer`. */
+  bearer_token_env_var?: string;
 };
 
-type SseMcpServerConfig = {
-  type: 'sse';
-  url: string;
-  enabled?: boolean;
-};
+type HttpMcpServerConfig = McpTimeouts &
+  McpHttpAuth & {
+    type: 'http';
+    url: string;
+    enabled?: boolean;
+  };
+
+type SseMcpServerConfig = McpTimeouts &
+  McpHttpAuth & {
+    type: 'sse';
+    url: string;
+    enabled?: boolean;
+  };
 
 export type McpServerConfig = StdioMcpServerConfig | HttpMcpServerConfig | SseMcpServerConfig;
@@ -0,0 +1,25 @@
+use super::to_error_response;
+use axum::Json;
+
+use codexia_db::approval_audit::{self, ApprovalQuery, ApprovalRecord};
+use crate::types::ErrorResponse;
+
+pub(crate) async fn api_query_approval_audit(
+    Json(query): Json<ApprovalQuery>,
+) -> Result<Json<Vec<ApprovalRecord>>, ErrorResponse> {
+    let records = tokio::task::spawn_blocking(move || approval_audit::query(&query))
+        .await
+        .map_err(to_error_response)?
+        .map_err(to_error_response)?;
+    Ok(Json(records))
+}
+
+pub(crate) async fn api_export_approval_audit_csv(
+    Json(query): Json<ApprovalQuery>,
+) -> Result<Json<String>, ErrorResponse> {
+    let csv = tokio::task::spawn_blocking(move || approval_audit::export_csv(&query))
+        .await
+        .map_err(to_error_response)?
+        .map_err(to_error_response)?;
+    Ok(Json(csv))
+}
@@ -2,8 +2,11 @@ use std::sync::Arc;
 use super::to_error_response;
 use super::types::{
     CcMcpAddParams, CcMcpGetParams, CcMcpListParams, CcMcpRemoveParams, CcMcpToggleParams,
-    CcGetSessionsParams, CcNewSessionParams, CcResolvePermissionParams, CcResumeSessionParams,
-    CcSendMessageParams, CcSessionIdParams, CcSetPermissionModeParams, CcUpdateSettingsParams,
+    CcExportSessionParams, CcForkSessionParams, CcGetSessionsParams, CcImportSessionParams, CcNewSessionParams,
+    CcPendingPermissionsParams, CcPermissionRuleParams, CcQueuedMessageParams, CcReorderQueueParams, CcPermissionRulesParams,
+    CcSensitivePathPolicyParams, CcResolvePermissionParams, CcResumeSessionParams, CcRewindSessionParams,
+    CcSendMessageParams, CcSessionIdParams, CcSessionLimitsParams, CcSetSessionTagsParams, CcSetSessionTitleParams, CcSetPermissionModeParams, CcSetPermissionTimeoutParams,
+    CcUpdateSettingsParams,
 };
 use axum::{Json, extract::State as AxumState, http::StatusCode};
 use serde_json::Value;
@@ -14,8 +17,15 @@ use codexia_cc::services::{
     message_service as cc_message_service, session_service as cc_session_service, settings_service as cc_settings_service,
     skill_service as cc_skill_service,
 };
+use codexia_cc::services::permission_storage;
+use codexia_cc::services::sensitive_paths;
+use codexia_cc::services::session_lifecycle::{self, CCStatus};
+use codexia_cc::services::usage_service::SessionUsage;
+use codexia_cc::state::QueuedMessage;
+use codexia_cc::transcript;
 use codexia_cc::types::CCConnectParams;
 use codexia_cc::services::session_service::SessionListResult;
+use codexia_cc::db::{SessionSearchHit, SessionSearchQuery};
 
 pub(crate) async fn api_cc_connect(
     AxumState(state): AxumState<WebServerState>,
@@ -30,14 +40,15 @@ pub(crate) async fn api_cc_connect(
 pub(crate) async fn api_cc_send_message(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<CcSendMessageParams>,
-) -> Result<StatusCode, ErrorResponse> {
+) -> Result<Json<QueuedMessage>, ErrorResponse> {
     let sid = params.session_id.clone();
     let cc_state = Arc::clone(&state.cc_state);
 
-    cc_message_service::send_message(
+    let queued = cc_message_service::send_message(
         &params.session_id,
         &params.message,
         &params.image_paths,
+        params.steer,
         state.cc_state.as_ref(),
         move |msg| match serde_json::to_value(msg) {
             Ok(mut payload) => {
@@ -56,6 +67,31 @@ pub(crate) async fn api_cc_send_message(
     .await
     .map_err(to_error_response)?;
 
+    Ok(Json(queued))
+}
+
+pub(crate) async fn api_cc_list_queue(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<CcSessionIdParams>,
+) -> Json<Vec<QueuedMessage>> {
+    Json(cc_message_service::list_queue(&params.session_id, state.cc_state.as_ref()))
+}
+
+pub(crate) async fn api_cc_cancel_queued(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<CcQueuedMessageParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    cc_message_service::cancel_queued(&params.session_id, &params.message_id, state.cc_state.as_ref())
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_cc_reorder_queue(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<CcReorderQueueParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    cc_message_service::reorder_queue(&params.session_id, &params.message_ids, state.cc_state.as_ref())
+        .map_err(to_error_response)?;
     Ok(StatusCode::OK)
 }
 
@@ -92,6 +128,29 @@ pub(crate) async fn api_cc_interrupt(
     Ok(StatusCode::OK)
 }
 
+pub(crate) async fn api_cc_session_usage(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<CcSessionIdParams>,
+) -> Json<SessionUsage> {
+    Json(state.cc_state.get_usage(&params.session_id))
+}
+
+pub(crate) async fn api_cc_status(AxumState(state): AxumState<WebServerState>) -> Json<CCStatus> {
+    Json(session_lifecycle::status(state.cc_state.as_ref()).await)
+}
+
+pub(crate) async fn api_cc_get_session_limits() -> Result<Json<Value>, ErrorResponse> {
+    let limits = session_lifecycle::read_limits().map_err(to_error_response)?;
+    Ok(Json(serde_json::json!(limits)))
+}
+
+pub(crate) async fn api_cc_set_session_limits(
+    Json(params): Json<CcSessionLimitsParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    session_lifecycle::write_limits(params.limits).map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
 pub(crate) async fn api_cc_resume_session(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<CcResumeSessionParams>,
@@ -145,6 +204,55 @@ pub(crate) async fn api_cc_list_sessions(
     Ok(Json(result))
 }
 
+pub(crate) async fn api_cc_search_sessions(
+    Json(query): Json<SessionSearchQuery>,
+) -> Result<Json<Vec<SessionSearchHit>>, ErrorResponse> {
+    // Without a watcher, searching first re-reads every changed transcript.
+    let hits = tokio::task::spawn_blocking(move || cc_session_service::search_sessions(&query))
+        .await
+        .map_err(to_error_response)?
+        .map_err(to_error_response)?;
+    Ok(Json(hits))
+}
+
+pub(crate) async fn api_cc_set_session_title(
+    Json(params): Json<CcSetSessionTitleParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    cc_session_service::set_session_title(&params.session_id, &params.title).map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_cc_set_session_tags(
+    Json(params): Json<CcSetSessionTagsParams>,
+) -> Result<Json<Vec<String>>, ErrorResponse> {
+    let tags = cc_session_service::set_session_tags(&params.session_id, &params.tags)
+        .map_err(to_error_response)?;
+    Ok(Json(tags))
+}
+
+pub(crate) async fn api_cc_fork_session(
+    Json(params): Json<CcForkSessionParams>,
+) -> Result<Json<codexia_cc::rewind::ForkedSession>, ErrorResponse> {
+    let forked = cc_session_service::fork_session(&params.session_id, &params.message_uuid)
+        .map_err(to_error_response)?;
+    Ok(Json(forked))
+}
+
+pub(crate) async fn api_cc_rewind_session(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<CcRewindSessionParams>,
+) -> Result<Json<codexia_cc::rewind::RewindResult>, ErrorResponse> {
+    let result = cc_session_service::rewind_session(
+        &params.session_id,
+        &params.message_uuid,
+        params.revert_files.unwrap_or(false),
+        state.cc_state.as_ref(),
+    )
+    .await
+    .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
 pub(crate) async fn api_cc_delete_session(
     Json(params): Json<CcSessionIdParams>,
 ) -> Result<StatusCode, ErrorResponse> {
@@ -163,6 +271,61 @@ pub(crate) async fn api_cc_get_session_messages(
     Ok(Json(messages))
 }
 
+pub(crate) async fn api_cc_get_permission_rules(
+    Json(params): Json<CcPermissionRulesParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let rules = permission_storage::get_project_rules(&params.cwd).map_err(to_error_response)?;
+    Ok(Json(serde_json::json!(rules)))
+}
+
+pub(crate) async fn api_cc_add_permission_rule(
+    Json(params): Json<CcPermissionRuleParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    permission_storage::add_project_rule(&params.cwd, params.kind, &params.rule)
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_cc_remove_permission_rule(
+    Json(params): Json<CcPermissionRuleParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    permission_storage::remove_project_rule(&params.cwd, params.kind, &params.rule)
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_cc_get_sensitive_path_policy() -> Result<Json<Value>, ErrorResponse> {
+    let policy = sensitive_paths::read_policy().map_err(to_error_response)?;
+    Ok(Json(serde_json::json!(policy)))
+}
+
+pub(crate) async fn api_cc_set_sensitive_path_policy(
+    Json(params): Json<CcSensitivePathPolicyParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    sensitive_paths::write_policy(params.policy).map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_cc_export_session(
+    Json(params): Json<CcExportSessionParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let export = transcript::export_session(&params.session_id, &params.options)
+        .map_err(to_error_response)?;
+    Ok(Json(serde_json::json!(export)))
+}
+
+pub(crate) async fn api_cc_import_session(
+    Json(params): Json<CcImportSessionParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let imported = tokio::task::spawn_blocking(move || {
+        transcript::import_session(&params.content, params.cwd.as_deref())
+    })
+    .await
+    .map_err(to_error_response)?
+    .map_err(to_error_response)?;
+    Ok(Json(serde_json::json!(imported)))
+}
+
 pub(crate) async fn api_cc_resolve_permission(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<CcResolvePermissionParams>,
@@ -174,6 +337,30 @@ pub(crate) async fn api_cc_resolve_permission(
     Ok(StatusCode::OK)
 }
 
+pub(crate) async fn api_cc_pending_permissions(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<CcPendingPermissionsParams>,
+) -> Result<Json<Vec<Value>>, ErrorResponse> {
+    Ok(Json(
+        state
+            .cc_state
+            .list_pending_permissions(params.session_id.as_deref()),
+    ))
+}
+
+pub(crate) async fn api_cc_set_permission_timeout(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<CcSetPermissionTimeoutParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    cc_session_service::set_permission_timeout(
+        &params.session_id,
+        params.timeout,
+        state.cc_state.as_ref(),
+    )
+    .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
 pub(crate) async fn api_cc_set_permission_mode(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<CcSetPermissionModeParams>,
@@ -1,7 +1,11 @@
 use super::to_error_response;
 use super::types::{
-    CommandExecutionApprovalParams, FileChangeApprovalParams, ListThreadsRequest,
-    UnifiedMcpAddParams, UnifiedMcpReadParams, UnifiedMcpRemoveParams, UnifiedMcpToggleParams,
+    CodexActiveProfileParams, CodexProfileCloneParams, CodexProfileNameParams, CodexProfileParams,
+    CodexProjectTrustParams, CodexSettingsWriteParams, CommandExecutionApprovalParams,
+    DeleteModelProviderParams,
+    ExportThreadParams, FileChangeApprovalParams, ListThreadsRequest, RateLimitHistoryParams,
+    TestModelProviderParams,
+    McpCatalogStatusParams, McpCatalogSyncParams, McpCatalogWriteParams, McpHealthParams, McpProbeParams, UnifiedMcpAddParams, UnifiedMcpReadParams, UnifiedMcpRemoveParams, UnifiedMcpToggleParams,
     UserInputResponseParams,
 };
 use axum::{Json, extract::State as AxumState, http::StatusCode};
@@ -15,8 +19,20 @@ use serde_json::{Value, json};
 use crate::types::{ErrorResponse, WebServerState};
 
 use codexia_codex::scan::{list_archived_threads_payload, list_threads_payload};
+use codexia_codex::config::provider::resync_model_providers;
+use codexia_codex::config::settings;
+use codexia_codex::providers::{self, ProviderConfig};
+use codexia_codex::approval_audit;
+use codexia_codex::rate_limits::{self, RateLimitAlertSettings};
+use codexia_shared::event_sink::WebSocketEventSink;
+use codexia_codex::export::export_thread;
+use codexia_codex::search::search_threads;
+use codexia_db::codex_search::CodexSearchQuery;
 use codexia_codex::AppState;
+use codexia_cc::mcp_catalog::{self, McpCatalog, McpCatalogStatus, McpSyncResult};
 use codexia_cc::mcp_unified as mcp;
+use codexia_codex::config::mcp::McpServerConfig;
+use codexia_codex::mcp_health::{self, McpHealth};
 
 fn require_codex(state: &WebServerState) -> Result<&AppState, ErrorResponse> {
     state.codex_state.as_deref().ok_or_else(|| ErrorResponse {
@@ -95,6 +111,22 @@ pub(crate) async fn api_list_archived_threads(
     Ok(Json(result))
 }
 
+pub(crate) async fn api_search_threads(
+    AxumState(_state): AxumState<WebServerState>,
+    Json(query): Json<CodexSearchQuery>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let hits = search_threads(&query).map_err(to_error_response)?;
+    Ok(Json(json!(hits)))
+}
+
+pub(crate) async fn api_export_thread(
+    AxumState(_state): AxumState<WebServerState>,
+    Json(params): Json<ExportThreadParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let export = export_thread(&params.path, &params.options).map_err(to_error_response)?;
+    Ok(Json(json!(export)))
+}
+
 pub(crate) async fn api_archive_thread(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<ThreadArchiveParams>,
@@ -166,9 +198,35 @@ pub(crate) async fn api_account_rate_limits(
         .send_request("account/rateLimits/read", Value::Null)
         .await
         .map_err(to_error_response)?;
+    let event_sink = WebSocketEventSink::new(state.event_tx.clone());
+    rate_limits::record_read_response(&event_sink, &result);
     Ok(Json(result))
 }
 
+pub(crate) async fn api_rate_limit_history(
+    Json(params): Json<RateLimitHistoryParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let samples = rate_limits::rate_limit_history(
+        params.limit_id.as_deref(),
+        params.since,
+        params.limit.unwrap_or(0),
+    )
+    .map_err(to_error_response)?;
+    Ok(Json(json!(samples)))
+}
+
+pub(crate) async fn api_read_rate_limit_alerts() -> Result<Json<Value>, ErrorResponse> {
+    let settings = rate_limits::read_alert_settings().map_err(to_error_response)?;
+    Ok(Json(json!(settings)))
+}
+
+pub(crate) async fn api_write_rate_limit_alerts(
+    Json(settings): Json<RateLimitAlertSettings>,
+) -> Result<StatusCode, ErrorResponse> {
+    rate_limits::write_alert_settings(settings).map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
 pub(crate) async fn api_get_account(
     AxumState(state): AxumState<WebServerState>,
     Json(params): Json<GetAccountParams>,
@@ -231,12 +289,15 @@ pub(crate) async fn api_respond_command_execution_approval(
         },
     )
     .map_err(to_error_response)?;
+    let decision = result_value["decision"].clone();
 
     require_codex(&state)?
         .codex
-        .send_response(params.request_id, result_value)
+        .send_response(params.request_id.clone(), result_value)
         .await
         .map_err(to_error_response)?;
+    // Only a delivered answer is a decision.
+    approval_audit::record_decision(&params.request_id, &decision);
 
     Ok(StatusCode::OK)
 }
@@ -251,12 +312,15 @@ pub(crate) async fn api_respond_file_change_approval(
         },
     )
     .map_err(to_error_response)?;
+    let decision = result_value["decision"].clone();
 
     require_codex(&state)?
         .codex
-        .send_response(params.request_id, result_value)
+        .send_response(params.request_id.clone(), result_value)
         .await
         .map_err(to_error_response)?;
+    // Only a delivered answer is a decision.
+    approval_audit::record_decision(&params.request_id, &decision);
 
     Ok(StatusCode::OK)
 }
@@ -343,3 +407,176 @@ pub(crate) async fn api_unified_read_mcp_config(
         .map_err(to_error_response)?;
     Ok(Json(result))
 }
+
+pub(crate) async fn api_mcp_catalog_read() -> Result<Json<McpCatalog>, ErrorResponse> {
+    let catalog = mcp_catalog::read_catalog().map_err(to_error_response)?;
+    Ok(Json(catalog))
+}
+
+pub(crate) async fn api_mcp_catalog_write(
+    Json(params): Json<McpCatalogWriteParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    mcp_catalog::write_catalog(&params.catalog).map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_mcp_catalog_status(
+    Json(params): Json<McpCatalogStatusParams>,
+) -> Result<Json<McpCatalogStatus>, ErrorResponse> {
+    let status = mcp_catalog::catalog_status(params.path.as_deref())
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(status))
+}
+
+pub(crate) async fn api_mcp_catalog_sync(
+    Json(params): Json<McpCatalogSyncParams>,
+) -> Result<Json<McpSyncResult>, ErrorResponse> {
+    let result = mcp_catalog::sync_catalog(params.request)
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_check_mcp_servers(
+    Json(params): Json<McpHealthParams>,
+) -> Result<Json<Vec<McpHealth>>, ErrorResponse> {
+    let result = mcp_health::check_mcp_servers(
+        params.names,
+        params.refresh.unwrap_or(false),
+        params.timeout_secs,
+    )
+    .await
+    .map_err(to_error_response)?;
+    Ok(Json(result))
+}
+
+pub(crate) async fn api_probe_mcp_server(
+    Json(params): Json<McpProbeParams>,
+) -> Result<Json<McpHealth>, ErrorResponse> {
+    let config: McpServerConfig = serde_json::from_value(params.server_config)
+        .map_err(|e| to_error_response(format!("Failed to parse MCP server config: {}", e)))?;
+    let health = mcp_health::probe_mcp_server(&params.server_name, &config, params.timeout_secs).await;
+    Ok(Json(health))
+}
+
+pub(crate) async fn api_read_codex_settings() -> Result<Json<Value>, ErrorResponse> {
+    let result = settings::read_codex_settings()
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(json!(result)))
+}
+
+pub(crate) async fn api_write_codex_settings(
+    Json(params): Json<CodexSettingsWriteParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    settings::write_codex_settings(params.settings)
+        .await
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_list_codex_profiles() -> Result<Json<Value>, ErrorResponse> {
+    let result = settings::list_profiles().await.map_err(to_error_response)?;
+    Ok(Json(json!(result)))
+}
+
+pub(crate) async fn api_create_codex_profile(
+    Json(params): Json<CodexProfileParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    settings::create_profile(params.name, params.profile)
+        .await
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_update_codex_profile(
+    Json(params): Json<CodexProfileParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    settings::update_profile(params.name, params.profile)
+        .await
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_clone_codex_profile(
+    Json(params): Json<CodexProfileCloneParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    settings::clone_profile(params.source, params.target)
+        .await
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_delete_codex_profile(
+    Json(params): Json<CodexProfileNameParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    settings::delete_profile(params.name)
+        .await
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_set_active_codex_profile(
+    Json(params): Json<CodexActiveProfileParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    settings::set_active_profile(params.name)
+        .await
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_read_codex_project_trust() -> Result<Json<Value>, ErrorResponse> {
+    let result = settings::read_project_trust()
+        .await
+        .map_err(to_error_response)?;
+    Ok(Json(json!(result)))
+}
+
+pub(crate) async fn api_set_codex_project_trust(
+    Json(params): Json<CodexProjectTrustParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    settings::set_project_trust(params.path, params.trust_level)
+        .await
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_list_model_providers() -> Result<Json<Value>, ErrorResponse> {
+    let entries = providers::list_providers().map_err(to_error_response)?;
+    Ok(Json(json!(entries)))
+}
+
+pub(crate) async fn api_upsert_model_provider(
+    AxumState(state): AxumState<WebServerState>,
+    Json(provider): Json<ProviderConfig>,
+) -> Result<StatusCode, ErrorResponse> {
+    providers::upsert_user_provider(provider).map_err(to_error_response)?;
+    let codex = state.codex_state.as_deref().map(|app| app.codex.as_ref());
+    resync_model_providers(codex, None)
+        .await
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_delete_model_provider(
+    AxumState(state): AxumState<WebServerState>,
+    Json(params): Json<DeleteModelProviderParams>,
+) -> Result<StatusCode, ErrorResponse> {
+    providers::delete_user_provider(&params.model_provider).map_err(to_error_response)?;
+    let codex = state.codex_state.as_deref().map(|app| app.codex.as_ref());
+    resync_model_providers(codex, Some(&params.model_provider))
+        .await
+        .map_err(to_error_response)?;
+    Ok(StatusCode::OK)
+}
+
+pub(crate) async fn api_test_model_provider(
+    Json(params): Json<TestModelProviderParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let result =
+        providers::test_provider(&params.model_provider, params.model, params.timeout_secs)
+            .await
+            .map_err(to_error_response)?;
+    Ok(Json(json!(result)))
+}
@@ -1,11 +1,18 @@
-use axum::{Json, extract::State as AxumState, http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    extract::{Query, State as AxumState},
+    http::StatusCode,
+    response::IntoResponse,
+};
 use serde::{Deserialize};
 use serde_json::{json, Value};
 use codexia_codex::env::set_env;
-use codexia_codex::providers::{load_env_keys, load_and_fetch_models};
+use codexia_codex::secrets;
+use codexia_codex::providers::{clear_models_cache, fetch_models, load_env_keys};
 
 use super::types::{ErrorResponse, WebServerState};
 
+mod audit;
 mod cc;
 mod codex;
 mod dxt;
@@ -20,6 +27,7 @@ mod skillssh;
 mod terminal;
 mod types;
 
+pub(super) use audit::*;
 pub(super) use cc::*;
 pub(super) use codex::*;
 pub(super) use dxt::*;
@@ -76,8 +84,20 @@ pub struct SetEnvPayload {
     value: String,
 }
 
-pub(super) async fn api_model_list_other() -> Result<Json<Value>, ErrorResponse> {
-    match load_and_fetch_models().await {
+#[derive(Deserialize)]
+pub struct DeleteSecretPayload {
+    name: String,
+}
+
+#[derive(Deserialize)]
+pub struct ModelListOtherQuery {
+    refresh: Option<bool>,
+}
+
+pub(super) async fn api_model_list_other(
+    Query(params): Query<ModelListOtherQuery>,
+) -> Result<Json<Value>, ErrorResponse> {
+    match fetch_models(params.refresh.unwrap_or(false)).await {
         Ok(models) => Ok(Json(json!(models))),
         Err(e) => Err(ErrorResponse { error: e }),
     }
@@ -95,7 +115,29 @@ pub(super) async fn api_set_env(
     Json(payload): Json<SetEnvPayload>,
 ) -> Result<StatusCode, ErrorResponse> {
     // Delegate to the same implementation used by the Tauri command
-    set_env(payload.key, payload.value)
+    tokio::task::spawn_blocking(move || set_env(payload.key, payload.value))
+        .await
+        .map_err(|e| ErrorResponse { error: e.to_string() })?
+        .map_err(|e| ErrorResponse { error: e })?;
+    clear_models_cache();
+    Ok(StatusCode::OK)
+}
+
+pub(super) async fn api_list_secrets() -> Result<Json<Value>, ErrorResponse> {
+    match tokio::task::spawn_blocking(secrets::list_secrets).await {
+        Ok(Ok(items)) => Ok(Json(json!(items))),
+        Ok(Err(e)) => Err(ErrorResponse { error: e }),
+        Err(e) => Err(ErrorResponse { error: e.to_string() }),
+    }
+}
+
+pub(super) async fn api_delete_secret(
+    Json(payload): Json<DeleteSecretPayload>,
+) -> Result<StatusCode, ErrorResponse> {
+    tokio::task::spawn_blocking(move || secrets::delete_secret(&payload.name))
+        .await
+        .map_err(|e| ErrorResponse { error: e.to_string() })?
         .map_err(|e| ErrorResponse { error: e })?;
+    clear_models_cache();
     Ok(StatusCode::OK)
 }
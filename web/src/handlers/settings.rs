@@ -1,8 +1,11 @@
 use axum::{Json, http::StatusCode};
-use serde_json::Value;
+use codexia_shared::config_backup;
+use serde_json::{Value, json};
+use std::path::Path;
 
 use crate::types::ErrorResponse;
 use super::to_error_response;
+use super::types::{ConfigBackupIdParams, ConfigBackupListParams};
 
 fn settings_path() -> Result<std::path::PathBuf, String> {
     let home = dirs::home_dir().ok_or("Could not find home directory")?;
@@ -35,3 +38,25 @@ pub(crate) async fn api_save_settings_file(
         .map_err(|e| to_error_response(e.to_string()))?;
     Ok(StatusCode::OK)
 }
+
+pub(crate) async fn api_list_config_backups(
+    Json(params): Json<ConfigBackupListParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let backups = config_backup::list_backups(params.path.as_deref().map(Path::new))
+        .map_err(to_error_response)?;
+    Ok(Json(json!(backups)))
+}
+
+pub(crate) async fn api_diff_config_backup(
+    Json(params): Json<ConfigBackupIdParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let diff = config_backup::diff_backup(&params.id).map_err(to_error_response)?;
+    Ok(Json(json!(diff)))
+}
+
+pub(crate) async fn api_restore_config_backup(
+    Json(params): Json<ConfigBackupIdParams>,
+) -> Result<Json<Value>, ErrorResponse> {
+    let backup = config_backup::restore_backup(&params.id).map_err(to_error_response)?;
+    Ok(Json(json!(backup)))
+}
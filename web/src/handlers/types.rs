@@ -6,8 +6,13 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use codexia_cc::mcp::ClaudeCodeMcpServer;
-use codexia_cc::types::AgentOptions;
+use codexia_cc::types::{AgentOptions, PermissionTimeout};
+use codexia_cc::services::permission_rules::RuleKind;
+use codexia_cc::services::sensitive_paths::SensitivePathPolicy;
+use codexia_cc::services::session_lifecycle::SessionLimits;
 use codexia_cc::automation::AutomationSchedule;
+use codexia_codex::config::settings::{CodexSettings, ConfigProfile, TrustLevel};
+use codexia_codex::export::ExportOptions;
 
 #[derive(Deserialize)]
 pub(crate) struct ListThreadsRequest {
@@ -16,6 +21,13 @@ pub(crate) struct ListThreadsRequest {
     pub(crate) cwd: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub(crate) struct ExportThreadParams {
+    pub(crate) path: String,
+    #[serde(default)]
+    pub(crate) options: ExportOptions,
+}
+
 #[derive(Deserialize)]
 pub(crate) struct CommandExecutionApprovalParams {
     pub(crate) request_id: RequestId,
@@ -194,12 +206,116 @@ pub(crate) struct UnifiedMcpReadParams {
     pub(crate) path: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub(crate) struct McpHealthParams {
+    #[serde(default)]
+    pub(crate) names: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) refresh: Option<bool>,
+    #[serde(default, rename = "timeout_secs", alias = "timeoutSecs")]
+    pub(crate) timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct McpProbeParams {
+    #[serde(rename = "server_name", alias = "serverName")]
+    pub(crate) server_name: String,
+    #[serde(rename = "server_config", alias = "serverConfig")]
+    pub(crate) server_config: Value,
+    #[serde(default, rename = "timeout_secs", alias = "timeoutSecs")]
+    pub(crate) timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct McpCatalogWriteParams {
+    pub(crate) catalog: codexia_cc::mcp_catalog::McpCatalog,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct McpCatalogStatusParams {
+    pub(crate) path: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct McpCatalogSyncParams {
+    pub(crate) request: codexia_cc::mcp_catalog::McpSyncRequest,
+}
+
 #[derive(Deserialize)]
 pub(crate) struct CcSessionIdParams {
     #[serde(rename = "session_id", alias = "sessionId")]
     pub(crate) session_id: String,
 }
 
+#[derive(Deserialize)]
+pub(crate) struct CcSetSessionTitleParams {
+    #[serde(rename = "session_id", alias = "sessionId")]
+    pub(crate) session_id: String,
+    pub(crate) title: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcSetSessionTagsParams {
+    #[serde(rename = "session_id", alias = "sessionId")]
+    pub(crate) session_id: String,
+    pub(crate) tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcForkSessionParams {
+    #[serde(rename = "session_id", alias = "sessionId")]
+    pub(crate) session_id: String,
+    #[serde(rename = "message_uuid", alias = "messageUuid")]
+    pub(crate) message_uuid: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcRewindSessionParams {
+    #[serde(rename = "session_id", alias = "sessionId")]
+    pub(crate) session_id: String,
+    #[serde(rename = "message_uuid", alias = "messageUuid")]
+    pub(crate) message_uuid: String,
+    #[serde(default, rename = "revert_files", alias = "revertFiles")]
+    pub(crate) revert_files: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcExportSessionParams {
+    #[serde(rename = "session_id", alias = "sessionId")]
+    pub(crate) session_id: String,
+    #[serde(default)]
+    pub(crate) options: ExportOptions,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcPermissionRulesParams {
+    pub(crate) cwd: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcPermissionRuleParams {
+    pub(crate) cwd: String,
+    pub(crate) kind: RuleKind,
+    pub(crate) rule: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcSensitivePathPolicyParams {
+    pub(crate) policy: SensitivePathPolicy,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcSessionLimitsParams {
+    pub(crate) limits: SessionLimits,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcImportSessionParams {
+    pub(crate) content: String,
+    #[serde(default)]
+    pub(crate) cwd: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub(crate) struct CcGetSessionsParams {
     #[serde(default)]
@@ -219,6 +335,25 @@ pub(crate) struct CcSendMessageParams {
     pub(crate) message: String,
     #[serde(default)]
     pub(crate) image_paths: Vec<String>,
+    /// Interrupt the running exchange and send this message next.
+    #[serde(default)]
+    pub(crate) steer: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcQueuedMessageParams {
+    #[serde(rename = "session_id", alias = "sessionId")]
+    pub(crate) session_id: String,
+    #[serde(rename = "message_id", alias = "messageId")]
+    pub(crate) message_id: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcReorderQueueParams {
+    #[serde(rename = "session_id", alias = "sessionId")]
+    pub(crate) session_id: String,
+    #[serde(rename = "message_ids", alias = "messageIds")]
+    pub(crate) message_ids: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -252,6 +387,19 @@ pub(crate) struct CcSetPermissionModeParams {
     pub(crate) mode: String,
 }
 
+#[derive(Deserialize)]
+pub(crate) struct CcSetPermissionTimeoutParams {
+    #[serde(rename = "session_id", alias = "sessionId")]
+    pub(crate) session_id: String,
+    pub(crate) timeout: PermissionTimeout,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CcPendingPermissionsParams {
+    #[serde(default, rename = "session_id", alias = "sessionId")]
+    pub(crate) session_id: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub(crate) struct CcMcpListParams {
     #[serde(rename = "working_dir", alias = "workingDir")]
@@ -361,3 +509,76 @@ pub(crate) struct InsightFiltersParams {
     #[serde(default)]
     pub(crate) agent: Option<String>,
 }
+
+#[derive(Deserialize)]
+pub(crate) struct CodexSettingsWriteParams {
+    pub(crate) settings: CodexSettings,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CodexProfileParams {
+    pub(crate) name: String,
+    pub(crate) profile: ConfigProfile,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CodexProfileCloneParams {
+    pub(crate) source: String,
+    pub(crate) target: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CodexProfileNameParams {
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CodexActiveProfileParams {
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CodexProjectTrustParams {
+    pub(crate) path: String,
+    #[serde(default, rename = "trust_level", alias = "trustLevel")]
+    pub(crate) trust_level: Option<TrustLevel>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ConfigBackupListParams {
+    #[serde(default)]
+    pub(crate) path: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ConfigBackupIdParams {
+    pub(crate) id: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct DeleteModelProviderParams {
+    #[serde(rename = "model_provider", alias = "modelProvider")]
+    pub(crate) model_provider: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RateLimitHistoryParams {
+    #[serde(default, rename = "limit_id", alias = "limitId")]
+    pub(crate) limit_id: Option<String>,
+    /// Unix milliseconds.
+    #[serde(default)]
+    pub(crate) since: Option<i64>,
+    #[serde(default)]
+    pub(crate) limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TestModelProviderParams {
+    #[serde(rename = "model_provider", alias = "modelProvider")]
+    pub(crate) model_provider: String,
+    #[serde(default)]
+    pub(crate) model: Option<String>,
+    #[serde(default, rename = "timeout_secs", alias = "timeoutSecs")]
+    pub(crate) timeout_secs: Option<u64>,
+}
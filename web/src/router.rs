@@ -13,10 +13,12 @@ use super::{
         api_cc_list_projects, api_cc_mcp_add, api_cc_mcp_disable, api_cc_mcp_enable,
         api_cc_mcp_get, api_cc_mcp_list, api_cc_mcp_remove,
         api_cc_connect, api_cc_disconnect, api_cc_get_installed_skills,
-        api_cc_delete_session, api_cc_get_session_messages, api_cc_list_sessions, api_cc_get_settings, api_cc_get_slash_commands,
-        api_cc_interrupt,
-        api_cc_new_session, api_cc_resolve_permission, api_cc_resume_session,
-        api_cc_send_message, api_cc_set_permission_mode, api_cc_update_settings,
+        api_cc_delete_session, api_cc_search_sessions, api_cc_set_session_title, api_cc_set_session_tags, api_cc_fork_session, api_cc_rewind_session, api_cc_export_session, api_cc_get_session_messages, api_cc_import_session,
+        api_cc_add_permission_rule, api_cc_get_permission_rules, api_cc_remove_permission_rule,
+        api_cc_get_sensitive_path_policy, api_cc_set_sensitive_path_policy, api_cc_list_sessions, api_cc_get_settings, api_cc_get_slash_commands,
+        api_cc_interrupt, api_cc_session_usage, api_cc_status, api_cc_get_session_limits, api_cc_set_session_limits, api_cc_list_queue, api_cc_cancel_queued, api_cc_reorder_queue,
+        api_cc_new_session, api_cc_pending_permissions, api_cc_resolve_permission, api_cc_resume_session,
+        api_cc_send_message, api_cc_set_permission_mode, api_cc_set_permission_timeout, api_cc_update_settings,
         api_create_automation, api_delete_automation, api_list_automation_runs, api_list_automations, api_run_automation_now, api_set_automation_paused,
         api_update_automation,
         api_check_manifests_exist, api_codex_home, api_create_note, api_delete_file,
@@ -37,7 +39,7 @@ use super::{
         api_read_pdf_content, api_read_text_file_lines,
         api_read_xlsx_content, api_respond_command_execution_approval,
         api_respond_file_change_approval, api_respond_user_input, api_resume_thread,
-        api_rollback_thread, api_fork_thread,
+        api_rollback_thread, api_fork_thread, api_search_threads, api_export_thread,
         api_save_dxt_setting,
         api_search_files, api_search_files_by_name, api_skills_config_write, api_skills_list, api_start_review,
         api_start_thread, api_watch_directory, api_unwatch_directory,
@@ -51,7 +53,17 @@ use super::{
         api_unified_add_mcp_server, api_unified_disable_mcp_server,
         api_unified_enable_mcp_server, api_unified_read_mcp_config,
         api_unified_remove_mcp_server, api_update_note, api_write_file,
+        api_mcp_catalog_read, api_mcp_catalog_write, api_mcp_catalog_status, api_mcp_catalog_sync,
+        api_check_mcp_servers, api_probe_mcp_server,
+        api_read_codex_settings, api_write_codex_settings, api_list_codex_profiles,
+        api_create_codex_profile, api_update_codex_profile, api_clone_codex_profile,
+        api_delete_codex_profile, api_set_active_codex_profile, api_read_codex_project_trust,
+        api_set_codex_project_trust, api_list_config_backups, api_diff_config_backup,
+        api_restore_config_backup, api_list_model_providers, api_upsert_model_provider,
+        api_delete_model_provider, api_test_model_provider, api_rate_limit_history,
+        api_read_rate_limit_alerts, api_write_rate_limit_alerts,
         api_get_settings_file, api_save_settings_file, health_check, api_model_list_other, api_load_env_keys, api_set_env,
+        api_list_secrets, api_delete_secret, api_query_approval_audit, api_export_approval_audit_csv,
     },
     types::WebServerState,
     websocket::{sse_handler, ws_handler},
@@ -111,6 +123,8 @@ pub fn create_router(state: WebServerState) -> Router {
             "/api/codex/thread/list-archived",
             post(api_list_archived_threads),
         )
+        .route("/api/codex/thread/search", post(api_search_threads))
+        .route("/api/codex/thread/export", post(api_export_thread))
         .route("/api/codex/thread/archive", post(api_archive_thread))
         .route("/api/codex/turn/start", post(api_turn_start))
         .route("/api/codex/turn/interrupt", post(api_turn_interrupt))
@@ -120,11 +134,29 @@ pub fn create_router(state: WebServerState) -> Router {
         )
         .route("/api/codex/model/list-other", get(api_model_list_other))
         .route("/api/codex/load_env_keys", get(api_load_env_keys))
+        .route("/api/codex/providers/list", post(api_list_model_providers))
+        .route("/api/codex/providers/upsert", post(api_upsert_model_provider))
+        .route("/api/codex/providers/delete", post(api_delete_model_provider))
+        .route("/api/codex/providers/test", post(api_test_model_provider))
         .route("/api/codex/set_env", post(api_set_env))
+        .route("/api/codex/secrets/list", post(api_list_secrets))
+        .route("/api/codex/secrets/delete", post(api_delete_secret))
         .route(
             "/api/codex/account/rate-limits",
             get(api_account_rate_limits),
         )
+        .route(
+            "/api/codex/account/rate-limits/history",
+            post(api_rate_limit_history),
+        )
+        .route(
+            "/api/codex/account/rate-limits/alerts/read",
+            post(api_read_rate_limit_alerts),
+        )
+        .route(
+            "/api/codex/account/rate-limits/alerts/write",
+            post(api_write_rate_limit_alerts),
+        )
         .route("/api/codex/account/get", post(api_get_account))
         .route("/api/codex/account/login", post(api_login_account))
         .route("/api/codex/skills/list", post(api_skills_list))
@@ -202,6 +234,22 @@ pub fn create_router(state: WebServerState) -> Router {
         .route("/api/codex/mcp/remove", post(api_unified_remove_mcp_server))
         .route("/api/codex/mcp/enable", post(api_unified_enable_mcp_server))
         .route("/api/codex/mcp/disable", post(api_unified_disable_mcp_server))
+        .route("/api/codex/mcp/catalog", get(api_mcp_catalog_read))
+        .route("/api/codex/mcp/catalog/write", post(api_mcp_catalog_write))
+        .route("/api/codex/mcp/catalog/status", post(api_mcp_catalog_status))
+        .route("/api/codex/mcp/catalog/sync", post(api_mcp_catalog_sync))
+        .route("/api/codex/mcp/health", post(api_check_mcp_servers))
+        .route("/api/codex/mcp/probe", post(api_probe_mcp_server))
+        .route("/api/codex/config/settings/read", post(api_read_codex_settings))
+        .route("/api/codex/config/settings/write", post(api_write_codex_settings))
+        .route("/api/codex/config/profiles/list", post(api_list_codex_profiles))
+        .route("/api/codex/config/profiles/create", post(api_create_codex_profile))
+        .route("/api/codex/config/profiles/update", post(api_update_codex_profile))
+        .route("/api/codex/config/profiles/clone", post(api_clone_codex_profile))
+        .route("/api/codex/config/profiles/delete", post(api_delete_codex_profile))
+        .route("/api/codex/config/profiles/activate", post(api_set_active_codex_profile))
+        .route("/api/codex/config/projects/trust/read", post(api_read_codex_project_trust))
+        .route("/api/codex/config/projects/trust/write", post(api_set_codex_project_trust))
         .route("/api/dxt/manifests", get(api_load_manifests))
         .route("/api/dxt/manifest", post(api_load_manifest))
         .route("/api/dxt/manifests/exist", get(api_check_manifests_exist))
@@ -235,17 +283,42 @@ pub fn create_router(state: WebServerState) -> Router {
         .route("/api/git/push", post(api_git_push))
         .route("/api/cc/connect", post(api_cc_connect))
         .route("/api/cc/send-message", post(api_cc_send_message))
+        .route("/api/cc/queue", post(api_cc_list_queue))
+        .route("/api/cc/queue/cancel", post(api_cc_cancel_queued))
+        .route("/api/cc/queue/reorder", post(api_cc_reorder_queue))
         .route("/api/cc/disconnect", post(api_cc_disconnect))
         .route("/api/cc/new-session", post(api_cc_new_session))
         .route("/api/cc/interrupt", post(api_cc_interrupt))
+        .route("/api/cc/session-usage", post(api_cc_session_usage))
+        .route("/api/cc/status", get(api_cc_status))
+        .route(
+            "/api/cc/session-limits",
+            get(api_cc_get_session_limits).post(api_cc_set_session_limits),
+        )
         .route("/api/cc/resume-session", post(api_cc_resume_session))
         .route("/api/cc/resolve-permission", post(api_cc_resolve_permission))
+        .route("/api/cc/pending-permissions", post(api_cc_pending_permissions))
         .route("/api/cc/set-permission-mode", post(api_cc_set_permission_mode))
+        .route("/api/cc/set-permission-timeout", post(api_cc_set_permission_timeout))
         .route("/api/cc/installed-skills", get(api_cc_get_installed_skills))
         .route("/api/cc/slash-commands", get(api_cc_get_slash_commands))
         .route("/api/cc/settings", get(api_cc_get_settings).post(api_cc_update_settings))
         .route("/api/cc/sessions", get(api_cc_list_sessions))
+        .route("/api/cc/sessions/search", post(api_cc_search_sessions))
+        .route("/api/cc/session/title", post(api_cc_set_session_title))
+        .route("/api/cc/session/tags", post(api_cc_set_session_tags))
+        .route("/api/cc/session/fork", post(api_cc_fork_session))
+        .route("/api/cc/session/rewind", post(api_cc_rewind_session))
         .route("/api/cc/session-messages", post(api_cc_get_session_messages))
+        .route("/api/cc/session/export", post(api_cc_export_session))
+        .route("/api/cc/session/import", post(api_cc_import_session))
+        .route("/api/cc/permission-rules", post(api_cc_get_permission_rules))
+        .route("/api/cc/permission-rules/add", post(api_cc_add_permission_rule))
+        .route("/api/cc/permission-rules/remove", post(api_cc_remove_permission_rule))
+        .route(
+            "/api/cc/sensitive-paths",
+            get(api_cc_get_sensitive_path_policy).post(api_cc_set_sensitive_path_policy),
+        )
         .route("/api/cc/delete-session", post(api_cc_delete_session))
         .route("/api/cc/mcp/list", post(api_cc_mcp_list))
         .route("/api/cc/mcp/get", post(api_cc_mcp_get))
@@ -257,9 +330,14 @@ pub fn create_router(state: WebServerState) -> Router {
         .route("/api/insights/heatmaps", post(api_get_agent_heatmaps))
         .route("/api/insights/rankings", post(api_get_insight_rankings))
         .route("/api/insights/filter-options", post(api_get_insight_filter_options))
+        .route("/api/audit/approvals", post(api_query_approval_audit))
+        .route("/api/audit/approvals/csv", post(api_export_approval_audit_csv))
         .route("/api/sleep/prevent", post(api_prevent_sleep))
         .route("/api/sleep/allow", post(api_allow_sleep))
         .route("/api/settings", get(api_get_settings_file).post(api_save_settings_file))
+        .route("/api/config/backups/list", post(api_list_config_backups))
+        .route("/api/config/backups/diff", post(api_diff_config_backup))
+        .route("/api/config/backups/restore", post(api_restore_config_backup))
         .fallback_service(static_site)
         .layer(
             CorsLayer::new()
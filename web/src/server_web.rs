@@ -8,6 +8,7 @@ use tokio::sync::broadcast;
 use super::{router::create_router, types::WebServerState};
 use codexia_cc::CCState;
 use codexia_codex::scan::start_history_scanner;
+use codexia_codex::config::provider::write_model_providers;
 use codexia_codex::{AppState, CodexInitializationState, connect_codex, initialize_codex};
 use codexia_shared::event_sink::{EventSink, WebSocketEventSink};
 use codexia_shared::sleep::SleepState;
@@ -37,8 +38,8 @@ pub async fn start_web_server_with_events(
     .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
 
     let history_sink: Arc<dyn EventSink> = Arc::new(WebSocketEventSink::new(event_tx.clone()));
+    codexia_cc::scan::start_session_scanner(Arc::clone(&history_sink));
     start_history_scanner(history_sink);
-    codexia_cc::scan::start_session_scanner();
 
     let state = WebServerState {
         codex_state,
@@ -91,6 +92,13 @@ pub async fn start_web_server(host: &str, port: u16) -> Result<(), Box<dyn std::
                 }
             }
 
+            let providers_client = Arc::clone(&codex);
+            tokio::spawn(async move {
+                if let Err(e) = write_model_providers(&providers_client).await {
+                    log::error!("[web] failed to write model provider configs: {}", e);
+                }
+            });
+
             Some(Arc::new(AppState { codex }))
         }
         Err(e) => {
@@ -103,6 +111,7 @@ pub async fn start_web_server(host: &str, port: u16) -> Result<(), Box<dyn std::
     };
 
     let cc_state = Arc::new(CCState::new(Arc::new(WebSocketEventSink::new(event_tx.clone()))));
+    tokio::spawn(codexia_cc::services::session_lifecycle::run_session_reaper((*cc_state).clone()));
     log::info!("[web] boot completed in {:?}", boot_started_at.elapsed());
     start_web_server_with_events(codex_state, cc_state, event_tx, host, port).await
 }